@@ -0,0 +1,415 @@
+app-title = Money counter
+interface-language = Interface language:
+words-language = Amount in words:
+
+budget = Budget:
+price = Price:
+quantity = Quantity:
+scale-short = Short scale
+scale-long = Long scale
+precision = Decimal places:
+rounding = Rounding:
+rounding-half-up = Half up
+rounding-half-even = Banker's
+rounding-down = Down
+rounding-up = Up
+
+custom-measure = Custom unit of measure
+measure-short = Abbreviation:
+measure-one = One:
+measure-few = Two:
+measure-many = Five:
+gender-masculine = Masculine
+gender-feminine = Feminine
+add = Add
+
+measure-item = u.
+measure-item-name = unit
+measure-item-count = { $count ->
+    [one] unit
+   *[other] units
+}
+measure-piece = pcs
+measure-piece-name = piece
+measure-piece-count = { $count ->
+    [one] piece
+   *[other] pieces
+}
+measure-gram = g
+measure-gram-name = gram
+measure-gram-count = { $count ->
+    [one] gram
+   *[other] grams
+}
+measure-kilogram = kg
+measure-kilogram-name = kilogram
+measure-kilogram-count = { $count ->
+    [one] kilogram
+   *[other] kilograms
+}
+measure-tonne = t
+measure-tonne-name = tonne
+measure-tonne-count = { $count ->
+    [one] tonne
+   *[other] tonnes
+}
+measure-millilitre = ml
+measure-millilitre-name = millilitre
+measure-millilitre-count = { $count ->
+    [one] millilitre
+   *[other] millilitres
+}
+measure-litre = l
+measure-litre-name = litre
+measure-litre-count = { $count ->
+    [one] litre
+   *[other] litres
+}
+measure-centimetre = cm
+measure-centimetre-name = centimetre
+measure-centimetre-count = { $count ->
+    [one] centimetre
+   *[other] centimetres
+}
+measure-metre = m
+measure-metre-name = metre
+measure-metre-count = { $count ->
+    [one] metre
+   *[other] metres
+}
+measure-pack = pk
+measure-pack-name = pack
+measure-pack-count = { $count ->
+    [one] pack
+   *[other] packs
+}
+measure-box = box
+measure-box-name = box
+measure-box-count = { $count ->
+    [one] box
+   *[other] boxes
+}
+measure-hour = h
+measure-hour-name = hour
+measure-hour-count = { $count ->
+    [one] hour
+   *[other] hours
+}
+
+vat-rate = Rate:
+vat-percent = VAT { $percent }%
+vat-none = VAT exempt
+vat-included = including VAT { $percent }% — { $amount }
+vat-added = plus VAT { $percent }% — { $amount }
+vat-custom = Other
+vat-extract = VAT included
+vat-add = VAT on top
+
+total = Total: { $total } for { $quantity }, { $vat }.
+leftover-positive = Left over: { $amount }
+leftover-negative = Short by: { $amount }
+leftover-accounting = Balance: { $amount }
+
+receipt = Receipt QR code
+receipt-image = Receipt photo (PNG, JPEG):
+receipt-recognize = Recognize
+receipt-recognized = QR code recognized, the sum { $sum } is put into the price
+receipt-recognizing = Recognizing…
+receipt-qr = QR string:
+receipt-header = { $operation } on { $time }, { $sum }
+receipt-to-price = Use as price
+receipt-to-budget = Use as budget
+receipt-to-expenses = Add to category expenses
+operation-income = Sale
+operation-income-return = Sale return
+operation-expense = Purchase by the shop
+operation-expense-return = Purchase return
+
+comparison = Offer comparison
+offer = Offer
+offer-numbered = Offer { $number }
+offer-for = For:
+offer-pack-size = Pieces per pack:
+offer-box-size = Pieces per box:
+offer-remove = Remove
+offer-add = Add offer
+offer-price-per = Price per { $measure }
+offer-packs = Packs within budget
+offer-leftover = Left over
+offer-cheapest = { $name } (cheapest)
+
+mode-purchase = Purchase
+mode-split = Split the bill
+remove = Remove
+
+split-equal = Equally
+split-weights = By weight
+split-items = By items
+split-total = Bill total:
+split-tip = Tip, %:
+split-participants = Participants
+split-participant = Participant
+split-participant-numbered = Participant { $number }
+split-weight = Weight:
+split-paid = Paid:
+split-add-participant = Add participant
+split-receipt = Receipt items
+split-item-numbered = Item { $number }
+split-add-item = Add item
+split-items-total = Items total: { $total }
+split-share = Share
+split-paid-column = Paid
+split-balance = Balance
+split-grand-total = Total with tip: { $total }
+split-settlement = Who owes whom
+split-transfer = { $from } → { $to }: { $amount }
+split-settled = Everyone is even
+split-enter-paid = Enter how much everyone paid
+history-summary-split = bill { $total } for { $count }
+
+mode-loan = Loan
+loan-principal = Loan amount:
+loan-rate = Rate, % per year:
+loan-months = Term, months:
+loan-annuity = Annuity
+loan-differentiated = Differentiated
+loan-early = Early repayment
+loan-early-month = Month:
+loan-early-amount = Amount:
+loan-reduce-term = Reduce term
+loan-reduce-payment = Reduce payment
+loan-add-early = Add early repayment
+loan-number = No.
+loan-payment = Payment
+loan-interest = Interest
+loan-principal-part = Principal
+loan-early-column = Early
+loan-balance = Balance
+loan-total = Total
+loan-total-paid = Total paid: { $amount }
+loan-total-interest = Interest paid: { $amount }
+history-summary-loan = loan { $principal } at { $rate }% for { $months } months
+
+mode-deposit = Savings
+deposit-initial = Initial deposit:
+deposit-top-up = Monthly top-up:
+deposit-rate = Rate, % per year:
+deposit-tax = Tax on interest, %:
+deposit-months = Term, months:
+deposit-capitalization = Capitalization:
+capitalization-daily = Daily
+capitalization-monthly = Monthly
+capitalization-quarterly = Quarterly
+deposit-target = Target (budget):
+deposit-target-reached = The target is reached in { $months } months, on { $date }
+deposit-target-unreachable = The target isn't reached within { $years } years
+deposit-total-balance = Balance at the end: { $amount }
+deposit-total-top-ups = Deposited: { $amount }
+deposit-total-interest = Interest earned: { $amount }
+deposit-total-tax = Tax withheld: { $amount }
+deposit-date = Date
+deposit-top-up-column = Top-up
+deposit-interest = Interest
+deposit-tax-column = Tax
+deposit-balance = Balance
+history-summary-deposit = savings { $initial } at { $rate }% for { $months } months
+
+mode-budget = Budget
+budget-categories = Categories
+budget-monthly = Per month:
+budget-add-category = Add category
+carry-over-none = No carry-over
+carry-over-full = Carry over surplus and overspending
+carry-over-surplus = Carry over surplus only
+budget-new-expense = New expense
+budget-date = Date:
+budget-category = Category:
+budget-amount = Amount:
+budget-note = Note:
+budget-category-column = Category
+budget-monthly-column = Budget
+budget-carried = Carried over
+budget-spent = Spent
+budget-remaining = Remaining
+budget-status = { $category }: { $status }
+budget-uncategorized = Spent outside categories: { $amount }
+budget-expenses = Expenses this month
+budget-field = (the Budget field)
+budget-record = Record as expense
+recurring = Recurring payments
+recurring-start = First payment:
+recurring-every = Every:
+frequency-daily = days
+frequency-weekly = weeks
+frequency-monthly = months
+frequency-yearly = years
+recurring-never = No end
+recurring-after = Payments:
+recurring-until = Until:
+recurring-add = Add recurring payment
+recurring-upcoming = Upcoming payments ({ $days } days)
+recurring-paid = Paid
+budget-planned = Planned
+budget-projected = Projected at month end
+budget-month-end = At month end, after recurring payments:
+history-summary-budget = budget for { $month }, { $count } expenses
+
+mode-charts = Charts
+bucket-day = By day
+bucket-week = By week
+bucket-month = By month
+chart-from = From:
+chart-to = To:
+chart-spending = Spending
+chart-categories = Spending by category
+chart-burn-down = Budget left in { $month }
+chart-remaining = Left
+chart-even = Even spending
+chart-empty = No spending in this period
+history-summary-charts = charts from { $from } to { $to }
+
+mode-payment = Payment order
+payment-number = Number:
+payment-date = Date:
+payment-amount = Amount:
+payment-words = Amount in words: { $words }
+payment-copy = Copy
+payment-payer = Payer
+payment-recipient = Recipient
+payment-name = Name:
+payment-inn = INN:
+payment-kpp = KPP:
+payment-account = Account:
+payment-bank = Bank:
+payment-bik = BIK:
+payment-correspondent = Corr. account:
+payment-purpose = Purpose of payment:
+payment-priority = Priority:
+payment-exchange = Save for the bank client (1C)
+payment-html = Save for printing (HTML)
+history-summary-payment = payment order No. { $number } for { $amount }
+
+mode-invoice = Invoice and act
+invoice-kind-invoice = Invoice
+invoice-kind-act = Act of acceptance
+invoice-buyer = Buyer (customer):
+invoice-basis = Basis:
+invoice-items = Items
+invoice-item-name = Name:
+invoice-item-quantity = Qty:
+invoice-item-unit = Unit:
+invoice-add-item = Add item
+invoice-prices-include-vat = Prices include VAT
+invoice-seller = Seller (contractor)
+invoice-address = Address:
+invoice-logo = Logo (PNG, JPEG):
+invoice-font = Font (TTF):
+invoice-director = Director:
+invoice-accountant = Accountant:
+invoice-terms = Payment terms:
+invoice-template-path = Template file:
+invoice-template-load = Load template
+invoice-template-save = Save template
+invoice-template-loaded = Template loaded from { $path }
+invoice-total = Total due: { $total }, VAT { $vat }
+invoice-pdf = Save PDF
+history-summary-invoice = { $kind } No. { $number } for { $amount }
+
+mode-ledger = Ledger
+ledger-path = Database file:
+ledger-new = New transaction
+ledger-amount = Amount (expenses with a minus):
+ledger-counterparty = Counterparty:
+ledger-added = Transactions recorded: { $count }
+ledger-from-budget = Record budget expenses for { $month }
+ledger-search = Search
+ledger-from = From:
+ledger-to = To:
+ledger-find = Find
+ledger-total = Found { $count } transactions, total { $amount } ({ $words })
+ledger-to-budget = Copy expenses to the budget
+ledger-amount-column = Amount
+ledger-currency = Currency:
+ledger-currency-column = Currency
+ledger-reference-column = Bank reference
+ledger-total-in = Found { $count } transactions, total { $amount } { $currency }
+ledger-counterparty-column = Counterparty
+ledger-note-column = Note
+ledger-statement = Statement (1C, OFX, QIF, camt.053):
+ledger-import = Import
+ledger-imported = Transactions recorded: { $count }, already in the ledger: { $duplicates }
+ledger-problem = Line { $line }, { $section }: { $details }
+history-summary-ledger = ledger { $path }
+
+cli-usage = Usage:
+    ledger <file> add <date> <amount> [category] [counterparty] [note]
+    ledger <file> list [--from <date>] [--to <date>] [--category <category>] [--counterparty <counterparty>] [--currency <currency>]
+    ledger <file> total [same filters as list]
+    ledger <file> receipt <receipt QR string or a PNG/JPEG photo of it> [category]
+    ledger <file> import <1CClientBankExchange, OFX, QIF or camt.053 statement> [currency, if the statement names none]
+    ledger <file> categories
+    ledger <file> remove <id>
+cli-missing-value = No value given for { $option }
+cli-unknown-option = Unknown option { $option }
+cli-added = Recorded with id { $id }
+cli-not-found = There is no transaction { $id }
+
+export-path = File:
+export-csv = Save CSV
+export-done = Saved to { $path }
+export-failed = Couldn't save { $path }: { $details }
+close = Close
+
+history = History
+undo = Undo
+redo = Redo
+history-summary = budget { $budget }, { $price } × { $quantity } { $measure }
+
+fix-kopeks = Round to kopecks
+fix-round = Round
+
+error-kopek-digits = Wrong number of kopecks, expected: [rubles].(1-99)
+error-quantity-digits = Too many decimal places, at most { $precision } allowed
+error-precision = Precision cannot exceed { $max } decimal places
+error-negative-quantity = Quantity can't be negative
+error-incompatible-units = Units of measure are not compatible
+error-pack-size-missing = Pieces per pack are not set
+error-division-by-zero = Division by zero
+error-no-offers = No offers to compare
+error-negative-amount = Amount can't be negative
+error-number-too-large = The number is too large to be written in words
+error-overflow = Overflow: the result is too large
+error-out-of-range = The number is out of the allowed range
+error-invalid-number = Enter a whole non-negative number
+error-no-participants = Nobody to pay: there are no participants
+error-unbalanced-payments = The amount paid doesn't match the bill
+error-zero-term = The term must be at least one month
+error-term-too-long = The term must be at most { $months } months
+error-invalid-date = Enter the date as DD.MM.YYYY
+error-storage = Database error: { $details }
+error-newer-schema = The database was created by a newer version of the program (schema { $version }), please update
+error-zero-interval = The repeat interval must be at least one
+error-missing-receipt-field = The receipt string has no { $field } field
+error-invalid-receipt-field = The receipt string has an invalid { $field } field
+error-no-qr-code = No receipt QR code found in the image
+error-image = Cannot open the image: { $details }
+error-file = Cannot read the file: { $details }
+error-unknown-statement-format = Unknown statement format, supported are 1CClientBankExchange, OFX, QIF and camt.053
+error-xml = Invalid XML: { $details }
+error-missing-statement-field = The statement has no field { $field }
+error-invalid-statement-field = Invalid field { $field } in the statement
+error-unclosed-section = A statement section is not closed
+error-unknown-direction = Cannot tell a payment from income: no account of the document matches the statement
+error-mixed-currencies = Amounts in different currencies cannot be added, choose a currency
+error-invalid-inn = An INN is 10 or 12 digits with valid check digits
+error-invalid-kpp = A KPP is 9 characters: four digits, two digits or letters, three digits
+error-invalid-bik = A BIK is 9 digits
+error-invalid-account = The account does not match the BIK: 20 digits with a valid check key are needed
+error-invalid-correspondent-account = The correspondent account does not match the BIK
+error-zero-amount = The amount must be greater than zero
+error-empty-purpose = The purpose of payment is empty
+error-no-items = The document has no items
+error-invalid-template-line = Template lines are written as Key=value
+error-font = Cannot load the TTF font: { $details }
+error-pdf = Cannot create the PDF: { $details }
+error-not-a-number = Not a number ({ $details })
@@ -0,0 +1,367 @@
+app-title = Счётчик денег
+interface-language = Язык интерфейса:
+words-language = Сумма прописью:
+
+budget = Бюджет:
+price = Цена:
+quantity = Количество:
+scale-short = Короткая шкала
+scale-long = Длинная шкала
+precision = Знаков после запятой:
+rounding = Округление:
+rounding-half-up = Математическое
+rounding-half-even = Банковское
+rounding-down = Вниз
+rounding-up = Вверх
+
+custom-measure = Своя единица измерения
+measure-short = Сокращение:
+measure-one = Одна:
+measure-few = Две:
+measure-many = Пять:
+gender-masculine = Мужской род
+gender-feminine = Женский род
+add = Добавить
+
+measure-item = ед.
+measure-item-name = единица
+measure-piece = шт
+measure-piece-name = штука
+measure-gram = г
+measure-gram-name = грамм
+measure-kilogram = кг
+measure-kilogram-name = килограмм
+measure-tonne = т
+measure-tonne-name = тонна
+measure-millilitre = мл
+measure-millilitre-name = миллилитр
+measure-litre = л
+measure-litre-name = литр
+measure-centimetre = см
+measure-centimetre-name = сантиметр
+measure-metre = м
+measure-metre-name = метр
+measure-pack = уп
+measure-pack-name = упаковка
+measure-box = кор
+measure-box-name = коробка
+measure-hour = ч
+measure-hour-name = час
+
+vat-rate = Ставка:
+vat-percent = НДС { $percent }%
+vat-none = без НДС
+vat-included = в том числе НДС { $percent }% — { $amount }
+vat-added = плюс НДС { $percent }% — { $amount }
+vat-custom = Другая
+vat-extract = НДС в цене
+vat-add = НДС сверху
+
+total = Итоговая сумма: { $total } за { $quantity }, { $vat }.
+leftover-positive = В остатке: { $amount }
+leftover-negative = Не хватает: { $amount }
+leftover-accounting = Остаток: { $amount }
+
+receipt = Чек по QR-коду
+receipt-image = Снимок чека (PNG, JPEG):
+receipt-recognize = Распознать
+receipt-recognized = QR-код распознан, сумма { $sum } подставлена в цену
+receipt-recognizing = Распознаётся…
+receipt-qr = Строка QR-кода:
+receipt-header = { $operation } { $time } на сумму { $sum }
+receipt-to-price = В цену
+receipt-to-budget = В бюджет
+receipt-to-expenses = В расходы категории
+operation-income = Приход
+operation-income-return = Возврат прихода
+operation-expense = Расход
+operation-expense-return = Возврат расхода
+
+comparison = Сравнение предложений
+offer = Предложение
+offer-numbered = Предложение { $number }
+offer-for = За:
+offer-pack-size = Штук в упаковке:
+offer-box-size = Штук в коробке:
+offer-remove = Удалить
+offer-add = Добавить предложение
+offer-price-per = Цена за { $measure }
+offer-packs = Упаковок на бюджет
+offer-leftover = Остаток
+offer-cheapest = { $name } (дешевле всех)
+
+mode-purchase = Покупка
+mode-split = Разделить счёт
+remove = Удалить
+
+split-equal = Поровну
+split-weights = По долям
+split-items = По позициям
+split-total = Сумма счёта:
+split-tip = Чаевые, %:
+split-participants = Участники
+split-participant = Участник
+split-participant-numbered = Участник { $number }
+split-weight = Доля:
+split-paid = Заплатил:
+split-add-participant = Добавить участника
+split-receipt = Позиции чека
+split-item-numbered = Позиция { $number }
+split-add-item = Добавить позицию
+split-items-total = Сумма позиций: { $total }
+split-share = К оплате
+split-paid-column = Заплатил
+split-balance = Баланс
+split-grand-total = Итого с чаевыми: { $total }
+split-settlement = Кто кому должен
+split-transfer = { $from } → { $to }: { $amount }
+split-settled = Все в расчёте
+split-enter-paid = Укажите, кто сколько заплатил
+history-summary-split = счёт { $total } на { $count }
+
+mode-loan = Кредит
+loan-principal = Сумма кредита:
+loan-rate = Ставка, % годовых:
+loan-months = Срок, месяцев:
+loan-annuity = Аннуитетный
+loan-differentiated = Дифференцированный
+loan-early = Досрочное погашение
+loan-early-month = Месяц:
+loan-early-amount = Сумма:
+loan-reduce-term = Сократить срок
+loan-reduce-payment = Уменьшить платёж
+loan-add-early = Добавить досрочное погашение
+loan-number = №
+loan-payment = Платёж
+loan-interest = Проценты
+loan-principal-part = Основной долг
+loan-early-column = Досрочно
+loan-balance = Остаток
+loan-total = Итого
+loan-total-paid = Всего выплат: { $amount }
+loan-total-interest = Переплата по процентам: { $amount }
+history-summary-loan = кредит { $principal } под { $rate }% на { $months } мес.
+
+mode-deposit = Вклад
+deposit-initial = Первоначальный взнос:
+deposit-top-up = Ежемесячное пополнение:
+deposit-rate = Ставка, % годовых:
+deposit-tax = Налог на проценты, %:
+deposit-months = Срок, месяцев:
+deposit-capitalization = Капитализация:
+capitalization-daily = Ежедневная
+capitalization-monthly = Ежемесячная
+capitalization-quarterly = Ежеквартальная
+deposit-target = Цель (бюджет):
+deposit-target-reached = Цель будет достигнута через { $months } мес., { $date }
+deposit-target-unreachable = Цель не достигается за { $years } лет
+deposit-total-balance = На счёте в конце срока: { $amount }
+deposit-total-top-ups = Внесено: { $amount }
+deposit-total-interest = Начислено процентов: { $amount }
+deposit-total-tax = Удержано налога: { $amount }
+deposit-date = Дата
+deposit-top-up-column = Пополнение
+deposit-interest = Проценты
+deposit-tax-column = Налог
+deposit-balance = Остаток
+history-summary-deposit = вклад { $initial } под { $rate }% на { $months } мес.
+
+mode-budget = Бюджет
+budget-categories = Категории
+budget-monthly = В месяц:
+budget-add-category = Добавить категорию
+carry-over-none = Без переноса
+carry-over-full = Переносить остаток и перерасход
+carry-over-surplus = Переносить только остаток
+budget-new-expense = Новый расход
+budget-date = Дата:
+budget-category = Категория:
+budget-amount = Сумма:
+budget-note = Комментарий:
+budget-category-column = Категория
+budget-monthly-column = Бюджет
+budget-carried = Перенесено
+budget-spent = Потрачено
+budget-remaining = Осталось
+budget-status = { $category }: { $status }
+budget-uncategorized = Расходы вне категорий: { $amount }
+budget-expenses = Расходы за месяц
+budget-field = (поле «Бюджет»)
+budget-record = Записать в расходы
+recurring = Регулярные платежи
+recurring-start = Первый платёж:
+recurring-every = Каждые:
+frequency-daily = дней
+frequency-weekly = недель
+frequency-monthly = месяцев
+frequency-yearly = лет
+recurring-never = Бессрочно
+recurring-after = Платежей:
+recurring-until = До:
+recurring-add = Добавить регулярный платёж
+recurring-upcoming = Ближайшие платежи ({ $days } дней)
+recurring-paid = Оплачено
+budget-planned = Запланировано
+budget-projected = Прогноз на конец месяца
+budget-month-end = К концу месяца с учётом регулярных платежей:
+history-summary-budget = бюджет за { $month }, расходов: { $count }
+
+mode-charts = Графики
+bucket-day = По дням
+bucket-week = По неделям
+bucket-month = По месяцам
+chart-from = С:
+chart-to = По:
+chart-spending = Расходы
+chart-categories = Расходы по категориям
+chart-burn-down = Остаток бюджета за { $month }
+chart-remaining = Остаток
+chart-even = Равномерный расход
+chart-empty = Нет расходов за этот период
+history-summary-charts = графики с { $from } по { $to }
+
+mode-payment = Платёжное поручение
+payment-number = Номер:
+payment-date = Дата:
+payment-amount = Сумма:
+payment-words = Сумма прописью: { $words }
+payment-copy = Копировать
+payment-payer = Плательщик
+payment-recipient = Получатель
+payment-name = Наименование:
+payment-inn = ИНН:
+payment-kpp = КПП:
+payment-account = Счёт:
+payment-bank = Банк:
+payment-bik = БИК:
+payment-correspondent = Корр. счёт:
+payment-purpose = Назначение платежа:
+payment-priority = Очерёдность:
+payment-exchange = Сохранить для банк-клиента (1С)
+payment-html = Сохранить для печати (HTML)
+history-summary-payment = платёжное поручение № { $number } на { $amount }
+
+mode-invoice = Счёт и акт
+invoice-kind-invoice = Счёт на оплату
+invoice-kind-act = Акт
+invoice-buyer = Покупатель (заказчик):
+invoice-basis = Основание:
+invoice-items = Позиции
+invoice-item-name = Наименование:
+invoice-item-quantity = Кол-во:
+invoice-item-unit = Ед.:
+invoice-add-item = Добавить позицию
+invoice-prices-include-vat = Цены включают НДС
+invoice-seller = Поставщик (исполнитель)
+invoice-address = Адрес:
+invoice-logo = Логотип (PNG, JPEG):
+invoice-font = Шрифт (TTF):
+invoice-director = Руководитель:
+invoice-accountant = Бухгалтер:
+invoice-terms = Условия оплаты:
+invoice-template-path = Файл шаблона:
+invoice-template-load = Загрузить шаблон
+invoice-template-save = Сохранить шаблон
+invoice-template-loaded = Шаблон загружен из { $path }
+invoice-total = Всего к оплате: { $total }, в т.ч. НДС { $vat }
+invoice-pdf = Сохранить PDF
+history-summary-invoice = { $kind } № { $number } на { $amount }
+
+mode-ledger = Журнал
+ledger-path = Файл базы:
+ledger-new = Новая операция
+ledger-amount = Сумма (расход со знаком минус):
+ledger-counterparty = Контрагент:
+ledger-added = Записано операций: { $count }
+ledger-from-budget = Записать расходы бюджета за { $month }
+ledger-search = Поиск
+ledger-from = С:
+ledger-to = По:
+ledger-find = Найти
+ledger-total = Найдено операций: { $count }, итого { $amount } ({ $words })
+ledger-to-budget = Перенести расходы в бюджет
+ledger-amount-column = Сумма
+ledger-currency = Валюта:
+ledger-currency-column = Валюта
+ledger-reference-column = Номер в банке
+ledger-total-in = Найдено операций: { $count }, итого { $amount } { $currency }
+ledger-counterparty-column = Контрагент
+ledger-note-column = Комментарий
+ledger-statement = Выписка (1С, OFX, QIF, camt.053):
+ledger-import = Загрузить
+ledger-imported = Записано операций: { $count }, уже были в журнале: { $duplicates }
+ledger-problem = Строка { $line }, { $section }: { $details }
+history-summary-ledger = журнал { $path }
+
+cli-usage = Использование:
+    ledger <файл> add <дата> <сумма> [категория] [контрагент] [комментарий]
+    ledger <файл> list [--from <дата>] [--to <дата>] [--category <категория>] [--counterparty <контрагент>] [--currency <валюта>]
+    ledger <файл> total [те же отборы, что у list]
+    ledger <файл> receipt <строка QR-кода чека или его снимок PNG/JPEG> [категория]
+    ledger <файл> import <выписка 1CClientBankExchange, OFX, QIF или camt.053> [валюта, если выписка её не указывает]
+    ledger <файл> categories
+    ledger <файл> remove <номер>
+cli-missing-value = Не указано значение для { $option }
+cli-unknown-option = Неизвестный параметр { $option }
+cli-added = Записано под номером { $id }
+cli-not-found = Операции с номером { $id } нет
+
+export-path = Файл:
+export-csv = Сохранить CSV
+export-done = Сохранено в { $path }
+export-failed = Не удалось сохранить { $path }: { $details }
+close = Закрыть
+
+history = История
+undo = Отменить
+redo = Повторить
+history-summary = бюджет { $budget }, { $price } × { $quantity } { $measure }
+
+fix-kopeks = Округлить до копеек
+fix-round = Округлить
+
+error-kopek-digits = Неверно указано количество копеек, правильное значение: [рубли].(1-99)
+error-quantity-digits = Слишком много знаков после запятой, допустимо не больше { $precision }
+error-precision = Точность не может быть больше { $max } знаков
+error-negative-quantity = Количество не может быть отрицательным
+error-incompatible-units = Единицы измерения несовместимы
+error-pack-size-missing = Не указано количество штук в упаковке
+error-division-by-zero = Деление на ноль
+error-no-offers = Нет предложений для сравнения
+error-negative-amount = Сумма не может быть отрицательной
+error-number-too-large = Число слишком велико, чтобы записать его словами
+error-overflow = Переполнение: результат слишком велик
+error-out-of-range = Число выходит за допустимый диапазон
+error-invalid-number = Введите целое неотрицательное число
+error-no-participants = Некому платить: нет участников
+error-unbalanced-payments = Оплаченная сумма не совпадает с суммой счёта
+error-zero-term = Срок должен быть не меньше одного месяца
+error-term-too-long = Срок должен быть не больше { $months } месяцев
+error-invalid-date = Дата указывается как ДД.ММ.ГГГГ
+error-storage = Ошибка базы данных: { $details }
+error-newer-schema = База создана более новой версией программы (схема { $version }), обновите программу
+error-zero-interval = Интервал повторения должен быть не меньше единицы
+error-missing-receipt-field = В строке чека нет поля { $field }
+error-invalid-receipt-field = В строке чека неверное поле { $field }
+error-no-qr-code = На изображении не найден QR-код чека
+error-image = Не удалось открыть изображение: { $details }
+error-file = Не удалось прочитать файл: { $details }
+error-unknown-statement-format = Неизвестный формат выписки, поддерживаются 1CClientBankExchange, OFX, QIF и camt.053
+error-xml = Ошибка в XML: { $details }
+error-missing-statement-field = В выписке нет поля { $field }
+error-invalid-statement-field = В выписке неверное поле { $field }
+error-unclosed-section = Секция выписки не закрыта
+error-unknown-direction = Непонятно, списание это или поступление: ни один счёт документа не совпадает со счётом выписки
+error-mixed-currencies = Суммы в разных валютах нельзя сложить, выберите валюту
+error-invalid-inn = ИНН должен состоять из 10 или 12 цифр с верными контрольными
+error-invalid-kpp = КПП состоит из 9 знаков: четыре цифры, две цифры или буквы, три цифры
+error-invalid-bik = БИК должен состоять из 9 цифр
+error-invalid-account = Номер счёта не сходится с БИК: нужно 20 цифр с верным контрольным ключом
+error-invalid-correspondent-account = Корреспондентский счёт не сходится с БИК
+error-zero-amount = Сумма должна быть больше нуля
+error-empty-purpose = Не указано назначение платежа
+error-no-items = В документе нет ни одной позиции
+error-invalid-template-line = В шаблоне строки записываются как Ключ=значение
+error-font = Не удалось загрузить шрифт TTF: { $details }
+error-pdf = Не удалось создать PDF: { $details }
+error-not-a-number = Это не число ({ $details })
@@ -0,0 +1,139 @@
+use chrono::NaiveDate;
+use money_counter::{
+    field::{self, FieldValue},
+    i18n::Localizer,
+    ledger::{self, Filter, Ledger, Transaction},
+    money::Money,
+    pronounce::Language,
+    receipt::{self, Receipt},
+    statement
+};
+use rust_decimal::Decimal;
+
+/// `ledger <file> <command> ...`
+fn run(args: &[String], tr: &Localizer) -> Result<String, String> {
+    let [path, command, rest @ ..] = args else {
+        return Err(tr.tr("cli-usage"));
+    };
+    let mut ledger = Ledger::open(path).map_err(|error| tr.error(&error))?;
+
+    let filter = || -> Result<Filter, String> {
+        let mut filter = Filter::default();
+        let mut options = rest.iter();
+        while let Some(option) = options.next() {
+            let value = options
+                .next()
+                .ok_or_else(|| tr.tr_args("cli-missing-value", &[("option", option.clone())]))?;
+            match option.as_str() {
+                "--from" => filter.from = field::optional(value).map_err(|error| tr.error(&error))?,
+                "--to" => filter.to = field::optional(value).map_err(|error| tr.error(&error))?,
+                "--category" => filter.category = field::optional_text(value),
+                "--counterparty" => filter.counterparty = field::optional_text(value),
+                "--currency" => filter.currency = field::optional_text(&value.to_uppercase()),
+                _ => return Err(tr.tr_args("cli-unknown-option", &[("option", option.clone())])),
+            }
+        }
+        Ok(filter)
+    };
+
+    match command.as_str() {
+        "add" => {
+            let [date, amount, details @ ..] = rest else {
+                return Err(tr.tr("cli-usage"));
+            };
+            let detail = |index: usize| details.get(index).cloned().unwrap_or_default();
+            let transaction = Transaction {
+                id: None,
+                date: NaiveDate::parse(date, &NaiveDate::default()).map_err(|error| tr.error(&error))?,
+                amount: Money::parse(amount, &Money(Decimal::ZERO)).map_err(|error| tr.error(&error))?,
+                currency: ledger::RUB.to_string(),
+                category: detail(0),
+                counterparty: detail(1),
+                note: detail(2),
+                reference: String::new()
+            };
+            let id = ledger.add(&transaction).map_err(|error| tr.error(&error))?;
+            Ok(tr.tr_args("cli-added", &[("id", id.to_string())]))
+        },
+        "list" => {
+            let lines: Vec<String> = ledger.query(&filter()?)
+                .map_err(|error| tr.error(&error))?
+                .iter()
+                .map(|transaction| format!("{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+                    transaction.id.unwrap_or_default(),
+                    transaction.date.format("%d.%m.%Y"),
+                    transaction.amount.accounting(),
+                    transaction.currency,
+                    transaction.category,
+                    transaction.counterparty,
+                    transaction.note,
+                    transaction.reference
+                ))
+                .collect();
+            Ok(lines.join("\n"))
+        },
+        "total" => {
+            let transactions = ledger.query(&filter()?).map_err(|error| tr.error(&error))?;
+            let total = ledger::sum(&transactions).map_err(|error| tr.error(&error))?;
+            match transactions.first().map(|transaction| transaction.currency.as_str()) {
+                Some(currency) if currency != ledger::RUB => Ok(format!("{}\t{}", total.accounting(), currency)),
+                _ => Ok(format!("{}\t{}", total.accounting(), total)),
+            }
+        },
+        "receipt" => {
+            let [source, details @ ..] = rest else {
+                return Err(tr.tr("cli-usage"));
+            };
+            // A file is a photo of the receipt, anything else is the string itself
+            let receipt = match std::path::Path::new(source).is_file() {
+                true => receipt::from_image(source),
+                false => source.parse::<Receipt>(),
+            }.map_err(|error| tr.error(&error))?;
+            let transaction = Transaction {
+                category: details.first().cloned().unwrap_or_default(),
+                ..Transaction::from(&receipt)
+            };
+            let id = ledger.add(&transaction).map_err(|error| tr.error(&error))?;
+            Ok(tr.tr_args("cli-added", &[("id", id.to_string())]))
+        },
+        "import" => {
+            let [statement, currency @ ..] = rest else {
+                return Err(tr.tr("cli-usage"));
+            };
+            let currency = currency.first().map_or(ledger::RUB.to_string(), |currency| currency.to_uppercase());
+            let statement = statement::read(statement, &currency).map_err(|error| tr.error(&error))?;
+            let count = ledger.add_new(&statement.transactions).map_err(|error| tr.error(&error))?;
+            Ok(tr.imported(&statement, count))
+        },
+        "categories" => Ok(ledger.categories().map_err(|error| tr.error(&error))?.join("\n")),
+        "remove" => {
+            let id = rest
+                .first()
+                .and_then(|id| id.parse().ok())
+                .ok_or_else(|| tr.tr("cli-usage"))?;
+            match ledger.remove(id).map_err(|error| tr.error(&error))? {
+                true => Ok(String::new()),
+                false => Err(tr.tr_args("cli-not-found", &[("id", id.to_string())])),
+            }
+        },
+        _ => Err(tr.tr("cli-usage")),
+    }
+}
+
+/// The ledger without the window. A program of its own: the window one is built
+/// for the Windows GUI subsystem, which has no console to print to
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let tr = Localizer::new(Language::default());
+    match run(&args, &tr) {
+        Ok(output) => {
+            if !output.is_empty() {
+                println!("{}", output);
+            }
+        },
+        Err(message) => {
+            eprintln!("{}", message);
+            std::process::exit(1);
+        }
+    }
+}
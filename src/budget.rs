@@ -0,0 +1,243 @@
+use chrono::{Datelike, Months, NaiveDate};
+use rust_decimal::Decimal;
+
+use crate::money::{Error, Money};
+
+/// What happens to the rest of a category's money when the month ends
+#[derive(Debug,
+    PartialEq, Eq,
+    Clone, Copy,
+    Default)]
+pub enum CarryOver {
+    /// Every month starts from the monthly budget
+    #[default]
+    None,
+    /// Unspent money is added to the next month, overspending is taken from it
+    Full,
+    /// Only unspent money moves on, overspending is forgiven
+    SurplusOnly
+}
+
+impl CarryOver {
+    pub const ALL: [CarryOver;3] = [CarryOver::None, CarryOver::Full, CarryOver::SurplusOnly];
+
+    fn carry(&self, remaining: Money) -> Money {
+        match self {
+            CarryOver::None => Money(Decimal::ZERO),
+            CarryOver::Full => remaining,
+            CarryOver::SurplusOnly => remaining.max(Money(Decimal::ZERO)),
+        }
+    }
+}
+
+#[derive(Debug,
+    PartialEq, Eq,
+    Clone)]
+pub struct Category {
+    pub name: String,
+    pub monthly: Money,
+    pub carry_over: CarryOver
+}
+
+#[derive(Debug,
+    PartialEq, Eq,
+    Clone)]
+pub struct Expense {
+    pub date: NaiveDate,
+    /// Name of a category, expenses in unknown categories are counted separately
+    pub category: String,
+    pub amount: Money,
+    pub note: String
+}
+
+/// Where one category stands in one month
+#[derive(Debug,
+    PartialEq, Eq,
+    Clone)]
+pub struct Status {
+    pub category: String,
+    pub monthly: Money,
+    /// Brought from the previous month, negative for overspending
+    pub carried: Money,
+    pub spent: Money
+}
+
+impl Status {
+    pub fn available(&self) -> Result<Money, Error> {
+        self.monthly.checked_add(self.carried)
+    }
+
+    /// Negative when the category is overspent
+    pub fn remaining(&self) -> Result<Money, Error> {
+        self.available()?.checked_sub(self.spent)
+    }
+}
+
+/// First day of the month `date` falls in
+pub fn month_of(date: NaiveDate) -> NaiveDate {
+    date.with_day(1).unwrap_or(date)
+}
+
+/// Last day of the month `date` falls in
+pub fn month_end(date: NaiveDate) -> NaiveDate {
+    month_of(date)
+        .checked_add_months(Months::new(1))
+        .and_then(|next| next.pred_opt())
+        .unwrap_or(date)
+}
+
+/// Monthly budgets by category and what was spent
+#[derive(Debug,
+    PartialEq, Eq,
+    Clone)]
+pub struct Budget {
+    /// Month the carry-over is counted from
+    pub start: NaiveDate,
+    pub categories: Vec<Category>,
+    pub expenses: Vec<Expense>
+}
+
+impl Budget {
+    pub fn new(start: NaiveDate) -> Self {
+        Budget { start: month_of(start), categories: Vec::new(), expenses: Vec::new() }
+    }
+
+    pub fn category(&self, name: &str) -> Option<&Category> {
+        self.categories.iter().find(|category| category.name == name)
+    }
+
+    /// Expenses of the month `month` falls in, in any category
+    pub fn expenses_in(&self, month: NaiveDate) -> impl Iterator<Item = &Expense> {
+        let month = month_of(month);
+        self.expenses.iter().filter(move |expense| month_of(expense.date) == month)
+    }
+
+    pub fn spent(&self, category: &str, month: NaiveDate) -> Result<Money, Error> {
+        self.expenses_in(month)
+            .filter(|expense| expense.category == category)
+            .try_fold(Money(Decimal::ZERO), |sum, expense| sum.checked_add(expense.amount))
+    }
+
+    /// Spent in categories that have no budget
+    pub fn uncategorized(&self, month: NaiveDate) -> Result<Money, Error> {
+        self.expenses_in(month)
+            .filter(|expense| self.category(&expense.category).is_none())
+            .try_fold(Money(Decimal::ZERO), |sum, expense| sum.checked_add(expense.amount))
+    }
+
+    /// Status of one category, carry-over counted from `start`
+    pub fn status_of(&self, category: &Category, month: NaiveDate) -> Result<Status, Error> {
+        let month = month_of(month);
+        let mut carried = Money(Decimal::ZERO);
+        let mut current = self.start;
+        while current < month {
+            let status = Status {
+                category: category.name.clone(),
+                monthly: category.monthly,
+                carried,
+                spent: self.spent(&category.name, current)?
+            };
+            carried = category.carry_over.carry(status.remaining()?);
+            current = current.checked_add_months(Months::new(1)).ok_or(Error::OutOfRange)?;
+        }
+
+        Ok(Status {
+            category: category.name.clone(),
+            monthly: category.monthly,
+            carried,
+            spent: self.spent(&category.name, month)?
+        })
+    }
+
+    /// Status of every category in the given month
+    pub fn status(&self, month: NaiveDate) -> Result<Vec<Status>, Error> {
+        self.categories
+            .iter()
+            .map(|category| self.status_of(category, month))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn rubles(amount: i64) -> Money {
+        Money(Decimal::from(amount))
+    }
+
+    /// 1000 a month from January, 1200 spent in January, 500 in February, nothing in March
+    fn budget(carry_over: CarryOver) -> Budget {
+        let mut budget = Budget::new(date(2024, 1, 15));
+        budget.categories.push(Category { name: "Еда".to_string(), monthly: rubles(1000), carry_over });
+        for (day, amount) in [(date(2024, 1, 3), 700), (date(2024, 1, 31), 500), (date(2024, 2, 29), 500)] {
+            budget.expenses.push(Expense { date: day, category: "Еда".to_string(), amount: rubles(amount), note: String::new() });
+        }
+        budget
+    }
+
+    fn carried(budget: &Budget, month: NaiveDate) -> Money {
+        budget.status_of(&budget.categories[0], month).unwrap().carried
+    }
+
+    #[test]
+    fn months() {
+        assert_eq!(month_of(date(2024, 2, 29)), date(2024, 2, 1));
+        assert_eq!(month_end(date(2024, 2, 10)), date(2024, 2, 29));
+        assert_eq!(month_end(date(2023, 2, 10)), date(2023, 2, 28));
+        assert_eq!(month_end(date(2023, 12, 31)), date(2023, 12, 31));
+        assert_eq!(Budget::new(date(2024, 1, 15)).start, date(2024, 1, 1));
+    }
+
+    #[test]
+    fn without_carry_over_every_month_starts_afresh() {
+        let budget = budget(CarryOver::None);
+        let status = budget.status_of(&budget.categories[0], date(2024, 1, 1)).unwrap();
+        assert_eq!(status.spent, rubles(1200));
+        assert_eq!(status.remaining(), Ok(rubles(-200)));
+        assert_eq!(carried(&budget, date(2024, 2, 1)), rubles(0));
+        assert_eq!(carried(&budget, date(2024, 3, 1)), rubles(0));
+    }
+
+    #[test]
+    fn full_carry_over_takes_overspending_from_the_next_month() {
+        let budget = budget(CarryOver::Full);
+        assert_eq!(carried(&budget, date(2024, 1, 1)), rubles(0));
+        assert_eq!(carried(&budget, date(2024, 2, 1)), rubles(-200));
+        let february = budget.status_of(&budget.categories[0], date(2024, 2, 10)).unwrap();
+        assert_eq!(february.available(), Ok(rubles(800)));
+        assert_eq!(february.remaining(), Ok(rubles(300)));
+        // Unspent money keeps adding up
+        assert_eq!(carried(&budget, date(2024, 3, 1)), rubles(300));
+        assert_eq!(carried(&budget, date(2024, 4, 1)), rubles(1300));
+    }
+
+    #[test]
+    fn surplus_only_forgives_overspending() {
+        let budget = budget(CarryOver::SurplusOnly);
+        assert_eq!(carried(&budget, date(2024, 2, 1)), rubles(0));
+        assert_eq!(carried(&budget, date(2024, 3, 1)), rubles(500));
+        assert_eq!(carried(&budget, date(2024, 4, 1)), rubles(1500));
+    }
+
+    #[test]
+    fn nothing_is_carried_into_months_before_the_start() {
+        let budget = budget(CarryOver::Full);
+        assert_eq!(carried(&budget, date(2023, 11, 1)), rubles(0));
+    }
+
+    #[test]
+    fn unknown_categories_are_counted_apart() {
+        let mut budget = budget(CarryOver::None);
+        budget.expenses.push(Expense { date: date(2024, 1, 5), category: "Кино".to_string(), amount: rubles(350), note: String::new() });
+        budget.expenses.push(Expense { date: date(2024, 2, 5), category: "Кино".to_string(), amount: rubles(400), note: String::new() });
+        assert_eq!(budget.uncategorized(date(2024, 1, 20)), Ok(rubles(350)));
+        assert_eq!(budget.spent("Еда", date(2024, 1, 20)), Ok(rubles(1200)));
+        assert_eq!(budget.expenses_in(date(2024, 2, 1)).count(), 2);
+        assert_eq!(budget.status(date(2024, 1, 1)).unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,220 @@
+use chrono::NaiveDate;
+use roxmltree::{Document, Node};
+
+use crate::{ledger::Transaction, money::Money, statement::{self, Error, Problem, Statement}};
+
+/// First descendant element with the local name, namespaces differ between versions
+fn find<'a, 'input>(node: Node<'a, 'input>, name: &str) -> Option<Node<'a, 'input>> {
+    node.descendants().find(|child| child.is_element() && child.tag_name().name() == name)
+}
+
+/// Element at the path of local names below `node`
+fn path<'a, 'input>(node: Node<'a, 'input>, names: &[&str]) -> Option<Node<'a, 'input>> {
+    names.iter().try_fold(node, |node, name| {
+        node.children().find(|child| child.is_element() && child.tag_name().name() == *name)
+    })
+}
+
+fn text<'a>(node: Node<'a, '_>, names: &[&str]) -> Option<&'a str> {
+    path(node, names)
+        .and_then(|node| node.text())
+        .map(str::trim)
+        .filter(|text| !text.is_empty())
+}
+
+/// `Dt` or the date part of `DtTm`
+fn date(node: Node, field: &'static str) -> Result<NaiveDate, Error> {
+    let value = text(node, &["Dt"])
+        .or_else(|| text(node, &["DtTm"]))
+        .ok_or(Error::MissingField(field))?;
+    value
+        .get(..10)
+        .and_then(|day| NaiveDate::parse_from_str(day, "%Y-%m-%d").ok())
+        .ok_or(Error::InvalidField(field))
+}
+
+/// `Amt` with the sign of `CdtDbtInd`, debits are negative
+fn amount(node: Node) -> Result<(Money, String), Error> {
+    let amount = path(node, &["Amt"]).ok_or(Error::MissingField("Amt"))?;
+    let value = statement::amount(amount.text().unwrap_or_default(), "Amt")?;
+    let currency = amount.attribute("Ccy").unwrap_or_default().to_string();
+    match text(node, &["CdtDbtInd"]) {
+        Some("CRDT") => Ok((value, currency)),
+        Some("DBIT") => Ok((Money(-value.0), currency)),
+        Some(_) => Err(Error::InvalidField("CdtDbtInd")),
+        None => Err(Error::MissingField("CdtDbtInd")),
+    }
+}
+
+/// Name of the other side: the creditor of a debit, the debtor of a credit
+fn counterparty<'a>(details: Node<'a, '_>, debit: bool) -> Option<&'a str> {
+    let party = match debit {
+        true => path(details, &["RltdPties", "Cdtr"]),
+        false => path(details, &["RltdPties", "Dbtr"]),
+    }?;
+    find(party, "Nm").and_then(|name| name.text()).map(str::trim)
+}
+
+fn entry(node: Node) -> Result<Transaction, Error> {
+    let (amount, currency) = amount(node)?;
+    let date = date(path(node, &["BookgDt"]).ok_or(Error::MissingField("BookgDt"))?, "BookgDt")?;
+    let details = path(node, &["NtryDtls", "TxDtls"]);
+
+    let reference = text(node, &["AcctSvcrRef"])
+        .or_else(|| details.and_then(|details| text(details, &["Refs", "AcctSvcrRef"])))
+        .or_else(|| details.and_then(|details| text(details, &["Refs", "EndToEndId"])))
+        .filter(|reference| *reference != "NOTPROVIDED");
+    let remittance: Vec<&str> = details
+        .and_then(|details| path(details, &["RmtInf"]))
+        .map(|information| information
+            .children()
+            .filter(|child| child.tag_name().name() == "Ustrd")
+            .filter_map(|child| child.text())
+            .map(str::trim)
+            .collect())
+        .unwrap_or_default();
+    let note = match remittance.is_empty() {
+        true => text(node, &["AddtlNtryInf"]).unwrap_or_default().to_string(),
+        false => remittance.join(" "),
+    };
+
+    Ok(Transaction {
+        id: None,
+        date,
+        amount,
+        currency,
+        category: String::new(),
+        counterparty: details
+            .and_then(|details| counterparty(details, amount.0.is_sign_negative()))
+            .unwrap_or_default()
+            .to_string(),
+        note,
+        reference: reference.unwrap_or_default().to_string()
+    })
+}
+
+/// ISO 20022 camt.053 end-of-day statement, any version, every `Stmt` in the file.
+/// Pending entries are skipped, only booked ones are in the ledger
+pub fn parse(xml: &str) -> Result<Statement, Error> {
+    let document = Document::parse(xml).map_err(|error| Error::Xml(error.to_string()))?;
+    let root = find(document.root(), "BkToCstmrStmt").ok_or(Error::UnknownFormat)?;
+    let line = |node: Node| document.text_pos_at(node.range().start).row as usize;
+
+    let mut statement = Statement::default();
+    for report in root.children().filter(|child| child.tag_name().name() == "Stmt") {
+        let account = path(report, &["Acct", "Id", "IBAN"]).or_else(|| path(report, &["Acct", "Id", "Othr", "Id"]));
+        statement.add_account(account.and_then(|account| account.text()).unwrap_or_default().trim());
+
+        if let Some(period) = path(report, &["FrToDt"]) {
+            let day = |name| text(period, &[name]).and_then(|value| value.get(..10)).and_then(|day| day.parse().ok());
+            if let Some(from) = day("FrDtTm") {
+                statement.from = Some(statement.from.map_or(from, |earliest| earliest.min(from)));
+            }
+            statement.to = statement.to.max(day("ToDtTm"));
+        }
+
+        for balance in report.children().filter(|child| child.tag_name().name() == "Bal") {
+            let Some(code) = text(balance, &["Tp", "CdOrPrtry", "Cd"]) else {
+                continue;
+            };
+            let money = match amount(balance) {
+                Ok((money, _)) => money,
+                Err(error) => {
+                    statement.problems.push(Problem { line: line(balance), section: "Bal".to_string(), error });
+                    continue;
+                }
+            };
+            match code {
+                "OPBD" | "PRCD" => statement.opening = Some(money),
+                "CLBD" => statement.closing = Some(money),
+                _ => {},
+            }
+        }
+
+        for node in report.children().filter(|child| child.tag_name().name() == "Ntry") {
+            let status = text(node, &["Sts"]).or_else(|| text(node, &["Sts", "Cd"]));
+            if status.is_some_and(|status| status != "BOOK") { continue; }
+
+            match entry(node) {
+                Ok(transaction) => statement.transactions.push(transaction),
+                Err(error) => statement.problems.push(Problem { line: line(node), section: "Ntry".to_string(), error }),
+            }
+        }
+    }
+
+    Ok(statement.finish())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal::Decimal;
+
+    use super::*;
+
+    const XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Document xmlns="urn:iso:std:iso:20022:tech:xsd:camt.053.001.02">
+  <BkToCstmrStmt>
+    <Stmt>
+      <Acct><Id><IBAN>KW81CBKU0000000000001234560101</IBAN></Id></Acct>
+      <FrToDt><FrDtTm>2024-03-01T00:00:00</FrDtTm><ToDtTm>2024-03-31T23:59:59</ToDtTm></FrToDt>
+      <Bal>
+        <Tp><CdOrPrtry><Cd>OPBD</Cd></CdOrPrtry></Tp>
+        <Amt Ccy="KWD">1500.000</Amt><CdtDbtInd>CRDT</CdtDbtInd>
+      </Bal>
+      <Bal>
+        <Tp><CdOrPrtry><Cd>CLBD</Cd></CdOrPrtry></Tp>
+        <Amt Ccy="KWD">1349.500</Amt><CdtDbtInd>CRDT</CdtDbtInd>
+      </Bal>
+      <Ntry>
+        <Amt Ccy="KWD">150.500</Amt><CdtDbtInd>DBIT</CdtDbtInd>
+        <Sts>BOOK</Sts>
+        <BookgDt><Dt>2024-03-05</Dt></BookgDt>
+        <AcctSvcrRef>REF-1</AcctSvcrRef>
+        <NtryDtls><TxDtls>
+          <RltdPties><Cdtr><Nm>Gulf Rent Co</Nm></Cdtr></RltdPties>
+          <RmtInf><Ustrd>Rent</Ustrd><Ustrd>March</Ustrd></RmtInf>
+        </TxDtls></NtryDtls>
+      </Ntry>
+      <Ntry>
+        <Amt Ccy="KWD">10.000</Amt><CdtDbtInd>CRDT</CdtDbtInd>
+        <Sts>PDNG</Sts>
+        <BookgDt><Dt>2024-03-30</Dt></BookgDt>
+      </Ntry>
+      <Ntry>
+        <Amt Ccy="KWD">1.234</Amt><CdtDbtInd>CRDT</CdtDbtInd>
+        <Sts>BOOK</Sts>
+        <BookgDt><DtTm>2024-03-31T10:00:00</DtTm></BookgDt>
+      </Ntry>
+    </Stmt>
+  </BkToCstmrStmt>
+</Document>
+"#;
+
+    #[test]
+    fn booked_entries() {
+        let statement = parse(XML).unwrap();
+        assert_eq!(statement.accounts, vec!["KW81CBKU0000000000001234560101".to_string()]);
+        assert_eq!((statement.from, statement.to), (NaiveDate::from_ymd_opt(2024, 3, 1), NaiveDate::from_ymd_opt(2024, 3, 31)));
+        assert_eq!(statement.opening, Some(Money(Decimal::from(1500))));
+        assert_eq!(statement.closing, Some(Money(Decimal::new(134950, 2))));
+        assert_eq!(statement.transactions, vec![Transaction {
+            id: None,
+            date: NaiveDate::from_ymd_opt(2024, 3, 5).unwrap(),
+            amount: Money(Decimal::new(-15050, 2)),
+            currency: "KWD".to_string(),
+            category: String::new(),
+            counterparty: "Gulf Rent Co".to_string(),
+            note: "Rent March".to_string(),
+            reference: "REF-1".to_string()
+        }]);
+        // Fils below a hundredth don't fit in kopeks
+        assert_eq!(statement.problems, vec![Problem { line: 30, section: "Ntry".to_string(), error: Error::InvalidField("Amt") }]);
+    }
+
+    #[test]
+    fn not_camt() {
+        assert!(matches!(parse("<Document><Other/></Document>"), Err(Error::UnknownFormat)));
+        assert!(matches!(parse("<Document>"), Err(Error::Xml(_))));
+    }
+}
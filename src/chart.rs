@@ -0,0 +1,239 @@
+use chrono::{Datelike, Days, Months, NaiveDate};
+use rust_decimal::Decimal;
+
+use crate::{budget::{self, Budget, Expense}, money::{Error, Money, Rounding}};
+
+/// How spending over time is grouped
+#[derive(Debug,
+    PartialEq, Eq,
+    Clone, Copy,
+    Default)]
+pub enum Bucket {
+    #[default]
+    Day,
+    /// From Monday
+    Week,
+    Month
+}
+
+impl Bucket {
+    pub const ALL: [Bucket;3] = [Bucket::Day, Bucket::Week, Bucket::Month];
+
+    /// First day of the bucket `date` falls in
+    pub fn start(&self, date: NaiveDate) -> NaiveDate {
+        match self {
+            Bucket::Day => date,
+            Bucket::Week => date
+                .checked_sub_days(Days::new(date.weekday().num_days_from_monday().into()))
+                .unwrap_or(date),
+            Bucket::Month => budget::month_of(date),
+        }
+    }
+
+    fn next(&self, start: NaiveDate) -> Option<NaiveDate> {
+        match self {
+            Bucket::Day => start.succ_opt(),
+            Bucket::Week => start.checked_add_days(Days::new(7)),
+            Bucket::Month => start.checked_add_months(Months::new(1)),
+        }
+    }
+}
+
+#[derive(Debug,
+    PartialEq, Eq,
+    Clone, Copy)]
+pub struct Point {
+    /// First day of the bucket
+    pub start: NaiveDate,
+    pub amount: Money
+}
+
+/// One day of the month against the budget
+#[derive(Debug,
+    PartialEq, Eq,
+    Clone, Copy)]
+pub struct Day {
+    pub date: NaiveDate,
+    /// Left at the end of the day
+    pub remaining: Money,
+    /// What would be left if the money was spent evenly
+    pub even: Money
+}
+
+fn sum<'a>(mut expenses: impl Iterator<Item = &'a Expense>) -> Result<Money, Error> {
+    expenses.try_fold(Money(Decimal::ZERO), |sum, expense| sum.checked_add(expense.amount))
+}
+
+/// Spending from `from` to `to` inclusive, one point per bucket, empty buckets included
+pub fn spending(expenses: &[Expense], bucket: Bucket, from: NaiveDate, to: NaiveDate) -> Result<Vec<Point>, Error> {
+    let mut points = Vec::new();
+    let mut start = bucket.start(from);
+    while start <= to {
+        let next = bucket.next(start).ok_or(Error::OutOfRange)?;
+        let amount = sum(expenses
+            .iter()
+            .filter(|expense| expense.date >= from && expense.date <= to)
+            .filter(|expense| expense.date >= start && expense.date < next))?;
+        points.push(Point { start, amount });
+        start = next;
+    }
+
+    Ok(points)
+}
+
+/// Spending from `from` to `to` inclusive by category, largest first
+pub fn by_category(expenses: &[Expense], from: NaiveDate, to: NaiveDate) -> Result<Vec<(String, Money)>, Error> {
+    let mut totals: Vec<(String, Money)> = Vec::new();
+    for expense in expenses.iter().filter(|expense| expense.date >= from && expense.date <= to) {
+        match totals.iter_mut().find(|(category, _)| *category == expense.category) {
+            Some((_, total)) => *total = total.checked_add(expense.amount)?,
+            None => totals.push((expense.category.clone(), expense.amount)),
+        }
+    }
+    totals.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+
+    Ok(totals)
+}
+
+/// Every day of the month `month` falls in: what is left of all categories together
+/// and the even spending line from the available money down to zero
+pub fn burn_down(budget: &Budget, month: NaiveDate, rounding: Rounding) -> Result<Vec<Day>, Error> {
+    let statuses = budget.status(month)?;
+    let available = statuses
+        .iter()
+        .try_fold(Money(Decimal::ZERO), |sum, status| sum.checked_add(status.available()?))?;
+
+    let first = budget::month_of(month);
+    let last = budget::month_end(month);
+    let length = Decimal::from(last.day());
+    let budgeted: Vec<&Expense> = budget
+        .expenses_in(month)
+        .filter(|expense| budget.category(&expense.category).is_some())
+        .collect();
+
+    let mut days = Vec::new();
+    let mut date = first;
+    while date <= last {
+        let spent = sum(budgeted.iter().copied().filter(|expense| expense.date <= date))?;
+        let left = length - Decimal::from(date.day());
+        days.push(Day {
+            date,
+            remaining: available.checked_sub(spent)?,
+            even: Money::round(available.checked_mul(left)?.checked_div(length)?.0, rounding)
+        });
+        date = date.succ_opt().ok_or(Error::OutOfRange)?;
+    }
+
+    Ok(days)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::budget::{CarryOver, Category};
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn rubles(amount: i64) -> Money {
+        Money(Decimal::from(amount))
+    }
+
+    fn expense(date: NaiveDate, category: &str, amount: i64) -> Expense {
+        Expense { date, category: category.to_string(), amount: rubles(amount), note: String::new() }
+    }
+
+    #[test]
+    fn buckets_start() {
+        // 2024-01-10 is a Wednesday
+        assert_eq!(Bucket::Week.start(date(2024, 1, 10)), date(2024, 1, 8));
+        assert_eq!(Bucket::Week.start(date(2024, 1, 8)), date(2024, 1, 8));
+        // A Sunday belongs to the week before, even across the new year
+        assert_eq!(Bucket::Week.start(date(2023, 1, 1)), date(2022, 12, 26));
+        assert_eq!(Bucket::Month.start(date(2024, 2, 29)), date(2024, 2, 1));
+        assert_eq!(Bucket::Day.start(date(2024, 2, 29)), date(2024, 2, 29));
+    }
+
+    #[test]
+    fn spending_keeps_empty_buckets() {
+        let expenses = [
+            expense(date(2024, 1, 2), "Еда", 1),
+            expense(date(2024, 1, 3), "Еда", 200),
+            expense(date(2024, 1, 17), "Кафе", 300),
+            expense(date(2024, 1, 18), "Еда", 50),
+            expense(date(2024, 1, 21), "Еда", 1000)
+        ];
+        // Expenses outside the range don't count even when their week does
+        assert_eq!(spending(&expenses, Bucket::Week, date(2024, 1, 3), date(2024, 1, 20)), Ok(vec![
+            Point { start: date(2024, 1, 1), amount: rubles(200) },
+            Point { start: date(2024, 1, 8), amount: rubles(0) },
+            Point { start: date(2024, 1, 15), amount: rubles(350) }
+        ]));
+
+        let days = spending(&expenses, Bucket::Day, date(2024, 1, 16), date(2024, 1, 19)).unwrap();
+        let amounts: Vec<Money> = days.iter().map(|point| point.amount).collect();
+        assert_eq!(amounts, [rubles(0), rubles(300), rubles(50), rubles(0)]);
+
+        let months = spending(&expenses, Bucket::Month, date(2023, 12, 15), date(2024, 2, 10)).unwrap();
+        let amounts: Vec<(NaiveDate, Money)> = months.iter().map(|point| (point.start, point.amount)).collect();
+        assert_eq!(amounts, [(date(2023, 12, 1), rubles(0)), (date(2024, 1, 1), rubles(1551)), (date(2024, 2, 1), rubles(0))]);
+
+        assert_eq!(spending(&expenses, Bucket::Day, date(2024, 1, 5), date(2024, 1, 4)), Ok(Vec::new()));
+    }
+
+    #[test]
+    fn categories_largest_first() {
+        let expenses = [
+            expense(date(2024, 1, 3), "Еда", 200),
+            expense(date(2024, 1, 17), "Кафе", 300),
+            expense(date(2024, 1, 18), "Еда", 150),
+            expense(date(2024, 1, 19), "Аптека", 300),
+            expense(date(2024, 2, 1), "Такси", 5000)
+        ];
+        assert_eq!(by_category(&expenses, date(2024, 1, 1), date(2024, 1, 31)), Ok(vec![
+            ("Еда".to_string(), rubles(350)),
+            ("Аптека".to_string(), rubles(300)),
+            ("Кафе".to_string(), rubles(300))
+        ]));
+    }
+
+    #[test]
+    fn burn_down_of_a_month() {
+        let mut budget = Budget::new(date(2024, 2, 1));
+        budget.categories.push(Category { name: "Еда".to_string(), monthly: rubles(2900), carry_over: CarryOver::None });
+        budget.expenses.push(expense(date(2024, 2, 1), "Еда", 100));
+        budget.expenses.push(expense(date(2024, 2, 10), "Еда", 400));
+        // Not budgeted, left out
+        budget.expenses.push(expense(date(2024, 2, 5), "Кино", 1000));
+
+        let days = burn_down(&budget, date(2024, 2, 20), Rounding::HalfUp).unwrap();
+        assert_eq!(days.len(), 29);
+        assert_eq!(days[0], Day { date: date(2024, 2, 1), remaining: rubles(2800), even: rubles(2800) });
+        assert_eq!(days[8].remaining, rubles(2800));
+        assert_eq!(days[9], Day { date: date(2024, 2, 10), remaining: rubles(2400), even: rubles(1900) });
+        assert_eq!(days[28], Day { date: date(2024, 2, 29), remaining: rubles(2400), even: rubles(0) });
+    }
+
+    #[test]
+    fn burn_down_counts_carry_over_and_rounds() {
+        let mut budget = Budget::new(date(2024, 3, 1));
+        budget.categories.push(Category { name: "Еда".to_string(), monthly: rubles(1000), carry_over: CarryOver::Full });
+        budget.expenses.push(expense(date(2024, 3, 31), "Еда", 1100));
+
+        // April starts 100 short and has 30 days
+        let half_up = burn_down(&budget, date(2024, 4, 1), Rounding::HalfUp).unwrap();
+        assert_eq!(half_up.len(), 30);
+        assert_eq!(half_up[0].remaining, rubles(900));
+        assert_eq!(half_up[0].even, Money(Decimal::new(87000, 2)));
+        assert_eq!(half_up[1].even, Money(Decimal::new(84000, 2)));
+
+        budget.categories[0].monthly = rubles(1100);
+        let half_up = burn_down(&budget, date(2024, 4, 1), Rounding::HalfUp).unwrap();
+        let down = burn_down(&budget, date(2024, 4, 1), Rounding::Down).unwrap();
+        // 1100 * 29 / 30 = 1063.333..., 1100 * 28 / 30 = 1026.666...
+        assert_eq!(half_up[0].even, Money(Decimal::new(106333, 2)));
+        assert_eq!(half_up[1].even, Money(Decimal::new(102667, 2)));
+        assert_eq!(down[1].even, Money(Decimal::new(102666, 2)));
+    }
+}
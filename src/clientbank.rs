@@ -0,0 +1,287 @@
+use std::collections::HashMap;
+
+use chrono::NaiveDate;
+
+use crate::{ledger::Transaction, money::{Money, SignPolicy}, statement::{Error, Problem, Statement}};
+
+fn date(value: &str, field: &'static str) -> Result<NaiveDate, Error> {
+    NaiveDate::parse_from_str(value, "%d.%m.%Y").map_err(|_| Error::InvalidField(field))
+}
+
+fn money(value: &str, field: &'static str) -> Result<Money, Error> {
+    Money::try_from(value).map_err(|_| Error::InvalidField(field))
+}
+
+/// One `Секция...` block, keys as they are in the file
+struct Section {
+    line: usize,
+    kind: String,
+    fields: HashMap<String, String>
+}
+
+impl Section {
+    /// Empty values count as missing
+    fn get(&self, key: &str) -> Option<&str> {
+        self.fields.get(key).map(String::as_str).filter(|value| !value.is_empty())
+    }
+
+    fn first(&self, keys: &[&str]) -> Option<&str> {
+        keys.iter().find_map(|key| self.get(key))
+    }
+
+    fn require(&self, key: &'static str) -> Result<&str, Error> {
+        self.get(key).ok_or(Error::MissingField(key))
+    }
+
+    fn optional_date(&self, key: &'static str) -> Result<Option<NaiveDate>, Error> {
+        self.get(key).map(|value| date(value, key)).transpose()
+    }
+
+    fn optional_money(&self, key: &'static str) -> Result<Option<Money>, Error> {
+        self.get(key).map(|value| money(value, key)).transpose()
+    }
+
+    fn problem(&self, error: Error) -> Problem {
+        Problem { line: self.line, section: self.kind.clone(), error }
+    }
+
+    /// A payment order, a memorial order and so on, seen from `accounts`
+    fn transaction(&self, accounts: &[String], currency: &str) -> Result<Transaction, Error> {
+        let sum = SignPolicy::NonNegative
+            .check(money(self.require("Сумма")?, "Сумма")?)
+            .map_err(|_| Error::InvalidField("Сумма"))?;
+
+        let ours = |keys: &[&str]| self.first(keys).is_some_and(|account| accounts.iter().any(|own| own == account));
+        let paid = match (ours(&["ПлательщикСчет", "ПлательщикРасчСчет"]), ours(&["ПолучательСчет", "ПолучательРасчСчет"])) {
+            (true, _) => true,
+            (false, true) => false,
+            // Without a matching account only the dates tell the direction
+            (false, false) => match (self.get("ДатаСписано"), self.get("ДатаПоступило")) {
+                (Some(_), None) => true,
+                (None, Some(_)) => false,
+                _ => return Err(Error::UnknownDirection),
+            },
+        };
+
+        let (date_key, counterparty) = match paid {
+            true => ("ДатаСписано", self.first(&["Получатель1", "Получатель"])),
+            false => ("ДатаПоступило", self.first(&["Плательщик1", "Плательщик"])),
+        };
+        let date = match self.get(date_key) {
+            Some(value) => date(value, date_key)?,
+            None => date(self.require("Дата")?, "Дата")?,
+        };
+
+        let purpose = self.get("НазначениеПлатежа").unwrap_or_default();
+        let note = match self.get("Номер") {
+            Some(number) => format!("{} № {}: {}", self.kind, number, purpose),
+            None => format!("{}: {}", self.kind, purpose),
+        };
+
+        Ok(Transaction {
+            id: None,
+            date,
+            amount: if paid { Money(-sum.0) } else { sum },
+            currency: currency.to_string(),
+            category: String::new(),
+            counterparty: counterparty.unwrap_or_default().to_string(),
+            note: note.trim_end_matches([':', ' ']).to_string(),
+            reference: self.get("Номер").unwrap_or_default().to_string()
+        })
+    }
+}
+
+/// Statement in the 1CClientBankExchange format, the one Russian banks export for 1C.
+/// Only a missing header is an error, broken sections go to `problems`
+pub fn parse(text: &str, currency: &str) -> Result<Statement, Error> {
+    let mut lines = text.lines().enumerate().map(|(index, line)| (index + 1, line.trim()));
+    let first = lines.by_ref().find(|(_, line)| !line.is_empty()).map(|(_, line)| line);
+    if first != Some("1CClientBankExchange") {
+        return Err(Error::UnknownFormat);
+    }
+
+    let mut statement = Statement::default();
+    let mut header = HashMap::new();
+    let mut open: Option<Section> = None;
+    let mut documents = Vec::new();
+    let mut balances = Vec::new();
+    for (number, line) in lines {
+        if line.is_empty() { continue; }
+        let (key, value) = line.split_once('=').map_or((line, ""), |(key, value)| (key.trim(), value.trim()));
+
+        let starts = match key {
+            "СекцияДокумент" => Some(value),
+            "СекцияРасчСчет" => Some("РасчСчет"),
+            _ => None,
+        };
+        let ends = matches!(key, "КонецДокумента" | "КонецРасчСчет" | "КонецФайла");
+        if starts.is_some() || ends {
+            match open.take() {
+                Some(section) if ends && key != "КонецФайла" => match section.kind.as_str() {
+                    "РасчСчет" => balances.push(section),
+                    _ => documents.push(section),
+                },
+                Some(section) => statement.problems.push(section.problem(Error::UnclosedSection)),
+                None => {},
+            }
+            if key == "КонецФайла" { break; }
+            if let Some(kind) = starts {
+                open = Some(Section { line: number, kind: kind.to_string(), fields: HashMap::new() });
+            }
+            continue;
+        }
+
+        match &mut open {
+            Some(section) => { section.fields.insert(key.to_string(), value.to_string()); },
+            None if key == "РасчСчет" => statement.add_account(value),
+            None => { header.insert(key.to_string(), value.to_string()); },
+        }
+    }
+    if let Some(section) = open {
+        statement.problems.push(section.problem(Error::UnclosedSection));
+    }
+
+    let header = Section { line: 1, kind: "1CClientBankExchange".to_string(), fields: header };
+    match (header.optional_date("ДатаНачала"), header.optional_date("ДатаКонца")) {
+        (Ok(from), Ok(to)) => (statement.from, statement.to) = (from, to),
+        (Err(error), _) | (_, Err(error)) => statement.problems.push(header.problem(error)),
+    }
+
+    for section in balances {
+        statement.add_account(section.get("РасчСчет").unwrap_or_default());
+        match (section.optional_money("НачальныйОстаток"), section.optional_money("КонечныйОстаток")) {
+            (Ok(opening), Ok(closing)) => (statement.opening, statement.closing) = (opening, closing),
+            (Err(error), _) | (_, Err(error)) => statement.problems.push(section.problem(error)),
+        }
+    }
+
+    for section in documents {
+        match section.transaction(&statement.accounts, currency) {
+            Ok(transaction) => statement.transactions.push(transaction),
+            Err(error) => statement.problems.push(section.problem(error)),
+        }
+    }
+
+    Ok(statement.finish())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use encoding_rs::WINDOWS_1251;
+    use rust_decimal::Decimal;
+
+    use super::*;
+    use crate::{payment::{self, Party, PaymentOrder}, statement, vat::VatRate};
+
+    const FILE: &str = "1CClientBankExchange\r
+ВерсияФормата=1.03\r
+Кодировка=Windows\r
+ДатаНачала=01.10.2024\r
+ДатаКонца=31.10.2024\r
+РасчСчет=40702810938000000001\r
+СекцияРасчСчет\r
+ДатаНачала=01.10.2024\r
+РасчСчет=40702810938000000001\r
+НачальныйОстаток=10000.00\r
+КонечныйОстаток=8765.50\r
+КонецРасчСчет\r
+СекцияДокумент=Платежное поручение\r
+Номер=17\r
+Дата=01.10.2024\r
+Сумма=1234.50\r
+ПлательщикСчет=40702810938000000001\r
+Плательщик1=ООО Ромашка\r
+ПолучательСчет=40817810000000000002\r
+Получатель1=Иванов Иван Иванович\r
+ДатаСписано=02.10.2024\r
+НазначениеПлатежа=Оплата по счёту № 5\r
+КонецДокумента\r
+СекцияДокумент=Банковский ордер\r
+Номер=3\r
+Дата=03.10.2024\r
+Сумма=сто\r
+КонецДокумента\r
+СекцияДокумент=Платежное поручение\r
+Номер=4\r
+КонецФайла\r
+";
+
+    #[test]
+    fn windows_1251_file() {
+        let bytes = WINDOWS_1251.encode(FILE).0.into_owned();
+        let text = statement::decode(&bytes);
+        assert_eq!(text, FILE);
+
+        let statement = statement::parse(&text, "RUB").unwrap();
+        assert_eq!(statement.accounts, vec!["40702810938000000001".to_string()]);
+        assert_eq!((statement.from, statement.to), (NaiveDate::from_ymd_opt(2024, 10, 1), NaiveDate::from_ymd_opt(2024, 10, 31)));
+        assert_eq!((statement.opening, statement.closing), (Some(Money(Decimal::from(10000))), Some(Money(Decimal::new(876550, 2)))));
+        assert_eq!(statement.transactions, vec![Transaction {
+            id: None,
+            date: NaiveDate::from_ymd_opt(2024, 10, 2).unwrap(),
+            amount: Money(Decimal::new(-123450, 2)),
+            currency: "RUB".to_string(),
+            category: String::new(),
+            counterparty: "Иванов Иван Иванович".to_string(),
+            note: "Платежное поручение № 17: Оплата по счёту № 5".to_string(),
+            reference: "17".to_string()
+        }]);
+        assert_eq!(statement.problems, vec![
+            Problem { line: 24, section: "Банковский ордер".to_string(), error: Error::InvalidField("Сумма") },
+            Problem { line: 29, section: "Платежное поручение".to_string(), error: Error::UnclosedSection },
+        ]);
+    }
+
+    #[test]
+    fn exported_payment_orders_come_back() {
+        let party = |name: &str, inn: &str, account: &str| Party {
+            name: name.to_string(),
+            inn: inn.to_string(),
+            kpp: String::new(),
+            account: account.to_string(),
+            bank: "ПАО Сбербанк г. Москва".to_string(),
+            bik: "044525225".to_string(),
+            correspondent: "30101810400000000225".to_string()
+        };
+        let order = PaymentOrder {
+            number: 17,
+            date: NaiveDate::from_ymd_opt(2024, 10, 1).unwrap(),
+            amount: Money(Decimal::new(150050, 2)),
+            payer: party("ООО Ромашка", "7707083893", "40702810938000000001"),
+            recipient: party("Иванов Иван Иванович", "500100732259", "40817810000000000002"),
+            purpose: "Оплата по счёту № 5".to_string(),
+            vat: VatRate::WithoutVat,
+            priority: 5
+        };
+        let created = NaiveDate::from_ymd_opt(2024, 10, 2).unwrap().and_hms_opt(9, 0, 0).unwrap();
+        let bytes = payment::encode(&payment::exchange(&[order], created).unwrap());
+
+        let statement = statement::parse(&statement::decode(&bytes), "RUB").unwrap();
+        assert_eq!(statement.problems, Vec::new());
+        assert_eq!(statement.transactions, vec![Transaction {
+            id: None,
+            date: NaiveDate::from_ymd_opt(2024, 10, 1).unwrap(),
+            amount: Money(Decimal::new(-150050, 2)),
+            currency: "RUB".to_string(),
+            category: String::new(),
+            counterparty: "Иванов Иван Иванович".to_string(),
+            note: "Платежное поручение № 17: Оплата по счёту № 5 НДС не облагается".to_string(),
+            reference: "17".to_string()
+        }]);
+    }
+
+    #[test]
+    fn income_is_positive() {
+        let text = "1CClientBankExchange\nРасчСчет=40702810938000000001\nСекцияДокумент=Платежное поручение\nНомер=9\nДата=05.10.2024\nСумма=500\nПлательщик=ИП Васильев\nПолучательСчет=40702810938000000001\nКонецДокумента\nКонецФайла\n";
+        let statement = parse(text, "RUB").unwrap();
+        assert_eq!(statement.transactions[0].amount, Money(Decimal::from(500)));
+        assert_eq!(statement.transactions[0].counterparty, "ИП Васильев");
+        assert_eq!(statement.transactions[0].date, NaiveDate::from_ymd_opt(2024, 10, 5).unwrap());
+    }
+
+    #[test]
+    fn header_is_required() {
+        assert_eq!(parse("СекцияДокумент=Платежное поручение\n", "RUB"), Err(Error::UnknownFormat));
+    }
+}
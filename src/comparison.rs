@@ -0,0 +1,71 @@
+use rust_decimal::Decimal;
+
+use crate::{
+    conversion::{Dimension, Offer},
+    money::{Error, Money},
+    unit::Unit
+};
+
+/// Place of one offer in the ranking
+#[derive(Debug,
+    PartialEq, Eq,
+    Clone)]
+pub struct RankedOffer {
+    /// Position of the offer in the slice passed to `rank`
+    pub index: usize,
+    /// Exact price of one base unit
+    pub base_price: Decimal,
+    /// Whole packs that can be bought for the budget
+    pub packs: Unit,
+    pub leftover: Money
+}
+
+/// Result of comparing several offers against one budget
+#[derive(Debug,
+    PartialEq, Eq,
+    Clone)]
+pub struct Ranking {
+    pub dimension: Dimension,
+    /// Cheapest per base unit first
+    pub offers: Vec<RankedOffer>
+}
+
+impl Ranking {
+    pub fn cheapest(&self) -> Option<&RankedOffer> {
+        self.offers.first()
+    }
+}
+
+/// How many whole packs of `offer` fit into `budget`
+pub fn packs_in_budget(offer: &Offer, budget: Money) -> Result<(Unit, Money), Error> {
+    let packs = budget.checked_div(offer.price)?.0.trunc().max(Decimal::ZERO);
+    let packs = Unit(packs.mantissa().unsigned_abs());
+
+    Ok((packs, budget.checked_sub(offer.price.checked_mul(packs)?)?))
+}
+
+/// Orders offers by price per base unit.
+/// All offers must measure the same thing: kilograms can't be ranked against litres
+pub fn rank(offers: &[Offer], budget: Money) -> Result<Ranking, Error> {
+    let Some(first) = offers.first() else {
+        return Err(Error::NoOffers);
+    };
+    let dimension = first.dimension()?;
+
+    let mut ranked = Vec::with_capacity(offers.len());
+    for (index, offer) in offers.iter().enumerate() {
+        if offer.dimension()? != dimension { return Err(Error::IncompatibleUnits); }
+
+        let (packs, leftover) = packs_in_budget(offer, budget)?;
+        ranked.push(RankedOffer {
+            index,
+            base_price: offer.base_price()?,
+            packs,
+            leftover
+        });
+    }
+
+    ranked.sort_by(|a, b| a.base_price.cmp(&b.base_price).then(a.index.cmp(&b.index)));
+
+    Ok(Ranking { dimension, offers: ranked })
+}
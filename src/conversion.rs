@@ -0,0 +1,158 @@
+use rust_decimal::Decimal;
+
+use crate::{measure::MeasureUnit, money::{Error, Money, Rounding}};
+
+/// What a unit measures; only units of the same dimension convert
+#[derive(Debug,
+    PartialEq, Eq,
+    Clone, Copy)]
+pub enum Dimension {
+    Mass,
+    Volume,
+    Length,
+    Count,
+    Time
+}
+
+impl Dimension {
+    /// Unit prices are normalized to it: "за кг", "за л"
+    pub fn base(&self) -> MeasureUnit {
+        match self {
+            Dimension::Mass => MeasureUnit::KILOGRAM,
+            Dimension::Volume => MeasureUnit::LITRE,
+            Dimension::Length => MeasureUnit::METRE,
+            Dimension::Count => MeasureUnit::PIECE,
+            Dimension::Time => MeasureUnit::HOUR,
+        }
+    }
+}
+
+/// Size of a catalogue unit in the base unit of its dimension
+pub struct Conversion {
+    pub measure: MeasureUnit,
+    pub dimension: Dimension,
+    pub factor: Decimal
+}
+
+const fn conversion(measure: MeasureUnit, dimension: Dimension, factor: Decimal) -> Conversion {
+    Conversion { measure, dimension, factor }
+}
+
+pub static TABLE: [Conversion;12] = [
+    conversion(MeasureUnit::GRAM, Dimension::Mass, Decimal::from_parts(1, 0, 0, false, 3)),
+    conversion(MeasureUnit::KILOGRAM, Dimension::Mass, Decimal::ONE),
+    conversion(MeasureUnit::TONNE, Dimension::Mass, Decimal::ONE_THOUSAND),
+    conversion(MeasureUnit::MILLILITRE, Dimension::Volume, Decimal::from_parts(1, 0, 0, false, 3)),
+    conversion(MeasureUnit::LITRE, Dimension::Volume, Decimal::ONE),
+    conversion(MeasureUnit::CENTIMETRE, Dimension::Length, Decimal::from_parts(1, 0, 0, false, 2)),
+    conversion(MeasureUnit::METRE, Dimension::Length, Decimal::ONE),
+    conversion(MeasureUnit::ITEM, Dimension::Count, Decimal::ONE),
+    conversion(MeasureUnit::PIECE, Dimension::Count, Decimal::ONE),
+    conversion(MeasureUnit::PACK, Dimension::Count, Decimal::ZERO),
+    conversion(MeasureUnit::BOX, Dimension::Count, Decimal::ZERO),
+    conversion(MeasureUnit::HOUR, Dimension::Time, Decimal::ONE),
+];
+
+/// Packs and boxes have no fixed size, their factor in `TABLE` is zero
+pub fn needs_pack_size(measure: &MeasureUnit) -> bool {
+    TABLE
+        .iter()
+        .any(|conversion| conversion.measure == *measure && conversion.factor.is_zero())
+}
+
+/// Dimension and size of `measure` in base units.
+/// A pack or a box has no fixed size, so `pack_size` (pieces in it) is needed for it
+pub fn base_factor(measure: &MeasureUnit, pack_size: Option<Decimal>) -> Result<(Dimension, Decimal), Error> {
+    let conversion = TABLE
+        .iter()
+        .find(|conversion| conversion.measure == *measure)
+        .ok_or(Error::IncompatibleUnits)?;
+
+    if conversion.factor.is_zero() {
+        return match pack_size {
+            Some(size) if size > Decimal::ZERO => Ok((conversion.dimension, size)),
+            _ => Err(Error::PackSizeMissing),
+        };
+    }
+
+    Ok((conversion.dimension, conversion.factor))
+}
+
+/// Converts `value` between units of the same dimension, e.g. 400 г -> 0.4 кг
+pub fn convert(value: Decimal, from: &MeasureUnit, to: &MeasureUnit, pack_size: Option<Decimal>) -> Result<Decimal, Error> {
+    if from == to { return Ok(value); }
+
+    let (from_dimension, from_factor) = base_factor(from, pack_size)?;
+    let (to_dimension, to_factor) = base_factor(to, pack_size)?;
+
+    if from_dimension != to_dimension { return Err(Error::IncompatibleUnits); }
+
+    value
+        .checked_mul(from_factor)
+        .and_then(|value| value.checked_div(to_factor))
+        .ok_or(Error::Overflow)
+}
+
+/// Price for some amount of goods: "89.99 за 400 г"
+#[derive(Debug,
+    PartialEq, Eq,
+    Clone)]
+pub struct Offer {
+    pub price: Money,
+    pub amount: Decimal,
+    pub measure: MeasureUnit,
+    /// Pieces per pack, only for offers sold in packs
+    pub pack_size: Option<Decimal>
+}
+
+impl Offer {
+    pub fn dimension(&self) -> Result<Dimension, Error> {
+        Ok(base_factor(&self.measure, self.pack_size)?.0)
+    }
+
+    /// Exact price of one base unit, not rounded so offers compare fairly
+    pub fn base_price(&self) -> Result<Decimal, Error> {
+        let (dimension, _) = base_factor(&self.measure, self.pack_size)?;
+        let amount = convert(self.amount, &self.measure, &dimension.base(), self.pack_size)?;
+
+        Ok(self.price.checked_div(amount)?.0)
+    }
+
+    /// Price of one base unit in kopeks, for display
+    pub fn base_price_rounded(&self, rounding: Rounding) -> Result<Money, Error> {
+        Ok(Money::round(self.base_price()?, rounding))
+    }
+}
+
+/// `Less` when `first` is cheaper per base unit than `second`
+pub fn compare(first: &Offer, second: &Offer) -> Result<std::cmp::Ordering, Error> {
+    if first.dimension()? != second.dimension()? { return Err(Error::IncompatibleUnits); }
+
+    Ok(first.base_price()?.cmp(&second.base_price()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn box_converts_with_its_size() {
+        let per_box = Offer {
+            price: Money(Decimal::from(300)),
+            amount: Decimal::ONE,
+            measure: MeasureUnit::BOX,
+            pack_size: Some(Decimal::from(12))
+        };
+        let per_piece = Offer {
+            price: Money(Decimal::from(30)),
+            amount: Decimal::ONE,
+            measure: MeasureUnit::PIECE,
+            pack_size: None
+        };
+        assert_eq!(per_box.base_price().unwrap(), Decimal::from(25));
+        assert_eq!(compare(&per_box, &per_piece).unwrap(), std::cmp::Ordering::Less);
+
+        let unsized_box = Offer { pack_size: None, ..per_box };
+        assert_eq!(unsized_box.base_price(), Err(Error::PackSizeMissing));
+    }
+}
@@ -0,0 +1,317 @@
+use chrono::{Months, NaiveDate};
+use rust_decimal::Decimal;
+
+use crate::money::{Error, Money, Rounding, SignPolicy};
+
+/// How often interest is added to the balance
+#[derive(Debug,
+    PartialEq, Eq,
+    Clone, Copy,
+    Default)]
+pub enum Capitalization {
+    /// Interest for every day is added the same day
+    Daily,
+    #[default]
+    Monthly,
+    /// Interest accrues monthly and is added every third month
+    Quarterly
+}
+
+impl Capitalization {
+    pub const ALL: [Capitalization;3] = [Capitalization::Daily, Capitalization::Monthly, Capitalization::Quarterly];
+}
+
+#[derive(Debug,
+    PartialEq, Eq,
+    Clone)]
+pub struct Deposit {
+    pub start: NaiveDate,
+    pub initial: Money,
+    /// Added at the start of every month but the first
+    pub top_up: Money,
+    /// Nominal percent per year
+    pub annual_rate: Decimal,
+    pub capitalization: Capitalization,
+    pub months: u32,
+    /// Percent withheld from interest when it is added, zero for none
+    pub tax_rate: Decimal
+}
+
+/// One month of the projection
+#[derive(Debug,
+    PartialEq, Eq,
+    Clone, Copy)]
+pub struct Period {
+    pub number: u32,
+    /// Last day of the month
+    pub end: NaiveDate,
+    pub top_up: Money,
+    /// Interest added this month, before tax
+    pub interest: Money,
+    pub tax: Money,
+    pub balance: Money
+}
+
+#[derive(Debug,
+    PartialEq, Eq,
+    Clone)]
+pub struct Projection {
+    pub periods: Vec<Period>,
+    /// Initial deposit included
+    pub total_top_ups: Money,
+    pub total_interest: Money,
+    pub total_tax: Money
+}
+
+impl Projection {
+    pub fn balance(&self) -> Option<Money> {
+        self.periods.last().map(|period| period.balance)
+    }
+}
+
+impl Deposit {
+    /// Longest term `months_to_reach` looks at and `project` accepts, a hundred years
+    pub const SEARCH_LIMIT: u32 = 1200;
+
+    /// Balance month by month for the whole term
+    pub fn project(&self, rounding: Rounding) -> Result<Projection, Error> {
+        if self.months == 0 { return Err(Error::ZeroTerm); }
+        if self.months > Deposit::SEARCH_LIMIT { return Err(Error::TermTooLong(Deposit::SEARCH_LIMIT)); }
+        self.run(self.months, None, rounding)
+    }
+
+    /// Months until the balance reaches `target`, `None` if it doesn't within `SEARCH_LIMIT`
+    pub fn months_to_reach(&self, target: Money, rounding: Rounding) -> Result<Option<u32>, Error> {
+        if self.initial >= target { return Ok(Some(0)); }
+        // Nothing grows, no point in walking a hundred years
+        if self.annual_rate.is_zero() && self.top_up.0.is_zero() { return Ok(None); }
+
+        let projection = self.run(Deposit::SEARCH_LIMIT, Some(target), rounding)?;
+        Ok(projection.periods
+            .last()
+            .filter(|period| period.balance >= target)
+            .map(|period| period.number))
+    }
+
+    fn run(&self, months: u32, target: Option<Money>, rounding: Rounding) -> Result<Projection, Error> {
+        for percent in [self.annual_rate, self.tax_rate] {
+            if percent.is_sign_negative() && !percent.is_zero() { return Err(Error::NegativeAmount); }
+        }
+        if self.tax_rate > Decimal::ONE_HUNDRED { return Err(Error::OutOfRange); }
+        SignPolicy::NonNegative.check(self.top_up)?;
+
+        let zero = Money(Decimal::ZERO);
+        let rate = self.annual_rate.checked_div(Decimal::ONE_HUNDRED).ok_or(Error::Overflow)?;
+        let mut balance = SignPolicy::NonNegative.check(self.initial)?;
+        let mut accrued = zero;
+        let mut projection = Projection {
+            periods: Vec::new(),
+            total_top_ups: balance,
+            total_interest: zero,
+            total_tax: zero
+        };
+
+        let mut start = self.start;
+        for number in 1..=months {
+            let end = start.checked_add_months(Months::new(1)).ok_or(Error::OutOfRange)?;
+            let top_up = if number == 1 { zero } else { self.top_up };
+            balance = balance.checked_add(top_up)?;
+
+            let mut interest = zero;
+            let mut tax = zero;
+            match self.capitalization {
+                Capitalization::Daily => {
+                    let mut day = start;
+                    while day < end {
+                        let daily = Money::round(balance.checked_mul(rate)?.checked_div(days_in_year(day))?.0, rounding);
+                        let daily_tax = self.tax(daily, rounding)?;
+                        balance = balance.checked_add(daily)?.checked_sub(daily_tax)?;
+                        interest = interest.checked_add(daily)?;
+                        tax = tax.checked_add(daily_tax)?;
+                        day = day.succ_opt().ok_or(Error::OutOfRange)?;
+                    }
+                },
+                Capitalization::Monthly | Capitalization::Quarterly => {
+                    let monthly = balance.checked_mul(rate)?.checked_div(Decimal::from(12))?;
+                    accrued = accrued.checked_add(monthly)?;
+
+                    let credited = self.capitalization == Capitalization::Monthly ||
+                        number % 3 == 0 ||
+                        number == months;
+                    if credited {
+                        interest = Money::round(accrued.0, rounding);
+                        tax = self.tax(interest, rounding)?;
+                        balance = balance.checked_add(interest)?.checked_sub(tax)?;
+                        accrued = zero;
+                    }
+                },
+            }
+
+            projection.total_top_ups = projection.total_top_ups.checked_add(top_up)?;
+            projection.total_interest = projection.total_interest.checked_add(interest)?;
+            projection.total_tax = projection.total_tax.checked_add(tax)?;
+            projection.periods.push(Period {
+                number,
+                end: end.pred_opt().ok_or(Error::OutOfRange)?,
+                top_up,
+                interest,
+                tax,
+                balance
+            });
+
+            if target.is_some_and(|target| balance >= target) { break; }
+            start = end;
+        }
+
+        Ok(projection)
+    }
+
+    fn tax(&self, interest: Money, rounding: Rounding) -> Result<Money, Error> {
+        let tax = interest.checked_mul(self.tax_rate)?.checked_div(Decimal::ONE_HUNDRED)?;
+        Ok(Money::round(tax.0, rounding))
+    }
+}
+
+fn days_in_year(day: NaiveDate) -> Decimal {
+    if day.leap_year() { Decimal::from(366) } else { Decimal::from(365) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rubles(kopeks: i64) -> Money {
+        Money(Decimal::new(kopeks, 2))
+    }
+
+    fn deposit(capitalization: Capitalization, months: u32) -> Deposit {
+        Deposit {
+            start: NaiveDate::from_ymd_opt(2023, 1, 1).unwrap(),
+            initial: rubles(10000000),
+            top_up: rubles(0),
+            annual_rate: Decimal::from(12),
+            capitalization,
+            months,
+            tax_rate: Decimal::ZERO
+        }
+    }
+
+    #[test]
+    fn monthly_interest_compounds() {
+        let projection = deposit(Capitalization::Monthly, 3).project(Rounding::HalfUp).unwrap();
+        let interest: Vec<Money> = projection.periods.iter().map(|period| period.interest).collect();
+        assert_eq!(interest, [rubles(100000), rubles(101000), rubles(102010)]);
+        assert_eq!(projection.balance(), Some(rubles(10303010)));
+        assert_eq!(projection.total_interest, rubles(303010));
+        assert_eq!(projection.periods[0].end, NaiveDate::from_ymd_opt(2023, 1, 31).unwrap());
+        assert_eq!(projection.periods[1].end, NaiveDate::from_ymd_opt(2023, 2, 28).unwrap());
+    }
+
+    #[test]
+    fn quarterly_interest_is_added_every_third_month() {
+        let projection = deposit(Capitalization::Quarterly, 7).project(Rounding::HalfUp).unwrap();
+        let interest: Vec<Money> = projection.periods.iter().map(|period| period.interest).collect();
+        assert_eq!(interest, [
+            rubles(0), rubles(0), rubles(300000),
+            rubles(0), rubles(0), rubles(309000),
+            // The term ends, what has accrued is added
+            rubles(106090)
+        ]);
+        assert_eq!(projection.balance(), Some(rubles(10715090)));
+    }
+
+    #[test]
+    fn more_frequent_capitalization_earns_more() {
+        let balance = |capitalization| deposit(capitalization, 12).project(Rounding::HalfUp).unwrap().balance().unwrap();
+        let daily = balance(Capitalization::Daily);
+        let monthly = balance(Capitalization::Monthly);
+        let quarterly = balance(Capitalization::Quarterly);
+        assert!(daily > monthly, "{daily:?} {monthly:?}");
+        assert!(monthly > quarterly, "{monthly:?} {quarterly:?}");
+    }
+
+    #[test]
+    fn daily_interest_counts_the_days_of_the_year() {
+        // 36.5% a year is a tenth of a percent a day in an ordinary year
+        let mut ordinary = deposit(Capitalization::Daily, 1);
+        ordinary.annual_rate = Decimal::new(365, 1);
+        ordinary.start = NaiveDate::from_ymd_opt(2023, 2, 1).unwrap();
+        let first = ordinary.project(Rounding::HalfUp).unwrap();
+        // 28 days of 0.1% compounded daily, rounded to kopeks every day
+        let mut expected = rubles(10000000);
+        for _ in 0..28 {
+            let daily = Money::round(expected.0 * Decimal::new(1, 3), Rounding::HalfUp);
+            expected = Money(expected.0 + daily.0);
+        }
+        assert_eq!(first.balance(), Some(expected));
+
+        // The same rate pays less per day in a leap year
+        let mut leap = ordinary.clone();
+        leap.start = NaiveDate::from_ymd_opt(2024, 2, 1).unwrap();
+        let leap = leap.project(Rounding::HalfUp).unwrap();
+        assert_eq!(leap.periods[0].end, NaiveDate::from_ymd_opt(2024, 2, 29).unwrap());
+        assert!(leap.periods[0].interest < first.periods[0].interest.checked_mul(Decimal::from(29)).unwrap().checked_div(Decimal::from(28)).unwrap());
+    }
+
+    #[test]
+    fn top_ups_and_tax() {
+        let mut with_top_ups = deposit(Capitalization::Monthly, 2);
+        with_top_ups.top_up = rubles(1000000);
+        with_top_ups.tax_rate = Decimal::from(13);
+        let projection = with_top_ups.project(Rounding::HalfUp).unwrap();
+
+        // The first month is the initial deposit alone
+        assert_eq!(projection.periods[0].top_up, rubles(0));
+        assert_eq!(projection.periods[0].interest, rubles(100000));
+        assert_eq!(projection.periods[0].tax, rubles(13000));
+        assert_eq!(projection.periods[0].balance, rubles(10087000));
+        // The top-up comes at the start of the month and earns for all of it
+        assert_eq!(projection.periods[1].top_up, rubles(1000000));
+        assert_eq!(projection.periods[1].interest, rubles(110870));
+        assert_eq!(projection.periods[1].tax, rubles(14413));
+        assert_eq!(projection.total_top_ups, rubles(11000000));
+        assert_eq!(projection.total_tax, rubles(27413));
+    }
+
+    #[test]
+    fn bad_terms_are_refused() {
+        assert_eq!(deposit(Capitalization::Monthly, 0).project(Rounding::HalfUp), Err(Error::ZeroTerm));
+        assert_eq!(deposit(Capitalization::Monthly, Deposit::SEARCH_LIMIT + 1).project(Rounding::HalfUp),
+            Err(Error::TermTooLong(Deposit::SEARCH_LIMIT)));
+        assert_eq!(deposit(Capitalization::Monthly, u32::MAX).project(Rounding::HalfUp),
+            Err(Error::TermTooLong(Deposit::SEARCH_LIMIT)));
+
+        let mut low = deposit(Capitalization::Monthly, 12);
+        low.annual_rate = Decimal::ZERO;
+        low.initial = rubles(100000);
+        assert_eq!(low.project(Rounding::HalfUp).unwrap().periods.len(), 12);
+        let longest = Deposit { months: Deposit::SEARCH_LIMIT, ..low.clone() };
+        assert_eq!(longest.project(Rounding::HalfUp).unwrap().periods.len(), Deposit::SEARCH_LIMIT as usize);
+
+        let mut negative = deposit(Capitalization::Monthly, 12);
+        negative.annual_rate = Decimal::from(-1);
+        assert_eq!(negative.project(Rounding::HalfUp), Err(Error::NegativeAmount));
+        let mut taxed = deposit(Capitalization::Monthly, 12);
+        taxed.tax_rate = Decimal::from(101);
+        assert_eq!(taxed.project(Rounding::HalfUp), Err(Error::OutOfRange));
+    }
+
+    #[test]
+    fn months_to_reach() {
+        let deposit = deposit(Capitalization::Monthly, 12);
+        assert_eq!(deposit.months_to_reach(rubles(10000000), Rounding::HalfUp), Ok(Some(0)));
+        assert_eq!(deposit.months_to_reach(rubles(10100000), Rounding::HalfUp), Ok(Some(1)));
+        assert_eq!(deposit.months_to_reach(rubles(10303010), Rounding::HalfUp), Ok(Some(3)));
+        assert_eq!(deposit.months_to_reach(rubles(10303011), Rounding::HalfUp), Ok(Some(4)));
+
+        // Nothing grows
+        let still = Deposit { annual_rate: Decimal::ZERO, ..deposit.clone() };
+        assert_eq!(still.months_to_reach(rubles(20000000), Rounding::HalfUp), Ok(None));
+        // Grows, but not within a hundred years
+        let slow = Deposit { annual_rate: Decimal::new(1, 2), ..deposit.clone() };
+        assert_eq!(slow.months_to_reach(rubles(20000000), Rounding::HalfUp), Ok(None));
+        // Top-ups alone get there, the last month is the one that crosses
+        let saving = Deposit { annual_rate: Decimal::ZERO, top_up: rubles(1000000), ..deposit };
+        assert_eq!(saving.months_to_reach(rubles(15000000), Rounding::HalfUp), Ok(Some(6)));
+    }
+}
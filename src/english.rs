@@ -0,0 +1,111 @@
+use rust_decimal::{prelude::ToPrimitive, Decimal};
+
+use crate::money::{Error, Money, Sign};
+
+const ONES: [&str;20] = [
+    "", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+    "ten", "eleven", "twelve", "thirteen", "fourteen", "fifteen", "sixteen",
+    "seventeen", "eighteen", "nineteen"
+];
+
+const TENS: [&str;10] = [
+    "", "", "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety"
+];
+
+/// Short scale, as used in English: every power of a thousand has its own name
+const SCALES: [&str;22] = [
+    "", "thousand", "million", "billion", "trillion", "quadrillion",
+    "quintillion", "sextillion", "septillion", "octillion", "nonillion",
+    "decillion", "undecillion", "duodecillion", "tredecillion",
+    "quattuordecillion", "quindecillion", "sexdecillion", "septendecillion",
+    "octodecillion", "novemdecillion", "vigintillion"
+];
+
+/// 0..999 in words, empty for zero
+fn pronounce_triplet(number: u128) -> String {
+    let mut words: Vec<String> = Vec::new();
+
+    let hundreds = (number / 100) as usize;
+    let rest = (number % 100) as usize;
+
+    if hundreds != 0 {
+        words.push(format!("{} hundred", ONES[hundreds]));
+    }
+
+    match rest {
+        0 => (),
+        1..20 => words.push(ONES[rest].to_string()),
+        _ => match rest % 10 {
+            0 => words.push(TENS[rest / 10].to_string()),
+            units => words.push(format!("{}-{}", TENS[rest / 10], ONES[units])),
+        },
+    }
+
+    words.join(" ")
+}
+
+/// "one thousand two hundred thirty-four"
+pub fn pronounce_number(number: u128) -> Result<String, Error> {
+    if number == 0 { return Ok("zero".to_string()); }
+
+    let mut triplets = Vec::new();
+    let mut rest = number;
+    while rest != 0 {
+        triplets.push(rest % 1000);
+        rest /= 1000;
+    }
+
+    let mut words: Vec<String> = Vec::new();
+    for (pos, triplet) in triplets.iter().enumerate().rev() {
+        if *triplet == 0 { continue; }
+
+        let scale = SCALES.get(pos).ok_or(Error::NumberTooLarge)?;
+        words.push(pronounce_triplet(*triplet));
+        if !scale.is_empty() {
+            words.push(scale.to_string());
+        }
+    }
+
+    Ok(words.join(" "))
+}
+
+/// "one point two five", digits after the point are read one by one
+pub fn pronounce_decimal(value: Decimal) -> Result<String, Error> {
+    let value = value.normalize();
+    let whole = value.trunc().abs().to_u128().ok_or(Error::NumberTooLarge)?;
+    let mut words = vec![pronounce_number(whole)?];
+
+    if value.scale() > 0 {
+        words.push("point".to_string());
+        let fraction = value.fract().abs().mantissa().to_string();
+        let zeros = value.scale() as usize - fraction.len();
+        for digit in "0".repeat(zeros).chars().chain(fraction.chars()) {
+            words.push(match digit {
+                '0' => "zero".to_string(),
+                digit => ONES[digit as usize - '0' as usize].to_string(),
+            });
+        }
+    }
+
+    Ok(words.join(" "))
+}
+
+/// "minus twelve rubles five kopecks"
+pub fn pronounce_money(money: &Money) -> Result<String, Error> {
+    let (sign, rubles, kopeks) = money.decompose();
+    let mut words: Vec<String> = Vec::new();
+
+    if sign == Sign::Negative {
+        words.push("minus".to_string());
+    }
+
+    words.push(pronounce_number(rubles)?);
+    words.push(if rubles == 1 { "ruble" } else { "rubles" }.to_string());
+
+    if kopeks != 0 {
+        words.push(pronounce_number(kopeks as u128)?);
+        words.push(if kopeks == 1 { "kopeck" } else { "kopecks" }.to_string());
+    }
+
+    Ok(words.join(" "))
+}
@@ -0,0 +1,315 @@
+use chrono::NaiveDate;
+use eframe::egui::TextBuffer;
+use rust_decimal::Decimal;
+
+use crate::{money::{Error, Money, Rounding}, quantity::Quantity, unit::Unit};
+
+/// Value that can be typed into a `Field`
+pub trait FieldValue: Sized {
+    /// `previous` carries settings the text doesn't, e.g. quantity precision
+    fn parse(text: &str, previous: &Self) -> Result<Self, Error>;
+    fn format(&self) -> String;
+
+    /// Closest valid value for text that was rejected, if there is an obvious one
+    fn fix(_text: &str, _previous: &Self, _rounding: Rounding) -> Option<Self> {
+        None
+    }
+}
+
+impl FieldValue for Money {
+    fn parse(text: &str, _previous: &Self) -> Result<Self, Error> {
+        Money::try_from(text.replace(',', ".").as_str())
+    }
+
+    fn format(&self) -> String {
+        self.0.to_string()
+    }
+
+    /// "12.345" -> "12.35"
+    fn fix(text: &str, _previous: &Self, rounding: Rounding) -> Option<Self> {
+        let value = Decimal::from_str_exact(&text.replace(',', ".")).ok()?;
+        Some(Money::round(value, rounding))
+    }
+}
+
+impl FieldValue for Quantity {
+    fn parse(text: &str, previous: &Self) -> Result<Self, Error> {
+        Quantity::parse(text, previous.precision())
+    }
+
+    fn format(&self) -> String {
+        self.value().to_string()
+    }
+
+    /// "1.2345" with precision 3 -> "1.235"
+    fn fix(text: &str, previous: &Self, rounding: Rounding) -> Option<Self> {
+        let value = Decimal::from_str_exact(&text.replace(',', ".")).ok()?;
+        let precision = previous.precision();
+        Quantity::new(value.round_dp_with_strategy(precision, rounding.strategy()), precision).ok()
+    }
+}
+
+impl FieldValue for Unit {
+    fn parse(text: &str, _previous: &Self) -> Result<Self, Error> {
+        text.parse::<u128>().map(Unit).map_err(|_| Error::InvalidNumber)
+    }
+
+    fn format(&self) -> String {
+        self.0.to_string()
+    }
+}
+
+impl FieldValue for Decimal {
+    fn parse(text: &str, _previous: &Self) -> Result<Self, Error> {
+        Ok(Decimal::from_str_exact(&text.replace(',', "."))?)
+    }
+
+    fn format(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl FieldValue for NaiveDate {
+    /// "31.12.2024", ISO "2024-12-31" is accepted too
+    fn parse(text: &str, _previous: &Self) -> Result<Self, Error> {
+        NaiveDate::parse_from_str(text, "%d.%m.%Y")
+            .or_else(|_| NaiveDate::parse_from_str(text, "%Y-%m-%d"))
+            .map_err(|_| Error::InvalidDate)
+    }
+
+    fn format(&self) -> String {
+        self.format("%d.%m.%Y").to_string()
+    }
+}
+
+/// `None` for blank text, for inputs that may be left empty like filters
+pub fn optional<T: FieldValue + Default>(text: &str) -> Result<Option<T>, Error> {
+    match text.trim() {
+        "" => Ok(None),
+        text => T::parse(text, &T::default()).map(Some),
+    }
+}
+
+pub fn optional_text(text: &str) -> Option<String> {
+    match text.trim() {
+        "" => None,
+        text => Some(text.to_string()),
+    }
+}
+
+/// Text as the user typed it together with the last valid value.
+/// Unfinished input like "", "-" or "12." keeps the previous value
+/// without complaining, wrong input keeps it and remembers the error
+#[derive(Debug,
+    PartialEq,
+    Clone)]
+pub struct Field<T> {
+    text: String,
+    value: T,
+    error: Option<Error>
+}
+
+impl<T: FieldValue> Field<T> {
+    pub fn new(value: T) -> Self {
+        Field { text: value.format(), value, error: None }
+    }
+
+    pub fn value(&self) -> &T {
+        &self.value
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Why the current text was not accepted
+    pub fn error(&self) -> Option<&Error> {
+        self.error.as_ref()
+    }
+
+    /// Applies `FieldValue::fix` to the rejected text, `false` if there is no fix
+    pub fn fix(&mut self, rounding: Rounding) -> bool {
+        if self.error.is_none() { return false; }
+
+        match T::fix(self.text.trim(), &self.value, rounding) {
+            Some(value) => {
+                self.set(value);
+                true
+            },
+            None => false,
+        }
+    }
+
+    pub fn can_fix(&self, rounding: Rounding) -> bool {
+        self.error.is_some() && T::fix(self.text.trim(), &self.value, rounding).is_some()
+    }
+
+    /// Replaces both the value and the text
+    pub fn set(&mut self, value: T) {
+        *self = Field::new(value);
+    }
+
+    fn is_unfinished(text: &str) -> bool {
+        matches!(text, "" | "-") || text.ends_with(['.', ','])
+    }
+
+    fn reparse(&mut self) {
+        let text = self.text.trim();
+        if Field::<T>::is_unfinished(text) {
+            self.error = None;
+            return;
+        }
+
+        match T::parse(text, &self.value) {
+            Ok(value) => {
+                self.value = value;
+                self.error = None;
+            },
+            Err(error) => self.error = Some(error),
+        }
+    }
+}
+
+impl<T: FieldValue + Default> Default for Field<T> {
+    fn default() -> Self {
+        Field::new(T::default())
+    }
+}
+
+impl<T: FieldValue> TextBuffer for Field<T> {
+    fn is_mutable(&self) -> bool {
+        true
+    }
+
+    fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    fn insert_text(&mut self, text: &str, char_index: usize) -> usize {
+        let inserted = self.text.insert_text(text, char_index);
+        self.reparse();
+        inserted
+    }
+
+    fn delete_char_range(&mut self, char_range: std::ops::Range<usize>) {
+        self.text.delete_char_range(char_range);
+        self.reparse();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Types `text` over whatever the field holds, as a person selecting all and typing would
+    fn retype<T: FieldValue>(field: &mut Field<T>, text: &str) {
+        let length = field.as_str().chars().count();
+        field.delete_char_range(0..length);
+        for (i, c) in text.chars().enumerate() {
+            field.insert_text(&c.to_string(), i);
+        }
+    }
+
+    #[test]
+    fn unfinished_input_keeps_the_value() {
+        let mut price = Field::new(Money(Decimal::from(5)));
+        for text in ["", "-"] {
+            retype(&mut price, text);
+            assert_eq!(price.text(), text);
+            assert_eq!(price.value(), &Money(Decimal::from(5)));
+            assert_eq!(price.error(), None);
+        }
+        // "12" on the way was valid, the separator after it changes nothing
+        for text in ["12.", "12,"] {
+            retype(&mut price, text);
+            assert_eq!(price.value(), &Money(Decimal::from(12)));
+            assert_eq!(price.error(), None);
+        }
+
+        retype(&mut price, "12,5");
+        assert_eq!(price.value(), &Money(Decimal::new(125, 1)));
+        assert_eq!(price.text(), "12,5");
+    }
+
+    #[test]
+    fn values_are_parsed_as_typed() {
+        let mut date = Field::new(NaiveDate::from_ymd_opt(2024, 1, 1).unwrap());
+        assert_eq!(date.text(), "01.01.2024");
+        retype(&mut date, "31.12.2024");
+        assert_eq!(date.value(), &NaiveDate::from_ymd_opt(2024, 12, 31).unwrap());
+        retype(&mut date, "2025-02-28");
+        assert_eq!(date.value(), &NaiveDate::from_ymd_opt(2025, 2, 28).unwrap());
+
+        let mut count = Field::new(Unit(1));
+        retype(&mut count, "250");
+        assert_eq!(count.value(), &Unit(250));
+
+        // Precision is not part of the text, it comes from the previous value
+        let mut weight = Field::new(Quantity::new(Decimal::ONE, 3).unwrap());
+        retype(&mut weight, "0,125");
+        assert_eq!(weight.value(), &Quantity::new(Decimal::new(125, 3), 3).unwrap());
+
+        let mut rate = Field::<Decimal>::default();
+        retype(&mut rate, "7,5");
+        assert_eq!(rate.value(), &Decimal::new(75, 1));
+
+        rate.set(Decimal::from(16));
+        assert_eq!(rate.text(), "16");
+    }
+
+    #[test]
+    fn wrong_input_is_remembered_with_the_last_value() {
+        let mut price = Field::new(Money(Decimal::from(5)));
+        retype(&mut price, "5x");
+        assert_eq!(price.value(), &Money(Decimal::from(5)));
+        assert!(matches!(price.error(), Some(Error::RustDecimalError(_))));
+        assert!(!price.can_fix(Rounding::HalfUp));
+        assert!(!price.fix(Rounding::HalfUp));
+        assert_eq!(price.text(), "5x");
+
+        retype(&mut price, "50");
+        assert_eq!(price.error(), None);
+
+        let mut date = Field::new(NaiveDate::from_ymd_opt(2024, 1, 1).unwrap());
+        retype(&mut date, "30.02.2024");
+        assert_eq!(date.error(), Some(&Error::InvalidDate));
+
+        let mut count = Field::new(Unit(1));
+        retype(&mut count, "1.5");
+        assert_eq!(count.error(), Some(&Error::InvalidNumber));
+        assert!(!count.can_fix(Rounding::HalfUp));
+    }
+
+    #[test]
+    fn too_many_digits_are_rounded_on_request() {
+        let mut price = Field::new(Money(Decimal::ZERO));
+        retype(&mut price, "12,345");
+        assert_eq!(price.error(), Some(&Error::KopekDigitsTooBig));
+        assert!(price.can_fix(Rounding::HalfUp));
+        assert!(price.fix(Rounding::HalfUp));
+        assert_eq!(price.value(), &Money(Decimal::new(1235, 2)));
+        assert_eq!(price.text(), "12.35");
+        assert_eq!(price.error(), None);
+        // Nothing left to fix
+        assert!(!price.fix(Rounding::HalfUp));
+
+        retype(&mut price, "12.345");
+        assert!(price.fix(Rounding::Down));
+        assert_eq!(price.value(), &Money(Decimal::new(1234, 2)));
+
+        let mut weight = Field::new(Quantity::new(Decimal::ONE, 3).unwrap());
+        retype(&mut weight, "1,2345");
+        assert_eq!(weight.error(), Some(&Error::QuantityDigitsTooBig(3)));
+        assert!(weight.fix(Rounding::HalfEven));
+        assert_eq!(weight.value(), &Quantity::new(Decimal::new(1234, 3), 3).unwrap());
+    }
+
+    #[test]
+    fn blank_optional_input_is_none() {
+        assert_eq!(optional::<NaiveDate>("  "), Ok(None));
+        assert_eq!(optional::<NaiveDate>("01.03.2024"), Ok(NaiveDate::from_ymd_opt(2024, 3, 1)));
+        assert_eq!(optional::<NaiveDate>("1 марта"), Err(Error::InvalidDate));
+        assert_eq!(optional_text(" \t"), None);
+        assert_eq!(optional_text(" Еда "), Some("Еда".to_string()));
+    }
+}
@@ -0,0 +1,159 @@
+use chrono::{DateTime, Local};
+
+/// State saved at some moment
+#[derive(Debug,
+    Clone)]
+pub struct Snapshot<T> {
+    pub state: T,
+    pub time: DateTime<Local>
+}
+
+/// Undo/redo stacks and a journal of past states.
+/// Only finished edits are recorded, so all the keystrokes of one number form a single step
+pub struct History<T> {
+    current: T,
+    undo: Vec<T>,
+    redo: Vec<T>,
+    journal: Vec<Snapshot<T>>,
+    limit: usize
+}
+
+impl<T: Clone + PartialEq> History<T> {
+    pub fn new(initial: T, limit: usize) -> Self {
+        History {
+            journal: vec![Snapshot { state: initial.clone(), time: Local::now() }],
+            current: initial,
+            undo: Vec::new(),
+            redo: Vec::new(),
+            limit
+        }
+    }
+
+    /// Records the state after a finished edit, nothing happens if it didn't change
+    pub fn commit(&mut self, state: &T) {
+        if self.current == *state { return; }
+
+        self.undo.push(std::mem::replace(&mut self.current, state.clone()));
+        self.redo.clear();
+        self.push_journal(Snapshot { state: state.clone(), time: Local::now() });
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// State to go back to, `None` if there is nothing to undo
+    pub fn undo(&mut self) -> Option<T> {
+        let previous = self.undo.pop()?;
+        self.redo.push(std::mem::replace(&mut self.current, previous.clone()));
+
+        Some(previous)
+    }
+
+    pub fn redo(&mut self) -> Option<T> {
+        let next = self.redo.pop()?;
+        self.undo.push(std::mem::replace(&mut self.current, next.clone()));
+
+        Some(next)
+    }
+
+    /// Past states, oldest first
+    pub fn journal(&self) -> &[Snapshot<T>] {
+        &self.journal
+    }
+
+    /// Brings back a journal entry, the jump itself can be undone
+    pub fn restore(&mut self, index: usize) -> Option<T> {
+        let state = self.journal.get(index)?.state.clone();
+
+        self.undo.push(std::mem::replace(&mut self.current, state.clone()));
+        self.redo.clear();
+
+        Some(state)
+    }
+
+    fn push_journal(&mut self, snapshot: Snapshot<T>) {
+        self.journal.push(snapshot);
+
+        if self.journal.len() > self.limit {
+            self.journal.remove(0);
+        }
+        if self.undo.len() > self.limit {
+            self.undo.remove(0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn states(history: &History<u32>) -> Vec<u32> {
+        history.journal().iter().map(|snapshot| snapshot.state).collect()
+    }
+
+    #[test]
+    fn undo_and_redo() {
+        let mut history = History::new(0, 10);
+        history.commit(&0);
+        assert!(!history.can_undo());
+
+        history.commit(&1);
+        history.commit(&2);
+        assert_eq!(history.undo(), Some(1));
+        assert_eq!(history.undo(), Some(0));
+        assert_eq!(history.undo(), None);
+        assert_eq!(history.redo(), Some(1));
+        assert!(history.can_redo());
+
+        // A new edit drops what could be redone
+        history.commit(&5);
+        assert!(!history.can_redo());
+        assert_eq!(history.redo(), None);
+        assert_eq!(history.undo(), Some(1));
+    }
+
+    #[test]
+    fn unchanged_state_is_not_a_step() {
+        let mut history = History::new(0, 10);
+        history.commit(&1);
+        history.commit(&1);
+        history.commit(&1);
+        assert_eq!(states(&history), [0, 1]);
+        assert_eq!(history.undo(), Some(0));
+        assert!(!history.can_undo());
+    }
+
+    #[test]
+    fn journal_entries_are_restored() {
+        let mut history = History::new(0, 10);
+        history.commit(&1);
+        history.commit(&2);
+        assert_eq!(states(&history), [0, 1, 2]);
+
+        assert_eq!(history.restore(0), Some(0));
+        assert_eq!(history.restore(7), None);
+        // The jump itself is undone
+        assert_eq!(history.undo(), Some(2));
+        assert_eq!(history.undo(), Some(1));
+        // Undoing doesn't rewrite the journal
+        assert_eq!(states(&history), [0, 1, 2]);
+    }
+
+    #[test]
+    fn oldest_states_are_dropped() {
+        let mut history = History::new(0, 3);
+        for state in 1..=5 {
+            history.commit(&state);
+        }
+        assert_eq!(states(&history), [3, 4, 5]);
+        assert_eq!(history.undo(), Some(4));
+        assert_eq!(history.undo(), Some(3));
+        assert_eq!(history.undo(), Some(2));
+        assert_eq!(history.undo(), None);
+    }
+}
@@ -0,0 +1,380 @@
+use fluent::{FluentArgs, FluentBundle, FluentResource, FluentValue};
+
+use crate::{
+    budget::CarryOver,
+    chart::Bucket,
+    deposit::Capitalization,
+    english,
+    ledger,
+    invoice::{self, Kind},
+    money::{Error, Rounding},
+    measure::MeasureUnit,
+    payment,
+    pronounce::Language,
+    qr,
+    quantity::{MeasuredQuantity, Quantity},
+    receipt::{self, Operation},
+    recurring::Frequency,
+    statement,
+    vat::{Vat, VatMode, VatRate}
+};
+
+/// Catalogs are built into the binary, so the app runs from any directory
+fn catalog(language: Language) -> &'static str {
+    match language {
+        Language::Russian => include_str!("../locales/ru/main.ftl"),
+        Language::English => include_str!("../locales/en/main.ftl"),
+    }
+}
+
+/// Interface strings in the current language.
+/// Messages missing from a catalog fall back to Russian, then to the message id
+pub struct Localizer {
+    language: Language,
+    bundles: Vec<(Language, FluentBundle<FluentResource>)>
+}
+
+impl Localizer {
+    pub fn new(language: Language) -> Self {
+        let bundles = Language::ALL
+            .into_iter()
+            .map(|language| {
+                let resource = FluentResource::try_new(catalog(language).to_string())
+                    .expect("built-in catalog is valid Fluent");
+                let mut bundle = FluentBundle::new(vec![
+                    language.code().parse().expect("language code is a valid tag")
+                ]);
+                // Isolation marks show up as boxes in egui
+                bundle.set_use_isolating(false);
+                bundle.add_resource(resource).expect("built-in catalog has no duplicate messages");
+
+                (language, bundle)
+            })
+            .collect();
+
+        Localizer { language, bundles }
+    }
+
+    pub fn language(&self) -> Language {
+        self.language
+    }
+
+    pub fn set_language(&mut self, language: Language) {
+        self.language = language;
+    }
+
+    pub fn tr(&self, id: &str) -> String {
+        self.format(id, None)
+    }
+
+    pub fn tr_args(&self, id: &str, args: &[(&str, String)]) -> String {
+        let mut fluent_args = FluentArgs::new();
+        for (name, value) in args {
+            fluent_args.set(*name, value.clone());
+        }
+
+        self.format(id, Some(&fluent_args))
+    }
+
+    fn format(&self, id: &str, args: Option<&FluentArgs>) -> String {
+        self.format_in(self.language, id, args)
+    }
+
+    fn format_in(&self, language: Language, id: &str, args: Option<&FluentArgs>) -> String {
+        for language in [language, Language::Russian] {
+            let Some((_, bundle)) = self.bundles.iter().find(|(bundle_language, _)| *bundle_language == language) else {
+                continue;
+            };
+            let Some(pattern) = bundle.get_message(id).and_then(|message| message.value()) else {
+                continue;
+            };
+
+            let mut errors = Vec::new();
+            return bundle.format_pattern(pattern, args, &mut errors).into_owned();
+        }
+
+        id.to_string()
+    }
+
+    pub fn error(&self, error: &impl Localized) -> String {
+        error.localize(self)
+    }
+
+    pub fn rounding(&self, rounding: Rounding) -> String {
+        match rounding {
+            Rounding::HalfUp => self.tr("rounding-half-up"),
+            Rounding::HalfEven => self.tr("rounding-half-even"),
+            Rounding::Down => self.tr("rounding-down"),
+            Rounding::Up => self.tr("rounding-up"),
+        }
+    }
+
+    pub fn capitalization(&self, capitalization: Capitalization) -> String {
+        match capitalization {
+            Capitalization::Daily => self.tr("capitalization-daily"),
+            Capitalization::Monthly => self.tr("capitalization-monthly"),
+            Capitalization::Quarterly => self.tr("capitalization-quarterly"),
+        }
+    }
+
+    pub fn carry_over(&self, carry_over: CarryOver) -> String {
+        match carry_over {
+            CarryOver::None => self.tr("carry-over-none"),
+            CarryOver::Full => self.tr("carry-over-full"),
+            CarryOver::SurplusOnly => self.tr("carry-over-surplus"),
+        }
+    }
+
+    pub fn bucket(&self, bucket: Bucket) -> String {
+        match bucket {
+            Bucket::Day => self.tr("bucket-day"),
+            Bucket::Week => self.tr("bucket-week"),
+            Bucket::Month => self.tr("bucket-month"),
+        }
+    }
+
+    pub fn operation(&self, operation: Operation) -> String {
+        match operation {
+            Operation::Income => self.tr("operation-income"),
+            Operation::IncomeReturn => self.tr("operation-income-return"),
+            Operation::Expense => self.tr("operation-expense"),
+            Operation::ExpenseReturn => self.tr("operation-expense-return"),
+        }
+    }
+
+    pub fn frequency(&self, frequency: Frequency) -> String {
+        match frequency {
+            Frequency::Daily => self.tr("frequency-daily"),
+            Frequency::Weekly => self.tr("frequency-weekly"),
+            Frequency::Monthly => self.tr("frequency-monthly"),
+            Frequency::Yearly => self.tr("frequency-yearly"),
+        }
+    }
+
+    pub fn invoice_kind(&self, kind: Kind) -> String {
+        match kind {
+            Kind::Invoice => self.tr("invoice-kind-invoice"),
+            Kind::Act => self.tr("invoice-kind-act"),
+        }
+    }
+
+    /// Abbreviation, "кг"; the user's own units keep theirs
+    pub fn measure_short(&self, measure: &MeasureUnit) -> String {
+        match measure.id.is_empty() {
+            true => measure.short.to_string(),
+            false => self.tr(&format!("measure-{}", measure.id)),
+        }
+    }
+
+    /// "килограмм", as the unit is named in a list
+    pub fn measure_name(&self, measure: &MeasureUnit) -> String {
+        match measure.id.is_empty() {
+            true => measure.forms[0].to_string(),
+            false => self.tr(&format!("measure-{}-name", measure.id)),
+        }
+    }
+
+    /// Quantity in words in `language`, which may differ from the interface:
+    /// "одна целая двадцать пять сотых килограмма", "one point two five kilograms"
+    pub fn quantity(&self, quantity: &Quantity, measure: &MeasureUnit, language: Language) -> String {
+        if language == Language::Russian {
+            return MeasuredQuantity(*quantity, measure).to_string();
+        }
+
+        let value = quantity.value().normalize().to_string();
+        let noun = match measure.id.is_empty() {
+            true => measure.short.to_string(),
+            false => {
+                let mut args = FluentArgs::new();
+                args.set("count", FluentValue::try_number(&value));
+                self.format_in(language, &format!("measure-{}-count", measure.id), Some(&args))
+            },
+        };
+        match english::pronounce_decimal(quantity.value()) {
+            Ok(words) => format!("{} {}", words, noun),
+            Err(_) => format!("{} {}", value, noun),
+        }
+    }
+
+    pub fn vat_rate(&self, rate: VatRate) -> String {
+        match rate.percent() {
+            Some(percent) => self.tr_args("vat-percent", &[("percent", percent.normalize().to_string())]),
+            None => self.tr("vat-none"),
+        }
+    }
+
+    /// "в том числе НДС 20% — ..." when VAT is inside the amount, "плюс НДС 20% — ..." when it is on top.
+    /// `amount` is the VAT already written out, in words or digits
+    pub fn vat(&self, vat: &Vat, amount: String) -> String {
+        let Some(percent) = vat.rate.percent() else {
+            return self.tr("vat-none");
+        };
+        let id = match vat.mode {
+            VatMode::Extract => "vat-included",
+            VatMode::Add => "vat-added",
+        };
+        self.tr_args(id, &[("percent", percent.normalize().to_string()), ("amount", amount)])
+    }
+
+    /// What a statement import did, `count` of its transactions were new to the ledger,
+    /// then a line for every problem
+    pub fn imported(&self, statement: &statement::Statement, count: usize) -> String {
+        let mut lines = vec![self.tr_args("ledger-imported", &[
+            ("count", count.to_string()),
+            ("duplicates", (statement.transactions.len() - count).to_string()),
+        ])];
+        lines.extend(statement.problems.iter().map(|problem| self.tr_args("ledger-problem", &[
+            ("line", problem.line.to_string()),
+            ("section", problem.section.clone()),
+            ("details", self.error(&problem.error)),
+        ])));
+        lines.join("\n")
+    }
+}
+
+/// Errors the user can be told about in the interface language
+pub trait Localized {
+    fn localize(&self, tr: &Localizer) -> String;
+}
+
+impl Localized for Error {
+    fn localize(&self, tr: &Localizer) -> String {
+        match self {
+            Error::KopekDigitsTooBig => tr.tr("error-kopek-digits"),
+            Error::QuantityDigitsTooBig(precision) => tr.tr_args("error-quantity-digits", &[("precision", precision.to_string())]),
+            Error::PrecisionTooBig(max) => tr.tr_args("error-precision", &[("max", max.to_string())]),
+            Error::NegativeQuantity => tr.tr("error-negative-quantity"),
+            Error::IncompatibleUnits => tr.tr("error-incompatible-units"),
+            Error::PackSizeMissing => tr.tr("error-pack-size-missing"),
+            Error::DivisionByZero => tr.tr("error-division-by-zero"),
+            Error::NoOffers => tr.tr("error-no-offers"),
+            Error::NegativeAmount => tr.tr("error-negative-amount"),
+            Error::NumberTooLarge => tr.tr("error-number-too-large"),
+            Error::Overflow => tr.tr("error-overflow"),
+            Error::OutOfRange |
+            Error::RustDecimalError(rust_decimal::Error::ExceedsMaximumPossibleValue) |
+            Error::RustDecimalError(rust_decimal::Error::LessThanMinimumPossibleValue) => tr.tr("error-out-of-range"),
+            Error::InvalidNumber => tr.tr("error-invalid-number"),
+            Error::NoParticipants => tr.tr("error-no-participants"),
+            Error::UnbalancedPayments => tr.tr("error-unbalanced-payments"),
+            Error::ZeroTerm => tr.tr("error-zero-term"),
+            Error::TermTooLong(months) => tr.tr_args("error-term-too-long", &[("months", months.to_string())]),
+            Error::InvalidDate => tr.tr("error-invalid-date"),
+            Error::ZeroInterval => tr.tr("error-zero-interval"),
+            Error::RustDecimalError(details) => tr.tr_args("error-not-a-number", &[("details", details.to_string())]),
+        }
+    }
+}
+
+impl Localized for ledger::Error {
+    fn localize(&self, tr: &Localizer) -> String {
+        match self {
+            ledger::Error::Storage(error) => tr.tr_args("error-storage", &[("details", error.to_string())]),
+            ledger::Error::NewerSchema(version) => tr.tr_args("error-newer-schema", &[("version", version.to_string())]),
+            ledger::Error::MixedCurrencies => tr.tr("error-mixed-currencies"),
+            ledger::Error::Money(error) => error.localize(tr),
+        }
+    }
+}
+
+impl Localized for invoice::Error {
+    fn localize(&self, tr: &Localizer) -> String {
+        match self {
+            invoice::Error::File(details) => tr.tr_args("error-file", &[("details", details.clone())]),
+            invoice::Error::InvalidTemplateLine => tr.tr("error-invalid-template-line"),
+            invoice::Error::NoItems => tr.tr("error-no-items"),
+            invoice::Error::Font(details) => tr.tr_args("error-font", &[("details", details.clone())]),
+            invoice::Error::Logo(details) => tr.tr_args("error-image", &[("details", details.clone())]),
+            invoice::Error::Pdf(details) => tr.tr_args("error-pdf", &[("details", details.clone())]),
+            invoice::Error::Money(error) => error.localize(tr),
+        }
+    }
+}
+
+impl Localized for payment::Error {
+    fn localize(&self, tr: &Localizer) -> String {
+        match self {
+            payment::Error::InvalidInn => tr.tr("error-invalid-inn"),
+            payment::Error::InvalidKpp => tr.tr("error-invalid-kpp"),
+            payment::Error::InvalidBik => tr.tr("error-invalid-bik"),
+            payment::Error::InvalidAccount => tr.tr("error-invalid-account"),
+            payment::Error::InvalidCorrespondentAccount => tr.tr("error-invalid-correspondent-account"),
+            payment::Error::NegativeAmount => tr.tr("error-negative-amount"),
+            payment::Error::ZeroAmount => tr.tr("error-zero-amount"),
+            payment::Error::EmptyPurpose => tr.tr("error-empty-purpose"),
+            payment::Error::Money(error) => error.localize(tr),
+        }
+    }
+}
+
+impl Localized for qr::Error {
+    fn localize(&self, tr: &Localizer) -> String {
+        match self {
+            qr::Error::Image(details) => tr.tr_args("error-image", &[("details", details.clone())]),
+        }
+    }
+}
+
+impl Localized for receipt::Error {
+    fn localize(&self, tr: &Localizer) -> String {
+        match self {
+            receipt::Error::MissingField(field) => tr.tr_args("error-missing-receipt-field", &[("field", field.to_string())]),
+            receipt::Error::InvalidField(field) => tr.tr_args("error-invalid-receipt-field", &[("field", field.to_string())]),
+            receipt::Error::NoQrCode => tr.tr("error-no-qr-code"),
+            receipt::Error::Qr(error) => error.localize(tr),
+        }
+    }
+}
+
+impl Localized for statement::Error {
+    fn localize(&self, tr: &Localizer) -> String {
+        match self {
+            statement::Error::File(details) => tr.tr_args("error-file", &[("details", details.clone())]),
+            statement::Error::UnknownFormat => tr.tr("error-unknown-statement-format"),
+            statement::Error::Xml(details) => tr.tr_args("error-xml", &[("details", details.clone())]),
+            statement::Error::MissingField(field) => tr.tr_args("error-missing-statement-field", &[("field", field.to_string())]),
+            statement::Error::InvalidField(field) => tr.tr_args("error-invalid-statement-field", &[("field", field.to_string())]),
+            statement::Error::UnclosedSection => tr.tr("error-unclosed-section"),
+            statement::Error::UnknownDirection => tr.tr("error-unknown-direction"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal::Decimal;
+
+    use super::*;
+    use crate::money::Money;
+
+    #[test]
+    fn every_catalogue_unit_is_named_in_every_language() {
+        for language in Language::ALL {
+            let tr = Localizer::new(language);
+            for measure in MeasureUnit::CATALOGUE.iter() {
+                let id = format!("measure-{}", measure.id);
+                assert_ne!(tr.measure_short(measure), id);
+                assert_ne!(tr.measure_name(measure), format!("{}-name", id));
+            }
+        }
+    }
+
+    #[test]
+    fn quantity_in_english_words() {
+        let tr = Localizer::new(Language::Russian);
+        let quantity = |text| Quantity::parse(text, 3).unwrap();
+        assert_eq!(tr.quantity(&quantity("1"), &MeasureUnit::KILOGRAM, Language::English), "one kilogram");
+        assert_eq!(tr.quantity(&quantity("1,05"), &MeasureUnit::KILOGRAM, Language::English), "one point zero five kilograms");
+        assert_eq!(tr.quantity(&quantity("21"), &MeasureUnit::PIECE, Language::Russian), "двадцать одна штука");
+    }
+
+    #[test]
+    fn vat_follows_mode() {
+        let tr = Localizer::new(Language::Russian);
+        let amount = Money(Decimal::from(120));
+        let extracted = Vat::calculate(amount, VatRate::Vat20, VatMode::Extract).unwrap();
+        let added = Vat::calculate(amount, VatRate::Vat20, VatMode::Add).unwrap();
+        assert_eq!(tr.vat(&extracted, "20".to_string()), "в том числе НДС 20% — 20");
+        assert_eq!(tr.vat(&added, "24".to_string()), "плюс НДС 20% — 24");
+    }
+}
@@ -0,0 +1,631 @@
+use std::{io::Cursor, path::Path, str::FromStr};
+
+use chrono::{Datelike, NaiveDate};
+use printpdf::{
+    ColorBits, ColorSpace, Image, ImageTransform, ImageXObject, IndirectFontRef, Line, Mm, PdfDocument,
+    PdfDocumentReference, PdfLayerReference, Point, Px
+};
+use rust_decimal::Decimal;
+use ttf_parser::Face;
+
+use crate::{money::{self, Money}, payment::Party, unit::Unit, vat::{Vat, VatMode, VatRate, VatRounding}};
+
+#[derive(Debug,
+    PartialEq,
+    Clone)]
+pub enum Error {
+    /// The template can't be read
+    File(String),
+    /// A template line without `=`
+    InvalidTemplateLine,
+    NoItems,
+    /// The font can't be read or parsed
+    Font(String),
+    /// The logo can't be read or decoded
+    Logo(String),
+    Pdf(String),
+    /// A total doesn't fit
+    Money(money::Error)
+}
+
+/// For logs only, what the user reads comes from `Localizer::error`
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Money(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<money::Error> for Error {
+    fn from(value: money::Error) -> Self {
+        Error::Money(value)
+    }
+}
+
+#[derive(Debug,
+    PartialEq, Eq,
+    Clone, Copy,
+    Default)]
+pub enum Kind {
+    /// Счёт на оплату
+    #[default]
+    Invoice,
+    /// Акт выполненных работ, оказанных услуг
+    Act
+}
+
+impl Kind {
+    pub const ALL: [Kind;2] = [Kind::Invoice, Kind::Act];
+}
+
+impl Kind {
+    /// Heading of the printed form, the form itself is Russian whatever the interface language
+    fn heading(&self) -> &'static str {
+        match self {
+            Kind::Invoice => "Счёт на оплату",
+            Kind::Act => "Акт",
+        }
+    }
+}
+
+#[derive(Debug,
+    PartialEq, Eq,
+    Clone)]
+pub struct Item {
+    pub name: String,
+    pub quantity: Unit,
+    /// "шт.", "усл.", "ч"
+    pub unit: String,
+    pub price: Money
+}
+
+impl Item {
+    pub fn total(&self) -> Result<Money, Error> {
+        Ok(self.price.checked_mul(Decimal::try_from(&self.quantity)?)?)
+    }
+}
+
+/// TrueType fonts with Cyrillic that are usually there, the first one found is the default
+const FONTS: [&str;6] = [
+    "/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf",
+    "/usr/share/fonts/dejavu/DejaVuSans.ttf",
+    "/usr/share/fonts/TTF/DejaVuSans.ttf",
+    "C:\\Windows\\Fonts\\arial.ttf",
+    "/System/Library/Fonts/Supplemental/Arial.ttf",
+    "/Library/Fonts/Arial Unicode.ttf",
+];
+
+/// What stays the same from document to document: the seller and how the page looks
+#[derive(Debug,
+    PartialEq, Eq,
+    Clone)]
+pub struct Template {
+    pub seller: Party,
+    pub address: String,
+    /// PNG or JPEG in the top left corner, empty for none
+    pub logo: String,
+    /// TrueType font, PDF's own fonts have no Cyrillic
+    pub font: String,
+    /// Signs as the head of the company or the contractor
+    pub director: String,
+    pub accountant: String,
+    /// Printed under an invoice, e.g. when it has to be paid
+    pub terms: String
+}
+
+impl Default for Template {
+    fn default() -> Self {
+        Template {
+            seller: Party::default(),
+            address: String::new(),
+            logo: String::new(),
+            font: FONTS
+                .iter()
+                .find(|font| Path::new(font).is_file())
+                .unwrap_or(&FONTS[0])
+                .to_string(),
+            director: String::new(),
+            accountant: String::new(),
+            terms: String::new()
+        }
+    }
+}
+
+impl Template {
+    /// Keys of the template file
+    const KEYS: [&'static str;13] = [
+        "Наименование", "ИНН", "КПП", "Счет", "Банк", "БИК", "Корсчет",
+        "Адрес", "Логотип", "Шрифт", "Руководитель", "Бухгалтер", "Условия",
+    ];
+
+    fn values(&self) -> [&String;13] {
+        [
+            &self.seller.name, &self.seller.inn, &self.seller.kpp, &self.seller.account,
+            &self.seller.bank, &self.seller.bik, &self.seller.correspondent,
+            &self.address, &self.logo, &self.font, &self.director, &self.accountant, &self.terms,
+        ]
+    }
+
+    pub fn read(path: impl AsRef<Path>) -> Result<Self, Error> {
+        std::fs::read_to_string(path)
+            .map_err(|error| Error::File(error.to_string()))?
+            .parse()
+    }
+}
+
+impl FromStr for Template {
+    type Err = Error;
+
+    /// `Ключ=значение` lines in any order, unknown keys are skipped
+    fn from_str(text: &str) -> Result<Self, Error> {
+        let mut template = Template { font: String::new(), ..Template::default() };
+        for line in text.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            let (key, value) = line.split_once('=').ok_or(Error::InvalidTemplateLine)?;
+            let value = value.trim().to_string();
+            match key.trim() {
+                "Наименование" => template.seller.name = value,
+                "ИНН" => template.seller.inn = value,
+                "КПП" => template.seller.kpp = value,
+                "Счет" => template.seller.account = value,
+                "Банк" => template.seller.bank = value,
+                "БИК" => template.seller.bik = value,
+                "Корсчет" => template.seller.correspondent = value,
+                "Адрес" => template.address = value,
+                "Логотип" => template.logo = value,
+                "Шрифт" => template.font = value,
+                "Руководитель" => template.director = value,
+                "Бухгалтер" => template.accountant = value,
+                "Условия" => template.terms = value,
+                _ => {},
+            }
+        }
+        if template.font.is_empty() {
+            template.font = Template::default().font;
+        }
+
+        Ok(template)
+    }
+}
+
+impl std::fmt::Display for Template {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (key, value) in Template::KEYS.iter().zip(self.values()) {
+            writeln!(f, "{}={}", key, value.replace(['\r', '\n'], " "))?;
+        }
+        Ok(())
+    }
+}
+
+/// Invoice or act for one buyer
+#[derive(Debug,
+    PartialEq, Eq,
+    Clone)]
+pub struct Document {
+    pub kind: Kind,
+    pub number: u32,
+    pub date: NaiveDate,
+    /// Name, INN and address in one line, as printed
+    pub buyer: String,
+    /// "Договор № 5 от 01.09.2024", empty for none
+    pub basis: String,
+    pub items: Vec<Item>,
+    pub vat: VatRate,
+    /// Whether the prices include VAT
+    pub vat_mode: VatMode
+}
+
+impl Document {
+    pub fn totals(&self) -> Result<Vat, Error> {
+        if self.items.is_empty() { return Err(Error::NoItems); }
+        let lines = self.items
+            .iter()
+            .map(Item::total)
+            .collect::<Result<Vec<Money>, Error>>()?;
+        Ok(Vat::calculate_document(&lines, self.vat, self.vat_mode, VatRounding::PerDocument)?)
+    }
+
+    /// "Всего наименований 3, на сумму 1 234,56 руб. (Одна тысяча двести тридцать четыре рубля 56 копеек)"
+    pub fn summary(&self) -> Result<String, Error> {
+        let gross = self.totals()?.gross;
+        Ok(format!("Всего наименований {}, на сумму {} руб. ({})", self.items.len(), plain(gross), gross.document_words()?))
+    }
+
+    /// "Счёт на оплату № 5 от 1 октября 2024 г."
+    pub fn title(&self) -> String {
+        format!("{} № {} от {}", self.kind.heading(), self.number, long_date(self.date))
+    }
+
+    /// The whole document as a PDF file, made here without any service
+    pub fn pdf(&self, template: &Template) -> Result<Vec<u8>, Error> {
+        let totals = self.totals()?;
+        let font = std::fs::read(&template.font).map_err(|error| Error::Font(format!("{}: {}", template.font, error)))?;
+        let mut page = Page::new(&self.title(), &font)?;
+
+        if !template.logo.is_empty() {
+            page.logo(&template.logo)?;
+        }
+        if self.kind == Kind::Invoice {
+            page.bank(template);
+        }
+
+        page.space(4.0);
+        page.paragraph(&self.title(), 14.0);
+        page.space(2.0);
+        page.rule();
+        let (seller, buyer) = match self.kind {
+            Kind::Invoice => ("Поставщик", "Покупатель"),
+            Kind::Act => ("Исполнитель", "Заказчик"),
+        };
+        page.paragraph(&format!("{}: {}", seller, seller_line(template)), 9.0);
+        page.paragraph(&format!("{}: {}", buyer, self.buyer), 9.0);
+        if !self.basis.trim().is_empty() {
+            page.paragraph(&format!("Основание: {}", self.basis.trim()), 9.0);
+        }
+        page.space(2.0);
+
+        page.table(&self.items)?;
+        page.space(2.0);
+
+        let vat_label = match (self.vat.percent(), self.vat_mode) {
+            (None, _) => "Без налога (НДС):".to_string(),
+            (Some(percent), VatMode::Extract) => format!("В том числе НДС {}%:", percent.normalize()),
+            (Some(percent), VatMode::Add) => format!("НДС {}%:", percent.normalize()),
+        };
+        let vat_amount = match self.vat.percent() {
+            None => "-".to_string(),
+            Some(_) => plain(totals.vat),
+        };
+        let subtotal = match self.vat_mode {
+            VatMode::Extract => totals.gross,
+            VatMode::Add => totals.net,
+        };
+        for (label, amount) in [
+            ("Итого:".to_string(), plain(subtotal)),
+            (vat_label, vat_amount),
+            ("Всего к оплате:".to_string(), plain(totals.gross)),
+        ] {
+            page.totals_line(&label, &amount);
+        }
+
+        page.space(2.0);
+        page.paragraph(&self.summary()?, 9.0);
+        page.space(2.0);
+        page.rule();
+
+        let after = match self.kind {
+            Kind::Invoice => template.terms.clone(),
+            Kind::Act => "Вышеперечисленные услуги выполнены полностью и в срок. \
+                Заказчик претензий по объёму, качеству и срокам оказания услуг не имеет.".to_string(),
+        };
+        if !after.trim().is_empty() {
+            page.paragraph(&after, 8.0);
+        }
+
+        page.space(10.0);
+        match self.kind {
+            Kind::Invoice => {
+                page.signature("Руководитель", &template.director, MARGIN);
+                page.signature("Бухгалтер", &template.accountant, MARGIN + BODY / 2.0);
+            },
+            Kind::Act => {
+                page.signature("Исполнитель", &template.director, MARGIN);
+                page.signature("Заказчик", "", MARGIN + BODY / 2.0);
+            },
+        }
+
+        page.document.save_to_bytes().map_err(|error| Error::Pdf(error.to_string()))
+    }
+}
+
+const MONTHS: [&str;12] = [
+    "января", "февраля", "марта", "апреля", "мая", "июня",
+    "июля", "августа", "сентября", "октября", "ноября", "декабря",
+];
+
+/// "1 октября 2024 г."
+fn long_date(date: NaiveDate) -> String {
+    format!("{} {} {} г.", date.day(), MONTHS[date.month0() as usize], date.year())
+}
+
+/// "1 234,56" with an ordinary space, fonts don't always have the narrow one
+fn plain(amount: Money) -> String {
+    amount.accounting().replace('\u{a0}', " ")
+}
+
+fn seller_line(template: &Template) -> String {
+    let seller = &template.seller;
+    let mut parts = vec![seller.name.clone(), format!("ИНН {}", seller.inn)];
+    if !seller.kpp.is_empty() {
+        parts.push(format!("КПП {}", seller.kpp));
+    }
+    if !template.address.is_empty() {
+        parts.push(template.address.clone());
+    }
+    parts.join(", ")
+}
+
+const WIDTH: f32 = 210.0;
+const HEIGHT: f32 = 297.0;
+const MARGIN: f32 = 15.0;
+const BODY: f32 = WIDTH - 2.0 * MARGIN;
+/// Millimetres in a typographic point
+const POINT: f32 = 25.4 / 72.0;
+/// Columns of the items table: header, width in mm, whether numbers are aligned right
+const COLUMNS: [(&str, f32, bool);6] = [
+    ("№", 10.0, true),
+    ("Товары (работы, услуги)", 80.0, false),
+    ("Кол-во", 20.0, true),
+    ("Ед.", 15.0, false),
+    ("Цена", 27.5, true),
+    ("Сумма", 27.5, true),
+];
+
+/// A4 pages written top to bottom, a new page starts when this one is full
+struct Page<'a> {
+    document: PdfDocumentReference,
+    layer: PdfLayerReference,
+    font: IndirectFontRef,
+    /// Widths of the letters, PDF doesn't measure text itself
+    face: Face<'a>,
+    /// Top of the next line, mm from the bottom of the page
+    y: f32
+}
+
+impl<'a> Page<'a> {
+    fn new(title: &str, font: &'a [u8]) -> Result<Self, Error> {
+        let face = Face::parse(font, 0).map_err(|error| Error::Font(error.to_string()))?;
+        let (document, page, layer) = PdfDocument::new(title, Mm(WIDTH), Mm(HEIGHT), "1");
+        let font = document
+            .add_external_font(Cursor::new(font))
+            .map_err(|error| Error::Font(error.to_string()))?;
+        let layer = document.get_page(page).get_layer(layer);
+
+        Ok(Page { document, layer, font, face, y: HEIGHT - MARGIN })
+    }
+
+    fn width(&self, text: &str, size: f32) -> f32 {
+        let em = f32::from(self.face.units_per_em());
+        let units: f32 = text
+            .chars()
+            .map(|c| self.face
+                .glyph_index(c)
+                .and_then(|glyph| self.face.glyph_hor_advance(glyph))
+                .map_or(em / 2.0, f32::from))
+            .sum();
+        units / em * size * POINT
+    }
+
+    /// Lines of `text` no wider than `width`, a word longer than that gets a line of its own
+    fn wrap(&self, text: &str, size: f32, width: f32) -> Vec<String> {
+        let mut lines = Vec::new();
+        for paragraph in text.lines() {
+            let mut line = String::new();
+            for word in paragraph.split_whitespace() {
+                let candidate = if line.is_empty() { word.to_string() } else { format!("{} {}", line, word) };
+                if !line.is_empty() && self.width(&candidate, size) > width {
+                    lines.push(std::mem::replace(&mut line, word.to_string()));
+                } else {
+                    line = candidate;
+                }
+            }
+            lines.push(line);
+        }
+        lines
+    }
+
+    fn height(size: f32) -> f32 {
+        size * POINT * 1.3
+    }
+
+    /// Moves to a new page if `height` doesn't fit on this one
+    fn reserve(&mut self, height: f32) -> bool {
+        if self.y - height >= MARGIN { return false; }
+        let (page, layer) = self.document.add_page(Mm(WIDTH), Mm(HEIGHT), "1");
+        self.layer = self.document.get_page(page).get_layer(layer);
+        self.y = HEIGHT - MARGIN;
+        true
+    }
+
+    fn space(&mut self, height: f32) {
+        self.y -= height;
+    }
+
+    /// Text with its top at `top`
+    fn text(&self, text: &str, size: f32, x: f32, top: f32) {
+        self.layer.use_text(text, size, Mm(x), Mm(top - size * POINT), &self.font);
+    }
+
+    fn right(&self, text: &str, size: f32, right: f32, top: f32) {
+        self.text(text, size, right - self.width(text, size), top);
+    }
+
+    fn line(&self, from: (f32, f32), to: (f32, f32)) {
+        self.layer.add_line(Line {
+            points: vec![(Point::new(Mm(from.0), Mm(from.1)), false), (Point::new(Mm(to.0), Mm(to.1)), false)],
+            is_closed: false
+        });
+    }
+
+    fn rule(&mut self) {
+        self.line((MARGIN, self.y), (WIDTH - MARGIN, self.y));
+        self.space(2.0);
+    }
+
+    fn paragraph(&mut self, text: &str, size: f32) {
+        for line in self.wrap(text, size, BODY) {
+            self.reserve(Page::height(size));
+            self.text(&line, size, MARGIN, self.y);
+            self.space(Page::height(size));
+        }
+    }
+
+    /// Logo 20 mm high in the top left corner
+    fn logo(&mut self, path: &str) -> Result<(), Error> {
+        const LOGO: f32 = 20.0;
+        let image = image::open(path).map_err(|error| Error::Logo(error.to_string()))?.to_rgb8();
+        let (width, height) = image.dimensions();
+        let logo = ImageXObject {
+            width: Px(width as usize),
+            height: Px(height as usize),
+            color_space: ColorSpace::Rgb,
+            bits_per_component: ColorBits::Bit8,
+            interpolate: true,
+            image_data: image.into_raw(),
+            image_filter: None,
+            smask: None,
+            clipping_bbox: None
+        };
+        Image::from(logo).add_to_layer(self.layer.clone(), ImageTransform {
+            translate_x: Some(Mm(MARGIN)),
+            translate_y: Some(Mm(self.y - LOGO)),
+            dpi: Some(height as f32 * 25.4 / LOGO),
+            ..ImageTransform::default()
+        });
+        self.space(LOGO + 4.0);
+        Ok(())
+    }
+
+    /// Requisites box at the top of an invoice, what the buyer's accountant needs to pay
+    fn bank(&mut self, template: &Template) {
+        let seller = &template.seller;
+        let (left, middle, right) = (MARGIN, MARGIN + 105.0, MARGIN + 125.0);
+        let row = 6.0;
+        let top = self.y;
+        let cells = [
+            (seller.bank.clone(), "БИК", seller.bik.as_str()),
+            ("Банк получателя".to_string(), "Сч. №", seller.correspondent.as_str()),
+            (format!("ИНН {}    КПП {}", seller.inn, seller.kpp), "Сч. №", seller.account.as_str()),
+            (seller.name.clone(), "", ""),
+            ("Получатель".to_string(), "", ""),
+        ];
+        for (index, (text, label, value)) in cells.iter().enumerate() {
+            let y = top - row * index as f32;
+            let size = if index == 1 || index == 4 { 7.0 } else { 9.0 };
+            self.text(text, size, left + 1.0, y - 1.0);
+            self.text(label, 9.0, middle + 1.0, y - 1.0);
+            self.text(value, 9.0, right + 1.0, y - 1.0);
+        }
+
+        let bottom = top - row * cells.len() as f32;
+        for x in [left, middle, right, WIDTH - MARGIN] {
+            self.line((x, top), (x, bottom));
+        }
+        for index in [0, 2, 3, 5] {
+            let y = top - row * index as f32;
+            self.line((left, y), (WIDTH - MARGIN, y));
+        }
+        self.line((middle, top - row), (WIDTH - MARGIN, top - row));
+        self.y = bottom - 2.0;
+    }
+
+    fn table_header(&mut self) {
+        let size = 8.0;
+        let height = Page::height(size) + 2.0;
+        self.table_row(&COLUMNS.map(|(header, _, _)| vec![header.to_string()]), size, height, false);
+    }
+
+    fn table_row(&mut self, cells: &[Vec<String>;6], size: f32, height: f32, numbers: bool) {
+        let top = self.y;
+        let mut x = MARGIN;
+        for ((_, width, right), lines) in COLUMNS.iter().zip(cells) {
+            for (index, line) in lines.iter().enumerate() {
+                let y = top - 1.0 - Page::height(size) * index as f32;
+                match numbers && *right {
+                    true => self.right(line, size, x + width - 1.0, y),
+                    false => self.text(line, size, x + 1.0, y),
+                }
+            }
+            self.line((x, top), (x, top - height));
+            x += width;
+        }
+        self.line((x, top), (x, top - height));
+        self.line((MARGIN, top), (x, top));
+        self.line((MARGIN, top - height), (x, top - height));
+        self.space(height);
+    }
+
+    fn table(&mut self, items: &[Item]) -> Result<(), Error> {
+        let size = 8.0;
+        self.reserve(3.0 * Page::height(size));
+        self.table_header();
+        for (index, item) in items.iter().enumerate() {
+            let names = self.wrap(&item.name, size, COLUMNS[1].1 - 2.0);
+            let height = Page::height(size) * names.len() as f32 + 2.0;
+            if self.reserve(height) {
+                self.table_header();
+            }
+            let cells = [
+                vec![(index + 1).to_string()],
+                names,
+                vec![item.quantity.0.to_string()],
+                vec![item.unit.clone()],
+                vec![plain(item.price)],
+                vec![plain(item.total()?)],
+            ];
+            self.table_row(&cells, size, height, true);
+        }
+        Ok(())
+    }
+
+    fn totals_line(&mut self, label: &str, amount: &str) {
+        let size = 9.0;
+        self.reserve(Page::height(size));
+        self.right(label, size, WIDTH - MARGIN - 30.0, self.y);
+        self.right(amount, size, WIDTH - MARGIN - 1.0, self.y);
+        self.space(Page::height(size));
+    }
+
+    /// "Руководитель ____________ Иванов И. И." starting at `x`
+    fn signature(&mut self, role: &str, name: &str, x: f32) {
+        let size = 9.0;
+        self.reserve(Page::height(size));
+        let signed = format!("{} ____________ {}", role, name);
+        self.text(&signed, size, x, self.y);
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(quantity: u128) -> Item {
+        Item { name: "Услуга".to_string(), quantity: Unit(quantity), unit: "усл.".to_string(), price: Money(Decimal::new(150, 2)) }
+    }
+
+    #[test]
+    fn item_total() {
+        assert_eq!(item(3).total(), Ok(Money(Decimal::new(450, 2))));
+    }
+
+    #[test]
+    fn summary_says_the_amount_in_words() {
+        let document = Document {
+            kind: Kind::Invoice,
+            number: 5,
+            date: NaiveDate::from_ymd_opt(2024, 10, 1).unwrap(),
+            buyer: "ООО Ромашка".to_string(),
+            basis: String::new(),
+            items: vec![item(3), Item { price: Money(Decimal::new(123000, 2)), ..item(1) }],
+            vat: VatRate::Vat20,
+            vat_mode: VatMode::Extract
+        };
+        assert_eq!(
+            document.summary().unwrap(),
+            "Всего наименований 2, на сумму 1 234,50 руб. (Одна тысяча двести тридцать четыре рубля 50 копеек)"
+        );
+    }
+
+    #[test]
+    fn quantity_beyond_decimal_is_refused() {
+        assert_eq!(item(1 << 100).total(), Err(Error::Money(money::Error::OutOfRange)));
+    }
+}
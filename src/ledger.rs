@@ -0,0 +1,400 @@
+use std::path::Path;
+
+use chrono::NaiveDate;
+use rusqlite::{params, params_from_iter, Connection};
+
+use crate::{budget::Expense, money::{self, Money}, receipt::Receipt};
+
+/// Schema changes in order, `PRAGMA user_version` counts how many are applied.
+/// New ones go to the end, applied ones are never edited
+const MIGRATIONS: [&str;2] = [
+    "CREATE TABLE transactions (
+        id INTEGER PRIMARY KEY,
+        date TEXT NOT NULL,
+        kopeks INTEGER NOT NULL,
+        category TEXT NOT NULL DEFAULT '',
+        counterparty TEXT NOT NULL DEFAULT '',
+        note TEXT NOT NULL DEFAULT ''
+    );
+    CREATE INDEX transactions_date ON transactions (date);
+    CREATE INDEX transactions_category ON transactions (category);
+    CREATE INDEX transactions_counterparty ON transactions (counterparty);",
+    "ALTER TABLE transactions ADD COLUMN currency TEXT NOT NULL DEFAULT 'RUB';
+    ALTER TABLE transactions ADD COLUMN reference TEXT NOT NULL DEFAULT '';",
+];
+
+const INSERT: &str = "INSERT INTO transactions (date, kopeks, currency, category, counterparty, note, reference) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)";
+
+/// Parameters of `INSERT` in order
+type Values<'a> = (String, i64, &'a str, &'a str, &'a str, &'a str, &'a str);
+
+/// Currency of everything that doesn't say otherwise
+pub const RUB: &str = "RUB";
+
+#[derive(Debug)]
+pub enum Error {
+    /// SQLite failed: the file is locked, damaged or not a database
+    Storage(rusqlite::Error),
+    /// The file was written by a newer version of the program, the version is its schema's
+    NewerSchema(usize),
+    /// Amounts in different currencies don't add up
+    MixedCurrencies,
+    /// A stored amount or date is out of range
+    Money(money::Error)
+}
+
+/// For logs only, what the user reads comes from `Localizer::error`
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Storage(error) => Some(error),
+            Error::Money(error) => Some(error),
+            Error::NewerSchema(_) | Error::MixedCurrencies => None,
+        }
+    }
+}
+
+impl From<rusqlite::Error> for Error {
+    fn from(value: rusqlite::Error) -> Self {
+        Error::Storage(value)
+    }
+}
+
+impl From<money::Error> for Error {
+    fn from(value: money::Error) -> Self {
+        Error::Money(value)
+    }
+}
+
+/// One ledger entry
+#[derive(Debug,
+    PartialEq, Eq,
+    Clone)]
+pub struct Transaction {
+    /// Row id, `None` until the transaction is stored
+    pub id: Option<i64>,
+    pub date: NaiveDate,
+    /// Income is positive, expenses are negative
+    pub amount: Money,
+    /// ISO 4217 code, kopeks are cents for other currencies
+    pub currency: String,
+    pub category: String,
+    pub counterparty: String,
+    pub note: String,
+    /// Bank's id of the payment, empty when unknown
+    pub reference: String
+}
+
+impl Transaction {
+    /// Whether both are the same payment, e.g. from two statements that overlap.
+    /// References stand in for the note when both have one, but a payment order
+    /// number is only unique per payer, so the counterparty always has to match.
+    /// The category is never compared
+    pub fn same_as(&self, other: &Transaction) -> bool {
+        let details = match self.reference.is_empty() || other.reference.is_empty() {
+            true => self.note == other.note,
+            false => self.reference == other.reference,
+        };
+        self.date == other.date && self.amount == other.amount && self.currency == other.currency
+            && self.counterparty == other.counterparty && details
+    }
+
+    fn kopeks(&self) -> Result<i64, money::Error> {
+        i64::try_from(self.amount.kopeks()?).map_err(|_| money::Error::OutOfRange)
+    }
+}
+
+impl From<&Expense> for Transaction {
+    fn from(expense: &Expense) -> Self {
+        Transaction {
+            id: None,
+            date: expense.date,
+            amount: Money(-expense.amount.0),
+            currency: RUB.to_string(),
+            category: expense.category.clone(),
+            counterparty: String::new(),
+            note: expense.note.clone(),
+            reference: String::new()
+        }
+    }
+}
+
+impl From<&Receipt> for Transaction {
+    fn from(receipt: &Receipt) -> Self {
+        Transaction {
+            id: None,
+            date: receipt.time.date(),
+            amount: receipt.amount(),
+            currency: RUB.to_string(),
+            category: String::new(),
+            counterparty: String::new(),
+            note: receipt.note(),
+            reference: String::new()
+        }
+    }
+}
+
+/// Which transactions to take, empty fields match everything
+#[derive(Debug,
+    PartialEq, Eq,
+    Clone,
+    Default)]
+pub struct Filter {
+    pub from: Option<NaiveDate>,
+    /// Inclusive
+    pub to: Option<NaiveDate>,
+    pub category: Option<String>,
+    pub counterparty: Option<String>,
+    pub currency: Option<String>
+}
+
+impl Filter {
+    /// WHERE clause and its parameters
+    fn sql(&self) -> (String, Vec<String>) {
+        let mut conditions = Vec::new();
+        let mut values = Vec::new();
+
+        if let Some(from) = self.from {
+            conditions.push("date >= ?");
+            values.push(from.to_string());
+        }
+        if let Some(to) = self.to {
+            conditions.push("date <= ?");
+            values.push(to.to_string());
+        }
+        if let Some(category) = &self.category {
+            conditions.push("category = ?");
+            values.push(category.clone());
+        }
+        if let Some(counterparty) = &self.counterparty {
+            conditions.push("counterparty = ?");
+            values.push(counterparty.clone());
+        }
+        if let Some(currency) = &self.currency {
+            conditions.push("currency = ?");
+            values.push(currency.clone());
+        }
+
+        match conditions.is_empty() {
+            true => (String::new(), values),
+            false => (format!("WHERE {}", conditions.join(" AND ")), values),
+        }
+    }
+}
+
+/// Sum of the transactions, which must all be in one currency
+pub fn sum(transactions: &[Transaction]) -> Result<Money, Error> {
+    if transactions.windows(2).any(|pair| pair[0].currency != pair[1].currency) {
+        return Err(Error::MixedCurrencies);
+    }
+    Ok(transactions
+        .iter()
+        .try_fold(Money::from_kopeks(0)?, |sum, transaction| sum.checked_add(transaction.amount))?)
+}
+
+/// Transactions in a SQLite file, amounts are kept as whole kopeks
+pub struct Ledger {
+    connection: Connection
+}
+
+impl Ledger {
+    /// Opens or creates the file and brings its schema up to date
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, Error> {
+        Ledger::migrate(Connection::open(path)?)
+    }
+
+    pub fn in_memory() -> Result<Self, Error> {
+        Ledger::migrate(Connection::open_in_memory()?)
+    }
+
+    fn migrate(mut connection: Connection) -> Result<Self, Error> {
+        let version: usize = connection.pragma_query_value(None, "user_version", |row| row.get(0))?;
+        if version > MIGRATIONS.len() {
+            return Err(Error::NewerSchema(version));
+        }
+
+        for (index, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+            let transaction = connection.transaction()?;
+            transaction.execute_batch(migration)?;
+            transaction.pragma_update(None, "user_version", index + 1)?;
+            transaction.commit()?;
+        }
+
+        Ok(Ledger { connection })
+    }
+
+    pub fn schema_version(&self) -> Result<usize, Error> {
+        Ok(self.connection.pragma_query_value(None, "user_version", |row| row.get(0))?)
+    }
+
+    /// Stores the transaction and returns its id
+    pub fn add(&self, transaction: &Transaction) -> Result<i64, Error> {
+        self.connection.execute(INSERT, Ledger::params(transaction)?)?;
+
+        Ok(self.connection.last_insert_rowid())
+    }
+
+    /// Stores several transactions at once, all or none
+    pub fn add_all(&mut self, transactions: &[Transaction]) -> Result<usize, Error> {
+        let batch = self.connection.transaction()?;
+        for transaction in transactions {
+            batch.execute(INSERT, Ledger::params(transaction)?)?;
+        }
+        batch.commit()?;
+
+        Ok(transactions.len())
+    }
+
+    /// Stores the transactions that aren't in the ledger yet, returns how many were new.
+    /// A payment stored once matches one imported payment, so two equal
+    /// payments in one statement are both kept
+    pub fn add_new(&mut self, transactions: &[Transaction]) -> Result<usize, Error> {
+        let (Some(from), Some(to)) = (
+            transactions.iter().map(|transaction| transaction.date).min(),
+            transactions.iter().map(|transaction| transaction.date).max()
+        ) else {
+            return Ok(0);
+        };
+
+        let mut stored = self.query(&Filter { from: Some(from), to: Some(to), ..Filter::default() })?;
+        let mut new = Vec::new();
+        for transaction in transactions {
+            match stored.iter().position(|old| old.same_as(transaction)) {
+                Some(index) => { stored.swap_remove(index); },
+                None => new.push(transaction.clone()),
+            }
+        }
+
+        self.add_all(&new)
+    }
+
+    fn params(transaction: &Transaction) -> Result<Values<'_>, Error> {
+        Ok((
+            transaction.date.to_string(),
+            transaction.kopeks()?,
+            &transaction.currency,
+            &transaction.category,
+            &transaction.counterparty,
+            &transaction.note,
+            &transaction.reference
+        ))
+    }
+
+    /// `false` if there was no such transaction
+    pub fn remove(&self, id: i64) -> Result<bool, Error> {
+        Ok(self.connection.execute("DELETE FROM transactions WHERE id = ?1", params![id])? != 0)
+    }
+
+    /// Matching transactions, oldest first
+    pub fn query(&self, filter: &Filter) -> Result<Vec<Transaction>, Error> {
+        let (condition, values) = filter.sql();
+        let mut statement = self.connection.prepare(&format!(
+            "SELECT id, date, kopeks, currency, category, counterparty, note, reference FROM transactions {} ORDER BY date, id",
+            condition
+        ))?;
+
+        let rows = statement.query_map(params_from_iter(values), |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, i64>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, String>(4)?,
+                row.get::<_, String>(5)?,
+                row.get::<_, String>(6)?,
+                row.get::<_, String>(7)?
+            ))
+        })?;
+
+        let mut transactions = Vec::new();
+        for row in rows {
+            let (id, date, kopeks, currency, category, counterparty, note, reference) = row?;
+            transactions.push(Transaction {
+                id: Some(id),
+                date: date.parse().map_err(|_| money::Error::InvalidDate)?,
+                amount: Money::from_kopeks(kopeks.into())?,
+                currency,
+                category,
+                counterparty,
+                note,
+                reference
+            });
+        }
+
+        Ok(transactions)
+    }
+
+    /// Sum of matching transactions, added up exactly in kopeks
+    pub fn total(&self, filter: &Filter) -> Result<Money, Error> {
+        sum(&self.query(filter)?)
+    }
+
+    /// Categories in use, sorted
+    pub fn categories(&self) -> Result<Vec<String>, Error> {
+        let mut statement = self.connection.prepare("SELECT DISTINCT category FROM transactions ORDER BY category")?;
+        let categories = statement
+            .query_map([], |row| row.get(0))?
+            .collect::<Result<_, _>>()?;
+
+        Ok(categories)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal::Decimal;
+
+    use super::*;
+
+    fn transaction(amount: i64, currency: &str) -> Transaction {
+        Transaction {
+            id: None,
+            date: NaiveDate::from_ymd_opt(2024, 3, 1).unwrap(),
+            amount: Money(Decimal::from(amount)),
+            currency: currency.to_string(),
+            category: String::new(),
+            counterparty: "ООО Ромашка".to_string(),
+            note: String::new(),
+            reference: String::new()
+        }
+    }
+
+    #[test]
+    fn sum_needs_one_currency() {
+        assert_eq!(sum(&[transaction(5, RUB), transaction(-2, RUB)]).unwrap(), Money(Decimal::from(3)));
+        assert!(matches!(sum(&[transaction(5, RUB), transaction(5, "USD")]), Err(Error::MixedCurrencies)));
+    }
+
+    #[test]
+    fn newer_schema_is_refused() {
+        let ledger = Ledger::in_memory().unwrap();
+        assert_eq!(ledger.schema_version().unwrap(), MIGRATIONS.len());
+
+        let connection = ledger.connection;
+        connection.pragma_update(None, "user_version", MIGRATIONS.len() + 1).unwrap();
+        assert!(matches!(Ledger::migrate(connection), Err(Error::NewerSchema(version)) if version == MIGRATIONS.len() + 1));
+    }
+
+    #[test]
+    fn stored_payments_are_not_imported_twice() {
+        let mut ledger = Ledger::in_memory().unwrap();
+        let statement = [transaction(-100, RUB), transaction(-100, RUB), transaction(50, RUB)];
+        assert_eq!(ledger.add_new(&statement[..2]).unwrap(), 2);
+        assert_eq!(ledger.add_new(&statement).unwrap(), 1);
+        assert_eq!(ledger.total(&Filter::default()).unwrap(), Money(Decimal::from(-150)));
+    }
+
+    #[test]
+    fn same_order_number_from_another_payer_is_another_payment() {
+        let first = Transaction { reference: "17".to_string(), ..transaction(100, RUB) };
+        let second = Transaction { counterparty: "ИП Васильев".to_string(), ..first.clone() };
+        assert!(first.same_as(&first.clone()));
+        assert!(!first.same_as(&second));
+    }
+}
@@ -0,0 +1,28 @@
+pub mod money;
+pub mod unit;
+pub mod pronounce;
+pub mod vat;
+pub mod measure;
+pub mod quantity;
+pub mod conversion;
+pub mod comparison;
+pub mod history;
+pub mod field;
+pub mod english;
+pub mod i18n;
+pub mod split;
+pub mod loan;
+pub mod deposit;
+pub mod budget;
+pub mod ledger;
+pub mod recurring;
+pub mod chart;
+pub mod receipt;
+pub mod qr;
+pub mod statement;
+pub mod clientbank;
+pub mod ofx;
+pub mod qif;
+pub mod camt;
+pub mod payment;
+pub mod invoice;
@@ -0,0 +1,246 @@
+use rust_decimal::Decimal;
+
+use crate::money::{Error, Money, Rounding, SignPolicy};
+
+#[derive(Debug,
+    PartialEq, Eq,
+    Clone, Copy,
+    Default)]
+pub enum Method {
+    /// Equal payments, interest first
+    #[default]
+    Annuity,
+    /// Equal parts of the principal plus interest on the balance
+    Differentiated
+}
+
+/// What an early repayment is spent on
+#[derive(Debug,
+    PartialEq, Eq,
+    Clone, Copy,
+    Default)]
+pub enum EarlyEffect {
+    /// Same payment, fewer months
+    #[default]
+    ReduceTerm,
+    /// Same months, smaller payment
+    ReducePayment
+}
+
+#[derive(Debug,
+    PartialEq, Eq,
+    Clone, Copy)]
+pub struct EarlyRepayment {
+    /// Number of the regular payment it comes together with, from 1
+    pub month: u32,
+    pub amount: Money,
+    pub effect: EarlyEffect
+}
+
+#[derive(Debug,
+    PartialEq, Eq,
+    Clone)]
+pub struct Loan {
+    pub principal: Money,
+    /// Percent per year
+    pub annual_rate: Decimal,
+    pub months: u32,
+    pub method: Method,
+    pub early: Vec<EarlyRepayment>
+}
+
+/// One row of the schedule
+#[derive(Debug,
+    PartialEq, Eq,
+    Clone, Copy)]
+pub struct Payment {
+    pub number: u32,
+    /// Regular payment: interest plus principal
+    pub payment: Money,
+    pub interest: Money,
+    pub principal: Money,
+    pub early: Money,
+    /// Debt left after the payment
+    pub balance: Money
+}
+
+#[derive(Debug,
+    PartialEq, Eq,
+    Clone)]
+pub struct Schedule {
+    pub payments: Vec<Payment>,
+    /// Everything paid, early repayments included
+    pub total_paid: Money,
+    pub total_interest: Money
+}
+
+/// (1 + rate)^months without the `maths` feature of rust_decimal, by squaring
+fn compound(rate: Decimal, months: u32) -> Result<Decimal, Error> {
+    let mut result = Decimal::ONE;
+    let mut base = Decimal::ONE.checked_add(rate).ok_or(Error::Overflow)?;
+    let mut exponent = months;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = result.checked_mul(base).ok_or(Error::Overflow)?;
+        }
+        exponent >>= 1;
+        if exponent > 0 {
+            base = base.checked_mul(base).ok_or(Error::Overflow)?;
+        }
+    }
+
+    Ok(result)
+}
+
+/// Payment that repays `balance` in `months` equal instalments
+fn annuity(balance: Money, rate: Decimal, months: u32, rounding: Rounding) -> Result<Money, Error> {
+    if months == 0 { return Err(Error::ZeroTerm); }
+    if rate.is_zero() {
+        return Ok(Money::round(balance.checked_div(Decimal::from(months))?.0, rounding));
+    }
+
+    let growth = compound(rate, months)?;
+    let payment = balance
+        .checked_mul(rate)?
+        .checked_mul(growth)?
+        .checked_div(growth - Decimal::ONE)?;
+
+    Ok(Money::round(payment.0, rounding))
+}
+
+/// Principal part of a differentiated payment
+fn principal_part(balance: Money, months: u32, rounding: Rounding) -> Result<Money, Error> {
+    if months == 0 { return Err(Error::ZeroTerm); }
+    Ok(Money::round(balance.checked_div(Decimal::from(months))?.0, rounding))
+}
+
+impl Loan {
+    /// Fifty years, the schedule has a row for every month
+    pub const MAX_MONTHS: u32 = 600;
+
+    /// Builds the schedule month by month, interest is rounded every month.
+    /// The last payment takes whatever the rounding left of the debt
+    pub fn schedule(&self, rounding: Rounding) -> Result<Schedule, Error> {
+        if self.months == 0 { return Err(Error::ZeroTerm); }
+        if self.months > Loan::MAX_MONTHS { return Err(Error::TermTooLong(Loan::MAX_MONTHS)); }
+        if self.annual_rate.is_sign_negative() && !self.annual_rate.is_zero() {
+            return Err(Error::NegativeAmount);
+        }
+        let zero = Money(Decimal::ZERO);
+        let mut balance = SignPolicy::NonNegative.check(self.principal)?;
+        let rate = self.annual_rate
+            .checked_div(Decimal::from(1200))
+            .ok_or(Error::Overflow)?;
+
+        let mut remaining = self.months;
+        let mut regular = match self.method {
+            Method::Annuity => annuity(balance, rate, remaining, rounding)?,
+            Method::Differentiated => principal_part(balance, remaining, rounding)?,
+        };
+
+        let mut payments = Vec::new();
+        let mut total_paid = zero;
+        let mut total_interest = zero;
+        let mut number = 0;
+        while balance > zero {
+            number += 1;
+            let interest = Money::round(balance.checked_mul(rate)?.0, rounding);
+            let mut principal = match self.method {
+                Method::Annuity => regular.checked_sub(interest)?.max(zero),
+                Method::Differentiated => regular,
+            };
+            if remaining <= 1 || principal >= balance {
+                principal = balance;
+            }
+            balance = balance.checked_sub(principal)?;
+            remaining = remaining.saturating_sub(1);
+
+            let mut early = zero;
+            let mut effect = None;
+            for repayment in self.early.iter().filter(|repayment| repayment.month == number) {
+                let amount = SignPolicy::NonNegative.check(repayment.amount)?.min(balance.checked_sub(early)?);
+                early = early.checked_add(amount)?;
+                effect = Some(repayment.effect);
+            }
+            balance = balance.checked_sub(early)?;
+
+            if effect == Some(EarlyEffect::ReducePayment) && balance > zero && remaining > 0 {
+                regular = match self.method {
+                    Method::Annuity => annuity(balance, rate, remaining, rounding)?,
+                    Method::Differentiated => principal_part(balance, remaining, rounding)?,
+                };
+            }
+
+            let payment = principal.checked_add(interest)?;
+            total_paid = total_paid.checked_add(payment)?.checked_add(early)?;
+            total_interest = total_interest.checked_add(interest)?;
+            payments.push(Payment { number, payment, interest, principal, early, balance });
+        }
+
+        Ok(Schedule { payments, total_paid, total_interest })
+    }
+}
+
+impl Schedule {
+    /// Semicolon separated with decimal commas, the way Russian spreadsheets open it.
+    /// `header` names the six columns, `total` starts the last line
+    pub fn csv(&self, header: [String;6], total: &str) -> String {
+        let amount = |money: Money| {
+            let mut value = money.0.round_dp(2);
+            value.rescale(2);
+            value.to_string().replace('.', ",")
+        };
+
+        let mut csv = format!("{}\n", header.join(";"));
+        for payment in self.payments.iter() {
+            csv.push_str(&format!("{};{};{};{};{};{}\n",
+                payment.number,
+                amount(payment.payment),
+                amount(payment.interest),
+                amount(payment.principal),
+                amount(payment.early),
+                amount(payment.balance)
+            ));
+        }
+        csv.push_str(&format!("{};{};{};;;\n", total, amount(self.total_paid), amount(self.total_interest)));
+
+        csv
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn loan(months: u32) -> Loan {
+        Loan {
+            principal: Money(Decimal::from(120_000)),
+            annual_rate: Decimal::from(12),
+            months,
+            method: Method::Annuity,
+            early: Vec::new()
+        }
+    }
+
+    #[test]
+    fn compound_by_squaring() {
+        let rate = Decimal::new(1, 2);
+        let mut expected = Decimal::ONE;
+        for months in 0..=40 {
+            assert_eq!(compound(rate, months).unwrap().round_dp(20), expected.round_dp(20));
+            expected *= Decimal::ONE + rate;
+        }
+        assert_eq!(compound(Decimal::ONE, 200), Err(Error::Overflow));
+    }
+
+    #[test]
+    fn term_is_capped() {
+        assert_eq!(loan(Loan::MAX_MONTHS + 1).schedule(Rounding::HalfUp), Err(Error::TermTooLong(Loan::MAX_MONTHS)));
+        assert_eq!(loan(u32::MAX).schedule(Rounding::HalfUp), Err(Error::TermTooLong(Loan::MAX_MONTHS)));
+
+        let schedule = loan(12).schedule(Rounding::HalfUp).unwrap();
+        assert_eq!(schedule.payments.len(), 12);
+        assert_eq!(schedule.payments[0].payment, Money(Decimal::new(1066185, 2)));
+        assert_eq!(loan(Loan::MAX_MONTHS).schedule(Rounding::HalfUp).unwrap().payments.len(), 600);
+    }
+}
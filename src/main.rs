@@ -1,117 +1,762 @@
 #![windows_subsystem = "windows"]
-use eframe::egui::CentralPanel;
-use money::Money;
+use std::{sync::mpsc::{self, TryRecvError}, time::Duration};
+
+use eframe::egui::{
+    Button, CentralPanel, Color32, ComboBox, DragValue, Grid, Id, Key, KeyboardShortcut, Modifiers,
+    ScrollArea, SidePanel, TextEdit, TopBottomPanel, Ui
+};
+use money_counter::{
+    budget::Expense,
+    comparison,
+    conversion::{self, Offer},
+    field::Field,
+    history::History,
+    measure::{Gender, MeasureUnit},
+    money::{self as money, Money, Rounding, SignPolicy},
+    i18n::Localizer,
+    pronounce::{Language, Scale},
+    receipt::{self, Receipt},
+    quantity::Quantity,
+    vat::{Vat, VatMode, VatRate}
+};
+use chrono::Local;
 use rust_decimal::Decimal;
-use unit::Unit;
 
-mod money;
-mod unit;
-mod pronounce;
+mod ui;
 
-struct MyApp {
-    budget: Money,
-    price: Money,
-    count: Unit
+use ui::{
+    budget::BudgetInput,
+    charts::ChartsInput,
+    deposit::DepositInput,
+    invoice::InvoiceInput,
+    ledger::LedgerInput,
+    loan::LoanInput,
+    payment::PaymentInput,
+    split::SplitInput,
+    field_edit, field_error, leftover_line, Session
+};
+
+
+#[derive(Clone, PartialEq)]
+struct OfferInput {
+    name: String,
+    price: Field<Money>,
+    amount: Field<Decimal>,
+    measure: MeasureUnit,
+    pack_size_text: String
+}
+
+impl OfferInput {
+    fn new(measure: MeasureUnit) -> Self {
+        OfferInput {
+            name: String::new(),
+            price: Field::new(Money(Decimal::ZERO)),
+            amount: Field::new(Decimal::ONE),
+            measure,
+            pack_size_text: String::new()
+        }
+    }
+
+    fn offer(&self) -> Result<Offer, money::Error> {
+        let pack_size = match self.pack_size_text.trim() {
+            "" => None,
+            text => Some(Decimal::from_str_exact(&text.replace(',', "."))?)
+        };
+
+        Ok(Offer {
+            price: *self.price.value(),
+            amount: *self.amount.value(),
+            measure: self.measure.clone(),
+            pack_size
+        })
+    }
+
+    fn ui(&mut self, ui: &mut Ui, id: &str, rounding: Rounding, tr: &Localizer) {
+        ui.horizontal(
+            |ui| {
+                ui.label(tr.tr("price"));
+                field_edit(ui, &mut self.price);
+            });
+        field_error(ui, &mut self.price, rounding, tr);
+        ui.horizontal(
+            |ui| {
+                ui.label(tr.tr("offer-for"));
+                field_edit(ui, &mut self.amount);
+                ComboBox::from_id_source(id)
+                    .selected_text(tr.measure_short(&self.measure))
+                    .show_ui(ui, |ui| {
+                        for conversion in conversion::TABLE.iter() {
+                            let measure = &conversion.measure;
+                            ui.selectable_value(&mut self.measure, measure.clone(), tr.measure_short(measure));
+                        }
+                    });
+            });
+        field_error(ui, &mut self.amount, rounding, tr);
+        if conversion::needs_pack_size(&self.measure) {
+            ui.horizontal(
+                |ui| {
+                    ui.label(tr.tr(if self.measure == MeasureUnit::BOX { "offer-box-size" } else { "offer-pack-size" }));
+                    ui.text_edit_singleline(&mut self.pack_size_text);
+                });
+        }
+    }
+}
+
+
+#[derive(Clone, Copy, PartialEq)]
+enum Mode {
+    Purchase,
+    Split,
+    Loan,
+    Deposit,
+    Budget,
+    Ledger,
+    Charts,
+    Payment,
+    Invoice
 }
 
-impl Default for MyApp {
+/// Everything the user has entered, undo and redo swap it as a whole
+#[derive(Clone, PartialEq)]
+struct Calculator {
+    mode: Mode,
+    budget: Field<Money>,
+    price: Field<Money>,
+    count: Field<Quantity>,
+    rounding: Rounding,
+    scale: Scale,
+    words_language: Language,
+    vat_rate: VatRate,
+    vat_mode: VatMode,
+    custom_vat_rate: Field<Decimal>,
+    measure: MeasureUnit,
+    custom_measures: Vec<MeasureUnit>,
+    new_measure_short: String,
+    new_measure_forms: [String;3],
+    new_measure_gender: Gender,
+    offers: Vec<OfferInput>,
+    split: SplitInput,
+    loan: LoanInput,
+    deposit: DepositInput,
+    budget_input: BudgetInput,
+    ledger: LedgerInput,
+    charts: ChartsInput,
+    payment: PaymentInput,
+    invoice: InvoiceInput,
+    /// Category the purchase is paid from, empty for the budget field
+    purchase_category: String,
+    /// QR string of a cash receipt
+    receipt: String,
+    /// Photo or screenshot of a receipt
+    receipt_image: String
+}
+
+impl Default for Calculator {
     fn default() -> Self {
-        MyApp { budget: Money(Decimal::from(0)), price: Money(Decimal::from(0)), count: Unit(0) }
+        Calculator {
+            mode: Mode::Purchase,
+            budget: Field::new(Money(Decimal::ZERO)),
+            price: Field::new(Money(Decimal::ZERO)),
+            count: Field::new(Quantity::new(Decimal::ZERO, 3).unwrap()),
+            rounding: Rounding::default(),
+            scale: Scale::default(),
+            words_language: Language::default(),
+            vat_rate: VatRate::Vat20,
+            vat_mode: VatMode::Extract,
+            custom_vat_rate: Field::new(Decimal::ZERO),
+            measure: MeasureUnit::default(),
+            custom_measures: Vec::new(),
+            new_measure_short: String::new(),
+            new_measure_forms: Default::default(),
+            new_measure_gender: Gender::Feminine,
+            offers: vec![OfferInput::new(MeasureUnit::GRAM), OfferInput::new(MeasureUnit::KILOGRAM)],
+            split: SplitInput::default(),
+            loan: LoanInput::default(),
+            deposit: DepositInput::default(),
+            budget_input: BudgetInput::default(),
+            ledger: LedgerInput::default(),
+            charts: ChartsInput::default(),
+            payment: PaymentInput::default(),
+            invoice: InvoiceInput::default(),
+            purchase_category: String::new(),
+            receipt: String::new(),
+            receipt_image: String::new()
+        }
     }
 }
 
-impl MyApp {
-    fn new(cc: &eframe::CreationContext<'_>) -> Self {
-        Self::default()
+impl Calculator {
+    /// One line for the history panel
+    fn summary(&self, tr: &Localizer) -> String {
+        if self.mode == Mode::Loan {
+            return tr.tr_args("history-summary-loan", &[
+                ("principal", self.loan.principal.value().accounting()),
+                ("rate", self.loan.rate.value().to_string()),
+                ("months", self.loan.months.value().0.to_string()),
+            ]);
+        }
+        if self.mode == Mode::Ledger {
+            return tr.tr_args("history-summary-ledger", &[("path", self.ledger.path.clone())]);
+        }
+        if self.mode == Mode::Payment {
+            return tr.tr_args("history-summary-payment", &[
+                ("number", self.payment.number.value().0.to_string()),
+                ("amount", self.payment.amount.value().accounting()),
+            ]);
+        }
+        if self.mode == Mode::Invoice {
+            return tr.tr_args("history-summary-invoice", &[
+                ("kind", tr.invoice_kind(self.invoice.kind)),
+                ("number", self.invoice.number.value().0.to_string()),
+                ("amount", self.invoice.document()
+                    .and_then(|document| document.totals())
+                    .map(|totals| totals.gross.accounting())
+                    .unwrap_or_default()),
+            ]);
+        }
+        if self.mode == Mode::Charts {
+            return tr.tr_args("history-summary-charts", &[
+                ("from", self.charts.from.value().format("%d.%m.%Y").to_string()),
+                ("to", self.charts.to.value().format("%d.%m.%Y").to_string()),
+            ]);
+        }
+        if self.mode == Mode::Budget {
+            return tr.tr_args("history-summary-budget", &[
+                ("month", self.budget_input.month.format("%m.%Y").to_string()),
+                ("count", self.budget_input.expenses.len().to_string()),
+            ]);
+        }
+        if self.mode == Mode::Deposit {
+            return tr.tr_args("history-summary-deposit", &[
+                ("initial", self.deposit.initial.value().accounting()),
+                ("rate", self.deposit.rate.value().to_string()),
+                ("months", self.deposit.months.value().0.to_string()),
+            ]);
+        }
+        if self.mode == Mode::Split {
+            let total = self.split.shares(self.rounding)
+                .and_then(|shares| shares.iter().try_fold(Money(Decimal::ZERO), |sum, share| sum.checked_add(*share)))
+                .unwrap_or(*self.split.total.value());
+            return tr.tr_args("history-summary-split", &[
+                ("total", total.accounting()),
+                ("count", self.split.participants.len().to_string()),
+            ]);
+        }
+
+        tr.tr_args("history-summary", &[
+            ("budget", self.budget.value().accounting()),
+            ("price", self.price.value().accounting()),
+            ("quantity", self.count.value().value().to_string()),
+            ("measure", tr.measure_short(&self.measure)),
+        ])
+    }
+
+
+    fn totals_text(&self, tr: &Localizer) -> Result<String, money::Error> {
+        let (vat, leftover) = self.totals()?;
+        let words = |money: Money| money.words(self.words_language, self.scale);
+
+        let total = tr.tr_args("total", &[
+            ("total", words(vat.gross)?),
+            ("quantity", tr.quantity(self.count.value(), &self.measure, self.words_language)),
+            ("vat", tr.vat(&vat, words(vat.vat)?)),
+        ]);
+        let leftover_words = leftover_line(leftover, words, tr)?;
+        let accounting = tr.tr_args("leftover-accounting", &[("amount", leftover.accounting())]);
+
+        Ok(format!("{}\n{}\n{}", total, leftover_words, accounting))
+    }
+
+    /// Adds the purchase to the expenses of its category
+    fn record_purchase(&mut self, tr: &Localizer) {
+        let Ok((vat, _)) = self.totals() else {
+            return;
+        };
+        self.budget_input.expenses.push(Expense {
+            date: Local::now().date_naive(),
+            category: self.purchase_category.clone(),
+            amount: vat.gross,
+            note: format!("{} {}", self.count.value().value(), tr.measure_short(&self.measure))
+        });
+    }
+
+    /// VAT breakdown of the purchase and what is left of the budget
+    fn totals(&self) -> Result<(Vat, Money), money::Error> {
+        let cost = self.count.value().cost(*self.price.value(), self.rounding)?;
+        let vat = Vat::calculate(cost, self.vat_rate, self.vat_mode)?;
+        let available = match self.budget_input.remaining(&self.purchase_category) {
+            Some(remaining) => remaining?,
+            None => *self.budget.value(),
+        };
+        let leftover = available.checked_sub(vat.gross)?;
+
+        Ok((vat, leftover))
+    }
+
+    fn comparison_ui(&mut self, ui: &mut Ui, tr: &Localizer) {
+        let mut removed = None;
+        for (i, offer) in self.offers.iter_mut().enumerate() {
+            ui.separator();
+            ui.horizontal(
+                |ui| {
+                    ui.label(format!("{}.", i + 1));
+                    ui.text_edit_singleline(&mut offer.name);
+                    if ui.button(tr.tr("offer-remove")).clicked() {
+                        removed = Some(i);
+                    }
+                });
+            offer.ui(ui, &format!("offer_measure_{}", i), self.rounding, tr);
+        }
+        if let Some(i) = removed {
+            self.offers.remove(i);
+        }
+        ui.separator();
+        if ui.button(tr.tr("offer-add")).clicked() {
+            self.offers.push(OfferInput::new(MeasureUnit::KILOGRAM));
+        }
+
+        let offers: Result<Vec<Offer>, money::Error> = self.offers
+            .iter()
+            .map(OfferInput::offer)
+            .collect();
+        let ranking = offers.and_then(|offers| comparison::rank(&offers, *self.budget.value()));
+
+        let ranking = match ranking {
+            Ok(ranking) => ranking,
+            Err(error) => {
+                ui.label(tr.error(&error));
+                return;
+            }
+        };
+        let base = ranking.dimension.base();
+
+        Grid::new("ranking")
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label(tr.tr("offer"));
+                ui.label(tr.tr_args("offer-price-per", &[("measure", tr.measure_short(&base))]));
+                ui.label(tr.tr("offer-packs"));
+                ui.label(tr.tr("offer-leftover"));
+                ui.end_row();
+
+                for ranked in ranking.offers.iter() {
+                    let name = match self.offers[ranked.index].name.trim() {
+                        "" => tr.tr_args("offer-numbered", &[("number", (ranked.index + 1).to_string())]),
+                        name => name.to_string()
+                    };
+                    if ranking.cheapest() == Some(ranked) {
+                        ui.strong(tr.tr_args("offer-cheapest", &[("name", name)]));
+                    } else {
+                        ui.label(name);
+                    }
+                    ui.label(Money::round(ranked.base_price, self.rounding).accounting());
+                    ui.label(ranked.packs.0.to_string());
+                    ui.label(ranked.leftover.accounting());
+                    ui.end_row();
+                }
+            });
     }
 }
 
-impl eframe::App for MyApp {
-    fn update(&mut self, ctx: &eframe::egui::Context, frame: &mut eframe::Frame) {
-        CentralPanel::default()
-            .show(ctx, |ui| {
-                let summ = self.price * Money(self.count.0.into());
-                let leftover = self.budget - self.price * self.count;
+impl Calculator {
+    fn ui(&mut self, ui: &mut Ui, tr: &Localizer, session: &mut Session) {
+        ui.horizontal(
+            |ui| {
+                ui.selectable_value(&mut self.mode, Mode::Purchase, tr.tr("mode-purchase"));
+                ui.selectable_value(&mut self.mode, Mode::Split, tr.tr("mode-split"));
+                ui.selectable_value(&mut self.mode, Mode::Loan, tr.tr("mode-loan"));
+                ui.selectable_value(&mut self.mode, Mode::Deposit, tr.tr("mode-deposit"));
+                ui.selectable_value(&mut self.mode, Mode::Budget, tr.tr("mode-budget"));
+                ui.selectable_value(&mut self.mode, Mode::Ledger, tr.tr("mode-ledger"));
+                ui.selectable_value(&mut self.mode, Mode::Charts, tr.tr("mode-charts"));
+                ui.selectable_value(&mut self.mode, Mode::Payment, tr.tr("mode-payment"));
+                ui.selectable_value(&mut self.mode, Mode::Invoice, tr.tr("mode-invoice"));
+            });
+        ui.separator();
 
-                ui.horizontal(
-                    |ui| {
-                        ui.label("Бюджет: ");
-                        ui.text_edit_singleline(&mut self.budget);
+        let (language, scale) = (self.words_language, self.scale);
+        let words = move |money: Money| money.words(language, scale);
+        match self.mode {
+            Mode::Purchase => self.purchase_ui(ui, tr, session),
+            Mode::Split => self.split.ui(ui, self.rounding, words, tr),
+            Mode::Loan => self.loan.ui(ui, self.rounding, words, tr, &mut session.notice),
+            Mode::Deposit => self.deposit.ui(ui, &mut self.budget, self.rounding, words, tr),
+            Mode::Budget => self.budget_input.ui(ui, self.rounding, words, tr),
+            Mode::Ledger => self.ledger.ui(ui, &mut self.budget_input, self.rounding, words, tr, session),
+            Mode::Charts => self.charts.ui(ui, &self.budget_input, self.rounding, tr),
+            Mode::Payment => self.payment.ui(ui, self.rounding, tr, &mut session.notice),
+            Mode::Invoice => self.invoice.ui(ui, self.rounding, tr, &mut session.notice),
+        }
+    }
+
+    fn purchase_ui(&mut self, ui: &mut Ui, tr: &Localizer, session: &mut Session) {
+        ui.horizontal(
+            |ui| {
+                ui.label(tr.tr("budget"));
+                field_edit(ui, &mut self.budget);
+                ui.radio_value(&mut self.scale, Scale::Short, tr.tr("scale-short"));
+                ui.radio_value(&mut self.scale, Scale::Long, tr.tr("scale-long"));
+            });
+        ui.horizontal(
+            |ui| {
+                let names: Vec<String> = self.budget_input.categories.iter().map(|category| category.name.trim().to_string()).collect();
+                ui.label(tr.tr("budget-category"));
+                BudgetInput::category_combo(ui, "purchase_category", &mut self.purchase_category, &names, Some(tr.tr("budget-field")));
+            });
+        field_error(ui, &mut self.budget, self.rounding, tr);
+        match self.budget.value().words(self.words_language, self.scale) {
+            Ok(pronounced) => ui.label(pronounced),
+            Err(error) => ui.colored_label(Color32::RED, tr.error(&error)),
+        };
+        ui.horizontal(
+            |ui| {
+                ui.label(tr.tr("price"));
+                field_edit(ui, &mut self.price);
+            });
+        field_error(ui, &mut self.price, self.rounding, tr);
+        match SignPolicy::NonNegative.check(*self.price.value()).and_then(|price| price.words(self.words_language, self.scale)) {
+            Ok(pronounced) => ui.label(pronounced),
+            Err(error) => ui.colored_label(Color32::RED, tr.error(&error)),
+        };
+        ui.horizontal(
+            |ui| {
+                ui.label(tr.tr("quantity"));
+                field_edit(ui, &mut self.count);
+                ComboBox::from_id_source("measure")
+                    .selected_text(tr.measure_short(&self.measure))
+                    .show_ui(ui, |ui| {
+                        for measure in MeasureUnit::CATALOGUE.iter().chain(self.custom_measures.iter()) {
+                            let label = format!("{} ({})", tr.measure_name(measure), tr.measure_short(measure));
+                            ui.selectable_value(&mut self.measure, measure.clone(), label);
+                        }
                     });
-                ui.label(format!("{}",self.budget));
+            }
+        );
+        field_error(ui, &mut self.count, self.rounding, tr);
+        ui.collapsing(tr.tr("custom-measure"), |ui| {
+            ui.horizontal(
+                |ui| {
+                    ui.label(tr.tr("measure-short"));
+                    ui.text_edit_singleline(&mut self.new_measure_short);
+                });
+            for (label, form) in ["measure-one", "measure-few", "measure-many"].into_iter().zip(self.new_measure_forms.iter_mut()) {
                 ui.horizontal(
                     |ui| {
-                        ui.label("Цена: ");
-                        ui.text_edit_singleline(&mut self.price);
+                        ui.label(tr.tr(label));
+                        ui.text_edit_singleline(form);
                     });
-                ui.label(format!("{}",self.price));
-                ui.horizontal(
-                    |ui| {
-                        ui.label("Количество: ");
-                        ui.text_edit_singleline(&mut self.count);
-                    }
+            }
+            ui.horizontal(
+                |ui| {
+                    ui.radio_value(&mut self.new_measure_gender, Gender::Masculine, tr.tr("gender-masculine"));
+                    ui.radio_value(&mut self.new_measure_gender, Gender::Feminine, tr.tr("gender-feminine"));
+                });
+            let filled = !self.new_measure_short.is_empty() &&
+                self.new_measure_forms.iter().all(|form| !form.is_empty());
+            if ui.add_enabled(filled, Button::new(tr.tr("add"))).clicked() {
+                let measure = MeasureUnit::custom(
+                    std::mem::take(&mut self.new_measure_short),
+                    std::mem::take(&mut self.new_measure_forms),
+                    self.new_measure_gender
                 );
-                ui.label(format!("{}",self.count));
-                ui.label(format!("Итоговая сумма: {} за {}.\n{} {}",
-                    summ,
-                    self.count,
-                    if leftover.0.is_sign_positive() {
-                        "В остатке:"
-                    } else {
-                        "Не хватает: "
-                    },
-                    leftover.abs()
-                ));
+                self.measure = measure.clone();
+                self.custom_measures.push(measure);
+            }
+        });
+        ui.horizontal(
+            |ui| {
+                let mut precision = self.count.value().precision();
+                ui.label(tr.tr("precision"));
+                if ui.add(DragValue::new(&mut precision).range(0..=Quantity::MAX_PRECISION)).changed() {
+                    if let Ok(count) = self.count.value().with_precision(precision, self.rounding) {
+                        self.count.set(count);
+                    }
+                }
+                ui.label(tr.tr("rounding"));
+                ComboBox::from_id_source("rounding")
+                    .selected_text(tr.rounding(self.rounding))
+                    .show_ui(ui, |ui| {
+                        for rounding in Rounding::ALL {
+                            ui.selectable_value(&mut self.rounding, rounding, tr.rounding(rounding));
+                        }
+                    });
+            }
+        );
+        ui.label(tr.quantity(self.count.value(), &self.measure, self.words_language));
+        ui.horizontal(
+            |ui| {
+                ui.label(tr.tr("vat-rate"));
+                ComboBox::from_id_source("vat_rate")
+                    .selected_text(match self.vat_rate {
+                        VatRate::Custom(_) => tr.tr("vat-custom"),
+                        rate => tr.vat_rate(rate)
+                    })
+                    .show_ui(ui, |ui| {
+                        for rate in VatRate::PRESETS {
+                            ui.selectable_value(&mut self.vat_rate, rate, tr.vat_rate(rate));
+                        }
+                        if ui.selectable_label(matches!(self.vat_rate, VatRate::Custom(_)), tr.tr("vat-custom")).clicked() {
+                            self.vat_rate = VatRate::Custom(*self.custom_vat_rate.value());
+                        }
+                    });
+                if let VatRate::Custom(_) = self.vat_rate {
+                    if field_edit(ui, &mut self.custom_vat_rate).changed() {
+                        self.vat_rate = VatRate::Custom(*self.custom_vat_rate.value());
+                    }
+                    ui.label("%");
+                }
+            }
+        );
+        if let VatRate::Custom(_) = self.vat_rate {
+            field_error(ui, &mut self.custom_vat_rate, self.rounding, tr);
+        }
+        ui.horizontal(
+            |ui| {
+                ui.radio_value(&mut self.vat_mode, VatMode::Extract, tr.tr("vat-extract"));
+                ui.radio_value(&mut self.vat_mode, VatMode::Add, tr.tr("vat-add"));
+            }
+        );
+        match self.totals_text(tr) {
+            Ok(text) => {
+                ui.label(text);
+                if !self.purchase_category.is_empty() && ui.button(tr.tr("budget-record")).clicked() {
+                    self.record_purchase(tr);
+                }
+            },
+            Err(error) => {
+                ui.colored_label(Color32::RED, tr.error(&error));
+            }
+        };
+
+        ui.collapsing(tr.tr("receipt"), |ui| self.receipt_ui(ui, tr, session));
+        ui.collapsing(tr.tr("comparison"), |ui| self.comparison_ui(ui, tr));
+    }
+
+    /// Applies the receipt recognized in the background, whatever mode is shown by then
+    /// `true` when a recognized receipt filled the form
+    fn poll_recognition(&mut self, ctx: &eframe::egui::Context, tr: &Localizer, session: &mut Session) -> bool {
+        let Some(recognition) = &session.recognition else {
+            return false;
+        };
+        match recognition.try_recv() {
+            Ok(Ok(receipt)) => {
+                self.receipt = receipt.to_string();
+                self.price.set(receipt.sum);
+                session.notice = Some(Ok(tr.tr_args("receipt-recognized", &[("sum", receipt.sum.accounting())])));
+                session.recognition = None;
+                return true;
+            },
+            Ok(Err(error)) => {
+                session.notice = Some(Err(tr.error(&error)));
+                session.recognition = None;
+            },
+            Err(TryRecvError::Empty) => ctx.request_repaint_after(Duration::from_millis(100)),
+            Err(TryRecvError::Disconnected) => session.recognition = None,
+        }
+        false
+    }
 
+    /// Receipt QR string, typed or read from a photo: its sum becomes the price or the budget,
+    /// or goes to the expenses
+    fn receipt_ui(&mut self, ui: &mut Ui, tr: &Localizer, session: &mut Session) {
+        ui.horizontal(
+            |ui| {
+                ui.label(tr.tr("receipt-image"));
+                ui.text_edit_singleline(&mut self.receipt_image);
+                if session.recognition.is_some() {
+                    ui.spinner();
+                    ui.label(tr.tr("receipt-recognizing"));
+                } else if ui.add_enabled(!self.receipt_image.trim().is_empty(), Button::new(tr.tr("receipt-recognize"))).clicked() {
+                    let (sender, receiver) = mpsc::channel();
+                    let path = self.receipt_image.trim().to_string();
+                    std::thread::spawn(move || sender.send(receipt::from_image(path)));
+                    session.recognition = Some(receiver);
+                }
+            });
+        ui.horizontal(
+            |ui| {
+                ui.label(tr.tr("receipt-qr"));
+                ui.add(TextEdit::singleline(&mut self.receipt).hint_text("t=20241001T1230&s=1234.50&fn=...&i=...&fp=...&n=1"));
+            });
+        if self.receipt.trim().is_empty() { return; }
+
+        let receipt = match self.receipt.parse::<Receipt>() {
+            Ok(receipt) => receipt,
+            Err(error) => {
+                ui.colored_label(Color32::RED, tr.error(&error));
+                return;
+            }
+        };
+        ui.label(tr.tr_args("receipt-header", &[
+            ("operation", tr.operation(receipt.operation)),
+            ("time", receipt.time.format("%d.%m.%Y %H:%M").to_string()),
+            ("sum", receipt.sum.accounting()),
+        ]));
+        ui.label(receipt.note());
+        ui.horizontal(
+            |ui| {
+                if ui.button(tr.tr("receipt-to-price")).clicked() {
+                    self.price.set(receipt.sum);
+                }
+                if ui.button(tr.tr("receipt-to-budget")).clicked() {
+                    self.budget.set(receipt.sum);
+                }
+                if ui.add_enabled(!self.purchase_category.is_empty(), Button::new(tr.tr("receipt-to-expenses"))).clicked() {
+                    self.budget_input.expenses.push(receipt.expense(&self.purchase_category));
+                    self.receipt.clear();
+                }
             });
     }
 }
 
-fn main() {
-    let options = eframe::NativeOptions::default();
-
-    eframe::run_native(
-        "Money counter",
-        options,
-        Box::new(|ctx| {
-            Ok( Box::new(MyApp::new(ctx) ))
-        })           
-    ).unwrap();
+struct MyApp {
+    calculator: Calculator,
+    history: History<Calculator>,
+    /// Widget that had the keyboard last frame, an edit is finished when it changes
+    focused: Option<Id>,
+    localizer: Localizer,
+    session: Session
 }
 
+impl MyApp {
+    const HISTORY_LIMIT: usize = 100;
 
-// fn main() -> anyhow::Result<()> {
-//     let budget: Money = match args().nth(1) {
-//         Some(val) => {
-//             let dec = Decimal::from_str_exact(&val)?;
-//             dec.try_into()?
-//         },
-//         None => {
-//             Err(money::Error::BudgetNotPresented)?
-//         },
-//     };
+    fn new(_cc: &eframe::CreationContext<'_>) -> Self {
+        let calculator = Calculator::default();
+        MyApp {
+            history: History::new(calculator.clone(), MyApp::HISTORY_LIMIT),
+            calculator,
+            focused: None,
+            localizer: Localizer::new(Language::default()),
+            session: Session::default()
+        }
+    }
 
-//     const PRICE: Money = Money(Decimal::from_parts(8999,0 ,0 ,false , 2));
+    fn undo(&mut self) {
+        // Typing that wasn't finished yet is a step of its own, so that redo brings it back
+        self.history.commit(&self.calculator);
+        if let Some(calculator) = self.history.undo() {
+            self.calculator = calculator;
+        }
+    }
 
-//     let count = budget.into() / PRICE.into();
-//     let units = Unit(count.trunc().try_into()?);
+    fn redo(&mut self) {
+        if let Some(calculator) = self.history.redo() {
+            self.calculator = calculator;
+        }
+    }
 
-//     let summ: Money = Money(
-//         PRICE.into() * Decimal::from(&units)
-//     );
+    fn languages_ui(&mut self, ui: &mut Ui) {
+        ui.horizontal(
+            |ui| {
+                let mut interface = self.localizer.language();
+                ui.label(self.localizer.tr("interface-language"));
+                ComboBox::from_id_source("interface_language")
+                    .selected_text(interface.native_name())
+                    .show_ui(ui, |ui| {
+                        for language in Language::ALL {
+                            ui.selectable_value(&mut interface, language, language.native_name());
+                        }
+                    });
+                self.localizer.set_language(interface);
 
-//     let leftover = Money(
-//         budget.0 - summ.0
-//     );
+                ui.label(self.localizer.tr("words-language"));
+                ComboBox::from_id_source("words_language")
+                    .selected_text(self.calculator.words_language.native_name())
+                    .show_ui(ui, |ui| {
+                        for language in Language::ALL {
+                            ui.selectable_value(&mut self.calculator.words_language, language, language.native_name());
+                        }
+                    });
+            });
+    }
 
-//     println!("Размер бюджета: {}",budget);
-//     println!("Цена за единицу товара: {}",PRICE);
+    fn history_ui(&mut self, ui: &mut Ui) {
+        ui.horizontal(
+            |ui| {
+                if ui.add_enabled(self.history.can_undo(), Button::new(self.localizer.tr("undo"))).clicked() {
+                    self.undo();
+                }
+                if ui.add_enabled(self.history.can_redo(), Button::new(self.localizer.tr("redo"))).clicked() {
+                    self.redo();
+                }
+            });
+        ui.separator();
 
+        let mut restored = None;
+        ScrollArea::vertical().show(ui, |ui| {
+            for (i, snapshot) in self.history.journal().iter().enumerate().rev() {
+                let text = format!("{} — {}", snapshot.time.format("%H:%M:%S"), snapshot.state.summary(&self.localizer));
+                if ui.selectable_label(snapshot.state == self.calculator, text).clicked() {
+                    restored = Some(i);
+                }
+            }
+        });
+        if let Some(calculator) = restored.and_then(|i| self.history.restore(i)) {
+            self.calculator = calculator;
+        }
+    }
+}
 
-//     print!("За {} можно приобрести {} товара ",budget,units);
-//     println!("на сумму {}, имея в остатке {}",summ,leftover);
+impl eframe::App for MyApp {
+    fn update(&mut self, ctx: &eframe::egui::Context, _frame: &mut eframe::Frame) {
+        // Taken before the text fields see them, so that Ctrl+Z undoes the whole form
+        let redo = ctx.input_mut(|input| input.consume_shortcut(&KeyboardShortcut::new(Modifiers::COMMAND | Modifiers::SHIFT, Key::Z)));
+        let undo = ctx.input_mut(|input| input.consume_shortcut(&KeyboardShortcut::new(Modifiers::COMMAND, Key::Z)));
+        if redo {
+            self.redo();
+        } else if undo {
+            self.undo();
+        }
+        let recognized = self.calculator.poll_recognition(ctx, &self.localizer, &mut self.session);
 
+        TopBottomPanel::top("languages")
+            .show(ctx, |ui| self.languages_ui(ui));
+        if let Some(notice) = &self.session.notice {
+            let mut closed = false;
+            TopBottomPanel::bottom("notice")
+                .show(ctx, |ui| {
+                    ui.horizontal(
+                        |ui| {
+                            match notice {
+                                Ok(message) => ui.label(message),
+                                Err(message) => ui.colored_label(Color32::RED, message),
+                            };
+                            closed = ui.button(self.localizer.tr("close")).clicked();
+                        });
+                });
+            if closed {
+                self.session.notice = None;
+            }
+        }
+        SidePanel::right("history")
+            .show(ctx, |ui| {
+                ui.heading(self.localizer.tr("history"));
+                self.history_ui(ui);
+            });
+        CentralPanel::default()
+            .show(ctx, |ui| {
+                ScrollArea::vertical().show(ui, |ui| self.calculator.ui(ui, &self.localizer, &mut self.session));
+            });
 
-//     Ok(())
-    
-// }
+        // Clicks and drags end when the button is released, typing when the field loses the keyboard
+        let focused = ctx.memory(|memory| memory.focused());
+        let released = ctx.input(|input| input.pointer.any_released());
+        if recognized || released || focused != self.focused {
+            self.history.commit(&self.calculator);
+        }
+        self.focused = focused;
+    }
+}
+
+fn main() {
+    let options = eframe::NativeOptions::default();
+
+    eframe::run_native(
+        "Money counter",
+        options,
+        Box::new(|ctx| {
+            Ok( Box::new(MyApp::new(ctx) ))
+        })           
+    ).unwrap();
+}
@@ -0,0 +1,125 @@
+use std::borrow::Cow;
+
+use crate::{pronounce::NumberPronouce, unit::Unit};
+
+/// Grammatical gender of a noun, decides between "один/одна" and "два/две"
+#[derive(Debug,
+    PartialEq, Eq,
+    Clone, Copy)]
+pub enum Gender {
+    Masculine,
+    Feminine
+}
+
+#[derive(Debug,
+    PartialEq, Eq,
+    Clone)]
+pub struct MeasureUnit {
+    /// Catalogue units are named in the interface by `measure-<id>` messages,
+    /// the user's own units have no id and show what the user typed
+    pub id: Cow<'static, str>,
+    /// Abbreviation, e.g. "кг"
+    pub short: Cow<'static, str>,
+    /// Forms for 1, 2-4 and 5+: "штука", "штуки", "штук", what the Russian words agree with
+    pub forms: [Cow<'static, str>;3],
+    pub gender: Gender
+}
+
+const fn unit(id: &'static str, short: &'static str, forms: [&'static str;3], gender: Gender) -> MeasureUnit {
+    MeasureUnit {
+        id: Cow::Borrowed(id),
+        short: Cow::Borrowed(short),
+        forms: [Cow::Borrowed(forms[0]), Cow::Borrowed(forms[1]), Cow::Borrowed(forms[2])],
+        gender
+    }
+}
+
+impl MeasureUnit {
+    pub const ITEM: MeasureUnit = unit("item", "ед.", ["единица", "единицы", "единиц"], Gender::Feminine);
+    pub const PIECE: MeasureUnit = unit("piece", "шт", ["штука", "штуки", "штук"], Gender::Feminine);
+    pub const GRAM: MeasureUnit = unit("gram", "г", ["грамм", "грамма", "граммов"], Gender::Masculine);
+    pub const KILOGRAM: MeasureUnit = unit("kilogram", "кг", ["килограмм", "килограмма", "килограммов"], Gender::Masculine);
+    pub const TONNE: MeasureUnit = unit("tonne", "т", ["тонна", "тонны", "тонн"], Gender::Feminine);
+    pub const MILLILITRE: MeasureUnit = unit("millilitre", "мл", ["миллилитр", "миллилитра", "миллилитров"], Gender::Masculine);
+    pub const LITRE: MeasureUnit = unit("litre", "л", ["литр", "литра", "литров"], Gender::Masculine);
+    pub const CENTIMETRE: MeasureUnit = unit("centimetre", "см", ["сантиметр", "сантиметра", "сантиметров"], Gender::Masculine);
+    pub const METRE: MeasureUnit = unit("metre", "м", ["метр", "метра", "метров"], Gender::Masculine);
+    pub const PACK: MeasureUnit = unit("pack", "уп", ["упаковка", "упаковки", "упаковок"], Gender::Feminine);
+    pub const BOX: MeasureUnit = unit("box", "кор", ["коробка", "коробки", "коробок"], Gender::Feminine);
+    pub const HOUR: MeasureUnit = unit("hour", "ч", ["час", "часа", "часов"], Gender::Masculine);
+
+    pub const CATALOGUE: [MeasureUnit;12] = [
+        MeasureUnit::ITEM,
+        MeasureUnit::PIECE,
+        MeasureUnit::GRAM,
+        MeasureUnit::KILOGRAM,
+        MeasureUnit::TONNE,
+        MeasureUnit::MILLILITRE,
+        MeasureUnit::LITRE,
+        MeasureUnit::CENTIMETRE,
+        MeasureUnit::METRE,
+        MeasureUnit::PACK,
+        MeasureUnit::BOX,
+        MeasureUnit::HOUR,
+    ];
+
+    pub fn custom(short: String, forms: [String;3], gender: Gender) -> Self {
+        let [one, few, many] = forms;
+        MeasureUnit {
+            id: Cow::Borrowed(""),
+            short: Cow::Owned(short),
+            forms: [Cow::Owned(one), Cow::Owned(few), Cow::Owned(many)],
+            gender
+        }
+    }
+
+    pub fn forms(&self) -> [&str;3] {
+        [&self.forms[0], &self.forms[1], &self.forms[2]]
+    }
+}
+
+impl Default for MeasureUnit {
+    fn default() -> Self {
+        MeasureUnit::ITEM
+    }
+}
+
+/// Count with its unit of measure: "двадцать одна штука", "два килограмма"
+pub struct Measured<'a>(pub Unit, pub &'a MeasureUnit);
+
+impl std::fmt::Display for Measured<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let triplets = self.into_triplets();
+        let mut measure_pronounce = "";
+        if let Some(triplet) = triplets.first() {
+            measure_pronounce = triplet.triplet_pronounce(self.1.forms());
+        }
+
+        match self.pronounce() {
+            // Triplets of zero say nothing, the noun alone would lose the number
+            Ok(_) if self.0.0 == 0 => write!(f,"ноль {}",measure_pronounce),
+            Ok(pronounced) => write!(f,"{} {}",pronounced.trim(),measure_pronounce),
+            Err(_) => write!(f,"{} {}",self.0.0,self.1.short),
+        }
+    }
+}
+
+impl NumberPronouce for Measured<'_> {
+    fn into_triplets(&self) -> Vec<crate::pronounce::Triplet> {
+        self.0.triplets_with_gender(self.1.gender)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zero_is_said() {
+        assert_eq!(Measured(Unit(0), &MeasureUnit::PIECE).to_string(), "ноль штук");
+        assert_eq!(Measured(Unit(0), &MeasureUnit::KILOGRAM).to_string(), "ноль килограммов");
+        assert_eq!(Measured(Unit(21), &MeasureUnit::PIECE).to_string(), "двадцать одна штука");
+        assert_eq!(Measured(Unit(2), &MeasureUnit::KILOGRAM).to_string(), "два килограмма");
+    }
+}
@@ -1,35 +1,171 @@
-use std::{str::FromStr, u128};
+use rust_decimal::{prelude::{FromPrimitive, ToPrimitive}, Decimal, RoundingStrategy};
 
-
-use eframe::egui;
-use egui::TextBuffer;
-use rust_decimal::{prelude::FromPrimitive, Decimal};
-
-use crate::{pronounce::{NumberPronouce, Triplet}, unit::Unit};
+use crate::{english, pronounce::{Language, NumberPronouce, Scale, Triplet}, unit::Unit};
 
 #[derive(Debug,
     PartialEq, Eq, PartialOrd, Ord,
     Clone, Copy)]
 pub struct Money(pub Decimal);
 
-type MoneyDecomposed = (u128,u8);
+#[derive(Debug,
+    PartialEq, Eq,
+    Clone, Copy)]
+pub enum Sign {
+    Positive,
+    Negative
+}
+
+/// Sign, rubles and kopeks
+type MoneyDecomposed = (Sign,u128,u8);
+
+/// Whether an amount may be below zero: a leftover may, a price may not
+#[derive(Debug,
+    PartialEq, Eq,
+    Clone, Copy,
+    Default)]
+pub enum SignPolicy {
+    #[default]
+    Signed,
+    NonNegative
+}
+
+impl SignPolicy {
+    pub fn check(&self, value: Money) -> Result<Money, Error> {
+        match (self, value.sign()) {
+            (SignPolicy::NonNegative, Sign::Negative) => Err(Error::NegativeAmount),
+            _ => Ok(value),
+        }
+    }
+}
+
+/// How a computed amount is brought to whole kopeks
+#[derive(Debug,
+    PartialEq, Eq,
+    Clone, Copy,
+    Default)]
+pub enum Rounding {
+    /// 0.005 -> 0.01, the usual accounting rule
+    #[default]
+    HalfUp,
+    /// 0.005 -> 0.00, 0.015 -> 0.02
+    HalfEven,
+    /// Towards zero, in the buyer's favour
+    Down,
+    /// Away from zero, in the seller's favour
+    Up
+}
+
+impl Rounding {
+    pub const ALL: [Rounding;4] = [Rounding::HalfUp, Rounding::HalfEven, Rounding::Down, Rounding::Up];
+
+    pub fn strategy(&self) -> RoundingStrategy {
+        match self {
+            Rounding::HalfUp => RoundingStrategy::MidpointAwayFromZero,
+            Rounding::HalfEven => RoundingStrategy::MidpointNearestEven,
+            Rounding::Down => RoundingStrategy::ToZero,
+            Rounding::Up => RoundingStrategy::AwayFromZero,
+        }
+    }
+}
 
 impl Money {
+    /// Brings any computed value to kopeks
+    pub fn round(value: Decimal, rounding: Rounding) -> Self {
+        Money(value.round_dp_with_strategy(2, rounding.strategy()))
+    }
+
     pub fn abs(&self) -> Self {
         Money(self.0.abs())
     }
 
-    fn decompose(&self) -> MoneyDecomposed {
-        let unpacked = self.0.fract().unpack();
-        let unpacked_trunc = self.0.trunc().unpack();
+    /// Whole number of kopeks, for exact sharing of an amount
+    pub fn kopeks(&self) -> Result<i128, Error> {
+        let mut value = self.0.round_dp(2);
+        value.rescale(2);
+        if value.scale() != 2 { return Err(Error::OutOfRange); }
+
+        Ok(value.mantissa())
+    }
+
+    pub fn from_kopeks(kopeks: i128) -> Result<Self, Error> {
+        Decimal::try_from_i128_with_scale(kopeks, 2)
+            .map(Money)
+            .map_err(|_| Error::OutOfRange)
+    }
 
-        let rubles: u128 = ((unpacked_trunc.hi as u128) << 64) |
-            ((unpacked_trunc.mid as u128) << 32) |
-            ((unpacked_trunc.lo) as u128);
-        let mut kopek: u8 = unpacked.lo as u8;
-        if unpacked.scale == 1 { kopek *= 10; }
+    /// Only for drawing, amounts are never computed in floating point
+    pub fn to_f64(&self) -> f64 {
+        self.0.to_f64().unwrap_or_default()
+    }
 
-        (rubles,kopek)
+    /// Zero is positive, so "-0.00" is never pronounced as "минус"
+    pub fn sign(&self) -> Sign {
+        if self.0.is_sign_negative() && !self.0.is_zero() {
+            Sign::Negative
+        } else {
+            Sign::Positive
+        }
+    }
+
+    pub(crate) fn decompose(&self) -> MoneyDecomposed {
+        let magnitude = self.0.abs().round_dp(2);
+
+        let rubles: u128 = magnitude.trunc().mantissa().unsigned_abs();
+        let kopek: u8 = (magnitude.fract() * Decimal::ONE_HUNDRED)
+            .trunc()
+            .mantissa()
+            .unsigned_abs() as u8;
+
+        (self.sign(),rubles,kopek)
+    }
+
+    /// Amount in words, with "миллиард" or "биллион" depending on `scale`
+    pub fn pronounce_in(&self, scale: Scale) -> Result<String, Error> {
+        let pronounced = self.decompose().pronounce_in(scale)?;
+        Ok(pronounced.split_whitespace().collect::<Vec<&str>>().join(" "))
+    }
+
+    /// Amount in words in the given language; `scale` only matters for Russian
+    pub fn words(&self, language: Language, scale: Scale) -> Result<String, Error> {
+        match language {
+            Language::Russian => self.pronounce_in(scale),
+            Language::English => english::pronounce_money(self),
+        }
+    }
+
+    /// "Одна тысяча пятьсот рублей 05 копеек": rubles in words and kopeks in digits,
+    /// the way payment documents write the amount
+    pub fn document_words(&self) -> Result<String, Error> {
+        let (sign, rubles, kopek) = self.decompose();
+        if sign == Sign::Negative { return Err(Error::NegativeAmount); }
+
+        let rubles = Decimal::from_u128(rubles).map(Money).ok_or(Error::OutOfRange)?.pronounce_in(Scale::Short)?;
+        let digits = format!("{:02}", kopek);
+        let kopeks = Triplet::new([b'0', digits.as_bytes()[0], digits.as_bytes()[1]], 0)
+            .triplet_pronounce(["копейка", "копейки", "копеек"]);
+
+        let mut chars = rubles.chars();
+        let capital: String = chars.next().into_iter().flat_map(char::to_uppercase).chain(chars).collect();
+        Ok(format!("{} {} {}", capital, digits, kopeks))
+    }
+
+    /// Accounting notation: "1 234,56", negatives in parentheses "(1 234,56)"
+    pub fn accounting(&self) -> String {
+        let (sign, rubles, kopek) = self.decompose();
+
+        let digits = rubles.to_string();
+        let mut grouped = String::new();
+        for (i, digit) in digits.chars().enumerate() {
+            if i != 0 && (digits.len() - i) % 3 == 0 {
+                grouped.push('\u{a0}');
+            }
+            grouped.push(digit);
+        }
+
+        match sign {
+            Sign::Positive => format!("{},{:02}",grouped,kopek),
+            Sign::Negative => format!("({},{:02})",grouped,kopek),
+        }
     }
 }
 
@@ -37,20 +173,20 @@ impl NumberPronouce for MoneyDecomposed {
     fn into_triplets(&self) -> Vec<crate::pronounce::Triplet> {
         let mut triplets: Vec<Triplet> = Vec::new();
 
-        let mut rubles_str = self.0.to_string();
+        let mut rubles_str = self.1.to_string();
 
         match rubles_str.chars().count() % 3 {
             1 => rubles_str.insert_str(0,"00"),
-            2 => rubles_str.insert_str(0,"0"),
+            2 => rubles_str.insert(0, '0'),
             _ => ()
         }
 
         let mut kopek_slice: [u8;3] = [0u8;3];
         let mut kopek_string = "0".to_string();
 
-        match self.1 {
-            1..10 => { kopek_string.push('0'); kopek_string.push_str(&self.1.to_string())},
-            10..100 => { kopek_string.push_str(&self.1.to_string())},
+        match self.2 {
+            1..10 => { kopek_string.push('0'); kopek_string.push_str(&self.2.to_string())},
+            10..100 => { kopek_string.push_str(&self.2.to_string())},
             _ => ()
         }
 
@@ -89,29 +225,34 @@ impl NumberPronouce for MoneyDecomposed {
         triplets
     }
 
-    fn pronounce(&self) -> String {
+    fn pronounce_in(&self, scale: Scale) -> Result<String, Error> {
         let mut result = String::new();
         let mut kopek_result = String::new();
 
+        if self.0 == Sign::Negative {
+            result.push_str("минус ");
+        }
+
         let mut triplets = self.into_triplets();
-        if self.1 != 0 {
+        if self.2 != 0 {
             if let Some(kopek) = &triplets.pop() {
-                kopek_result.push_str(&kopek.into_feminie().pronounce());
+                kopek_result.push_str(&kopek.into_feminie().pronounce()?);
                 kopek_result.push_str(
-                    &kopek.triplet_pronounce(
+                    kopek.triplet_pronounce(
                         ["копейка", "копейки", "копеек"]
                     )
                 );
             };
         }
 
-        triplets
-            .iter()
-            .rev()
-            .for_each( |triplet| {
-                result.push_str(&triplet.pronounce());
-                result.push(' ');
-            });
+        if self.1 == 0 {
+            result.push_str("ноль ");
+        }
+
+        for triplet in triplets.iter().rev() {
+            result.push_str(&triplet.with_scale(scale).pronounce()?);
+            result.push(' ');
+        }
 
         if let Some(triplet) = triplets.first() {
             result.push_str(
@@ -126,73 +267,54 @@ impl NumberPronouce for MoneyDecomposed {
         result.push_str(&kopek_result);
 
 
-        result
+        Ok(result)
     }
 
     
 }
 
-impl std::ops::Add for Money {
-    type Output = Self;
-
-    fn add(self, rhs: Self) -> Self::Output {
-        Self(self.0 + rhs.0)
-    }
+/// Right-hand side of checked multiplication and division
+pub trait Operand {
+    fn to_decimal(&self) -> Result<Decimal, Error>;
 }
 
-impl std::ops::Sub for Money {
-    type Output = Self;
-
-    fn sub(self, rhs: Self) -> Self::Output {
-        Self(self.0 - rhs.0)
+impl Operand for Money {
+    fn to_decimal(&self) -> Result<Decimal, Error> {
+        Ok(self.0)
     }
 }
 
-impl std::ops::Mul for Money {
-    type Output = Self;
-
-    fn mul(self, rhs: Self) -> Self::Output {
-        Self(self.0 * rhs.0)
+impl Operand for Unit {
+    fn to_decimal(&self) -> Result<Decimal, Error> {
+        Decimal::try_from(self)
     }
 }
 
-impl std::ops::Div for Money {
-    type Output = Self;
-
-    fn div(self, rhs: Self) -> Self::Output {
-        Self(self.0 / rhs.0)
+impl Operand for Decimal {
+    fn to_decimal(&self) -> Result<Decimal, Error> {
+        Ok(*self)
     }
 }
 
-impl std::ops::Add<Unit> for Money {
-    type Output = Self;
-
-    fn add(self, rhs: Unit) -> Self::Output {
-        self + self * rhs
+impl Money {
+    pub fn checked_add(self, rhs: Money) -> Result<Self, Error> {
+        self.0.checked_add(rhs.0).map(Money).ok_or(Error::Overflow)
     }
-}
-
-impl std::ops::Sub<Unit> for Money {
-    type Output = Self;
 
-    fn sub(self, rhs: Unit) -> Self::Output {
-        self - self * rhs
+    pub fn checked_sub(self, rhs: Money) -> Result<Self, Error> {
+        self.0.checked_sub(rhs.0).map(Money).ok_or(Error::Overflow)
     }
-}
 
-impl std::ops::Mul<Unit> for Money {
-    type Output = Self;
-
-    fn mul(self, rhs: Unit) -> Self::Output {
-        Self(self.0 * Decimal::from_u128(rhs.0).unwrap())
+    pub fn checked_mul(self, rhs: impl Operand) -> Result<Self, Error> {
+        self.0.checked_mul(rhs.to_decimal()?).map(Money).ok_or(Error::Overflow)
     }
-}
 
-impl std::ops::Div<Unit> for Money {
-    type Output = Self;
+    /// Result is not rounded to kopeks, use `Money::round` for that
+    pub fn checked_div(self, rhs: impl Operand) -> Result<Self, Error> {
+        let rhs = rhs.to_decimal()?;
+        if rhs.is_zero() { return Err(Error::DivisionByZero); }
 
-    fn div(self, rhs: Unit) -> Self::Output {
-        Self(self.0 / Decimal::from_u128(rhs.0).unwrap())
+        self.0.checked_div(rhs).map(Money).ok_or(Error::Overflow)
     }
 }
 
@@ -200,93 +322,61 @@ impl TryFrom<&str> for Money {
     type Error = Error;
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
-        let number = Decimal::from_str_exact(&value)?;
-        Ok(number.try_into()?)
+        let number = Decimal::from_str_exact(value)?;
+        number.try_into()
     }
 }
 
 impl TryFrom<Decimal> for Money {
     type Error = Error;
 
+    /// Zeros after the kopeks are fine, "100.000" is a hundred rubles
     fn try_from(value: Decimal) -> Result<Self,Self::Error> {
-        let unpacked = value.unpack();
+        if value.normalize().scale() > 2 { return Err(Error::KopekDigitsTooBig); };
 
-        if unpacked.scale > 2 { return Err(Error::KopekDigitsTooBig); };
-        
-        Ok(Money(value))
+        Ok(Money(value.round_dp(2)))
     }
 }
 
 impl std::fmt::Display for Money {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.decompose().pronounce())
-    }
-}
-
-impl TextBuffer for Money {
-    fn is_mutable(&self) -> bool {
-        true
-    }
-
-    fn as_str(&self) -> &str {
-        let formatted = format!("{}",self.0);
-        Box::leak(formatted.into_boxed_str())
-    }
-
-    fn insert_text(&mut self, text: &str, char_index: usize) -> usize {
-        let current_string = self.0.to_string();
-        let mut new_string = String::new();
-
-        if char_index > current_string.len() {
-            return 0;
-        }
-
-        if char_index == current_string.len() && current_string.len() > 3 && &current_string[current_string.len() - 2..] == ".0" {
-            new_string.push_str(&current_string[..char_index-2]);
-            new_string.push_str(".");
-            new_string.push_str(text);
-        } else {
-            new_string.push_str(&current_string[..char_index]);
-            new_string.push_str(text);
-            new_string.push_str(&current_string[char_index..]);
-        }
-        if text == "." && char_index == current_string.len() {
-            new_string.push_str("0");
-        }
-
-        match Money::try_from(new_string.as_str()) {
-            Ok(n) => {
-                self.0 = n.0;
-                new_string.len() - current_string.len()
-            },
-            Err(_) => 0,
-        }
-
-    }
-
-    fn delete_char_range(&mut self, char_range: std::ops::Range<usize>) {
-        let mut current_string = self.0.to_string();
-        current_string.delete_char_range(char_range);
-
-        match Decimal::from_str(&current_string) {
-            Ok(n) => self.0 = n,
-            Err(_) => (),
+        match self.pronounce_in(Scale::Short) {
+            Ok(pronounced) => write!(f, "{}", pronounced),
+            Err(_) => write!(f, "{} руб.", self.accounting()),
         }
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug,
+    PartialEq,
+    Clone)]
 pub enum Error {
     KopekDigitsTooBig,
+    QuantityDigitsTooBig(u32),
+    PrecisionTooBig(u32),
+    NegativeQuantity,
+    IncompatibleUnits,
+    PackSizeMissing,
+    DivisionByZero,
+    NoOffers,
+    NegativeAmount,
+    NumberTooLarge,
+    Overflow,
+    OutOfRange,
+    InvalidNumber,
+    NoParticipants,
+    UnbalancedPayments,
+    ZeroTerm,
+    TermTooLong(u32),
+    InvalidDate,
+    ZeroInterval,
     RustDecimalError(rust_decimal::Error)
 }
 
+/// For logs only, what the user reads comes from `Localizer::error`
 impl std::fmt::Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Error::RustDecimalError(c) => std::fmt::Display::fmt(c,f),
-            Error::KopekDigitsTooBig => write!(f,"Неверно указано количество копеек, правильное значение: [рубли].(1-99)"),
-        }
+        write!(f, "{:?}", self)
     }
 }
 
@@ -298,3 +388,33 @@ impl From<rust_decimal::Error> for Error {
 
 impl std::error::Error for Error { }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zeros_after_kopeks_are_allowed() {
+        assert_eq!(Money::try_from("100.000"), Ok(Money(Decimal::ONE_HUNDRED)));
+        assert_eq!(Money::try_from("12.3400").unwrap().0.scale(), 2);
+        assert_eq!(Money::try_from("12.345"), Err(Error::KopekDigitsTooBig));
+    }
+
+    #[test]
+    fn checked_arithmetic_reports_overflow() {
+        let max = Money(Decimal::MAX);
+        assert_eq!(max.checked_add(Money(Decimal::ONE)), Err(Error::Overflow));
+        assert_eq!(Money(Decimal::MIN).checked_sub(Money(Decimal::ONE)), Err(Error::Overflow));
+        assert_eq!(max.checked_mul(Unit(2)), Err(Error::Overflow));
+        assert_eq!(Money(Decimal::ONE).checked_mul(Unit(u128::MAX)), Err(Error::OutOfRange));
+        assert_eq!(Money(Decimal::ONE).checked_add(Money(Decimal::ONE)), Ok(Money(Decimal::TWO)));
+    }
+
+    #[test]
+    fn checked_division_by_zero() {
+        let money = Money(Decimal::ONE_HUNDRED);
+        assert_eq!(money.checked_div(Unit(0)), Err(Error::DivisionByZero));
+        assert_eq!(money.checked_div(Decimal::ZERO), Err(Error::DivisionByZero));
+        assert_eq!(money.checked_div(Unit(4)), Ok(Money(Decimal::from(25))));
+    }
+}
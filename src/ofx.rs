@@ -0,0 +1,261 @@
+use std::collections::HashMap;
+
+use chrono::NaiveDate;
+
+use crate::{ledger::Transaction, statement::{self, Error, Problem, Statement}};
+
+/// `<TAG>`, `</TAG>` or the text after a tag
+enum Token<'a> {
+    Open(&'a str),
+    Close(&'a str),
+    Text(String)
+}
+
+/// Tags with their line numbers. OFX 1 is SGML where values aren't closed,
+/// OFX 2 is XML, both come out the same
+fn tokens(text: &str) -> Vec<(usize, Token<'_>)> {
+    let mut tokens = Vec::new();
+    let mut rest = text;
+    let mut line = 1;
+    while let Some(start) = rest.find('<') {
+        let value = rest[..start].trim();
+        if !value.is_empty() {
+            tokens.push((line, Token::Text(unescape(value))));
+        }
+        line += rest[..start].matches('\n').count();
+
+        let Some(end) = rest[start..].find('>') else {
+            break;
+        };
+        let tag = rest[start + 1..start + end].trim();
+        match tag.strip_prefix('/') {
+            Some(name) => tokens.push((line, Token::Close(name.trim()))),
+            // Processing instructions and comments
+            None if tag.starts_with('?') || tag.starts_with('!') => {},
+            None => tokens.push((line, Token::Open(tag.split_whitespace().next().unwrap_or_default()))),
+        }
+        line += rest[start..start + end].matches('\n').count();
+        rest = &rest[start + end + 1..];
+    }
+
+    tokens
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&")
+}
+
+/// `20240131`, `20240131120000.000[-5:EST]`, only the date is taken
+fn date(value: &str, field: &'static str) -> Result<NaiveDate, Error> {
+    value
+        .get(..8)
+        .and_then(|day| NaiveDate::parse_from_str(day, "%Y%m%d").ok())
+        .ok_or(Error::InvalidField(field))
+}
+
+/// One `<STMTTRN>`, values by tag, values of `CURRENCY` and `PAYEE` as `CURRENCY.CURSYM`
+struct Entry {
+    line: usize,
+    fields: HashMap<String, String>
+}
+
+impl Entry {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.fields.get(key).map(String::as_str).filter(|value| !value.is_empty())
+    }
+
+    fn require(&self, key: &'static str) -> Result<&str, Error> {
+        self.get(key).ok_or(Error::MissingField(key))
+    }
+
+    /// `currency` is the `CURDEF` of the statement
+    fn transaction(&self, currency: &str) -> Result<Transaction, Error> {
+        let reference = ["FITID", "REFNUM", "CHECKNUM"]
+            .iter()
+            .find_map(|key| self.get(key))
+            .unwrap_or_default();
+
+        Ok(Transaction {
+            id: None,
+            date: date(self.require("DTPOSTED")?, "DTPOSTED")?,
+            amount: statement::amount(self.require("TRNAMT")?, "TRNAMT")?,
+            // Without CURRENCY the amount is in CURDEF even if ORIGCURRENCY is given
+            currency: self.get("CURRENCY.CURSYM").unwrap_or(currency).to_string(),
+            category: String::new(),
+            counterparty: self.get("NAME").or(self.get("PAYEE.NAME")).unwrap_or_default().to_string(),
+            note: self.get("MEMO").unwrap_or_default().to_string(),
+            reference: reference.to_string()
+        })
+    }
+}
+
+/// OFX or QFX statement of a bank or credit card account, every statement in the file
+pub fn parse(text: &str) -> Result<Statement, Error> {
+    let tokens = tokens(text);
+    if !tokens.iter().any(|(_, token)| matches!(token, Token::Open("OFX"))) {
+        return Err(Error::UnknownFormat);
+    }
+
+    let mut statement = Statement::default();
+    let mut currency = String::new();
+    let mut stack: Vec<&str> = Vec::new();
+    let mut entry: Option<Entry> = None;
+    let mut entries = Vec::new();
+    let mut tokens = tokens.into_iter().peekable();
+    while let Some((line, token)) = tokens.next() {
+        match token {
+            Token::Open("STMTTRN") => {
+                if let Some(unclosed) = entry.replace(Entry { line, fields: HashMap::new() }) {
+                    statement.problems.push(Problem { line: unclosed.line, section: "STMTTRN".to_string(), error: Error::UnclosedSection });
+                }
+                stack.push("STMTTRN");
+            },
+            Token::Open(tag) => {
+                let value = match tokens.peek() {
+                    Some((_, Token::Text(value))) => value.clone(),
+                    _ => {
+                        stack.push(tag);
+                        continue;
+                    }
+                };
+                tokens.next();
+
+                let parent = stack.last().copied().unwrap_or_default();
+                match &mut entry {
+                    Some(entry) => {
+                        let key = match parent {
+                            "CURRENCY" | "ORIGCURRENCY" | "PAYEE" => format!("{}.{}", parent, tag),
+                            _ => tag.to_string(),
+                        };
+                        entry.fields.insert(key, value);
+                    },
+                    None => match (parent, tag) {
+                        (_, "CURDEF") => currency = value,
+                        (_, "ACCTID") => statement.add_account(&value),
+                        ("BANKTRANLIST", "DTSTART") => statement.from = date(&value, "DTSTART").ok(),
+                        ("BANKTRANLIST", "DTEND") => statement.to = date(&value, "DTEND").ok(),
+                        ("LEDGERBAL", "BALAMT") => match statement::amount(&value, "BALAMT") {
+                            Ok(balance) => statement.closing = Some(balance),
+                            Err(error) => statement.problems.push(Problem { line, section: "LEDGERBAL".to_string(), error }),
+                        },
+                        _ => {},
+                    },
+                }
+            },
+            Token::Close(tag) => {
+                // Closing a value in OFX 2 or an aggregate that was left open
+                let Some(depth) = stack.iter().rposition(|open| *open == tag) else {
+                    continue;
+                };
+                stack.truncate(depth);
+                if tag == "STMTTRN" {
+                    entries.extend(entry.take().map(|entry| (entry, currency.clone())));
+                }
+            },
+            Token::Text(_) => {},
+        }
+    }
+    if let Some(unclosed) = entry {
+        statement.problems.push(Problem { line: unclosed.line, section: "STMTTRN".to_string(), error: Error::UnclosedSection });
+    }
+
+    for (entry, currency) in entries {
+        match entry.transaction(&currency) {
+            Ok(transaction) => statement.transactions.push(transaction),
+            Err(error) => statement.problems.push(Problem { line: entry.line, section: "STMTTRN".to_string(), error }),
+        }
+    }
+
+    Ok(statement.finish())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal::Decimal;
+
+    use super::*;
+    use crate::money::Money;
+
+    /// OFX 1: SGML, values left open
+    const SGML: &str = "OFXHEADER:100
+DATA:OFXSGML
+VERSION:102
+CHARSET:1252
+
+<OFX>
+<BANKMSGSRSV1><STMTTRNRS><STMTRS>
+<CURDEF>USD
+<BANKACCTFROM><BANKID>121000248<ACCTID>1234567890<ACCTTYPE>CHECKING</BANKACCTFROM>
+<BANKTRANLIST>
+<DTSTART>20240101
+<DTEND>20240131120000.000[-5:EST]
+<STMTTRN>
+<TRNTYPE>DEBIT
+<DTPOSTED>20240105
+<TRNAMT>-1,234.50
+<FITID>A-1
+<NAME>Coffee &amp; Co
+<MEMO>Beans
+</STMTTRN>
+<STMTTRN>
+<TRNTYPE>CREDIT
+<DTPOSTED>20240110
+<TRNAMT>100.000
+<FITID>A-2
+<CURRENCY><CURRATE>1.0<CURSYM>EUR</CURRENCY>
+</STMTTRN>
+<STMTTRN>
+<TRNTYPE>DEBIT
+<DTPOSTED>20240111
+<TRNAMT>-7
+<STMTTRN>
+<TRNTYPE>DEBIT
+<DTPOSTED>2024-01-12
+<TRNAMT>-5
+</STMTTRN>
+</BANKTRANLIST>
+<LEDGERBAL><BALAMT>865.50<DTASOF>20240131</LEDGERBAL>
+</STMTRS></STMTTRNRS></BANKMSGSRSV1>
+</OFX>
+";
+
+    #[test]
+    fn sgml_statement() {
+        let statement = parse(SGML).unwrap();
+        assert_eq!(statement.accounts, vec!["1234567890".to_string()]);
+        assert_eq!((statement.from, statement.to), (NaiveDate::from_ymd_opt(2024, 1, 1), NaiveDate::from_ymd_opt(2024, 1, 31)));
+        assert_eq!(statement.closing, Some(Money(Decimal::new(86550, 2))));
+
+        let [coffee, refund] = &statement.transactions[..] else {
+            panic!("{:?}", statement.transactions);
+        };
+        assert_eq!(coffee, &Transaction {
+            id: None,
+            date: NaiveDate::from_ymd_opt(2024, 1, 5).unwrap(),
+            amount: Money(Decimal::new(-123450, 2)),
+            currency: "USD".to_string(),
+            category: String::new(),
+            counterparty: "Coffee & Co".to_string(),
+            note: "Beans".to_string(),
+            reference: "A-1".to_string()
+        });
+        assert_eq!((refund.amount, refund.currency.as_str()), (Money(Decimal::from(100)), "EUR"));
+
+        // The third entry runs into the fourth, the fourth has a date that isn't OFX
+        assert_eq!(statement.problems, vec![
+            Problem { line: 28, section: "STMTTRN".to_string(), error: Error::UnclosedSection },
+            Problem { line: 32, section: "STMTTRN".to_string(), error: Error::InvalidField("DTPOSTED") },
+        ]);
+    }
+
+    #[test]
+    fn not_ofx() {
+        assert!(matches!(parse("<html><body>OFX</body></html>"), Err(Error::UnknownFormat)));
+    }
+}
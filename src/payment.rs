@@ -0,0 +1,518 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use encoding_rs::WINDOWS_1251;
+use rust_decimal::Decimal;
+
+use crate::{money::{self, Money, Sign}, vat::{Vat, VatMode, VatRate}};
+
+#[derive(Debug,
+    PartialEq,
+    Clone)]
+pub enum Error {
+    InvalidInn,
+    InvalidKpp,
+    InvalidBik,
+    InvalidAccount,
+    InvalidCorrespondentAccount,
+    NegativeAmount,
+    ZeroAmount,
+    EmptyPurpose,
+    /// VAT or the amount in words can't be worked out
+    Money(money::Error)
+}
+
+/// For logs only, what the user reads comes from `Localizer::error`
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Money(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<money::Error> for Error {
+    fn from(value: money::Error) -> Self {
+        Error::Money(value)
+    }
+}
+
+fn digits(text: &str, count: usize) -> Option<Vec<u32>> {
+    let digits: Vec<u32> = text.chars().map(|c| c.to_digit(10)).collect::<Option<_>>()?;
+    (digits.len() == count).then_some(digits)
+}
+
+/// Control digit of an INN: weighted sum modulo 11, then modulo 10
+fn inn_control(digits: &[u32], weights: &[u32]) -> u32 {
+    digits.iter().zip(weights).map(|(digit, weight)| digit * weight).sum::<u32>() % 11 % 10
+}
+
+/// 10 digits for organisations, 12 for individuals, control digits included
+pub fn check_inn(inn: &str) -> Result<(), Error> {
+    const WEIGHTS: [u32;11] = [3, 7, 2, 4, 10, 3, 5, 9, 4, 6, 8];
+    let valid = match inn.len() {
+        10 => digits(inn, 10).is_some_and(|digits| inn_control(&digits[..9], &WEIGHTS[2..]) == digits[9]),
+        12 => digits(inn, 12).is_some_and(|digits| {
+            inn_control(&digits[..10], &WEIGHTS[1..]) == digits[10] &&
+                inn_control(&digits[..11], &WEIGHTS) == digits[11]
+        }),
+        _ => false,
+    };
+
+    if valid { Ok(()) } else { Err(Error::InvalidInn) }
+}
+
+/// Four digits of the tax office, two digits or capital letters of the reason, three digits.
+/// Individuals have none, so empty is fine
+pub fn check_kpp(kpp: &str) -> Result<(), Error> {
+    let chars: Vec<char> = kpp.chars().collect();
+    let valid = kpp.is_empty() || chars.len() == 9 && chars.iter().enumerate().all(|(index, c)| match index {
+        4 | 5 => c.is_ascii_digit() || c.is_ascii_uppercase(),
+        _ => c.is_ascii_digit(),
+    });
+
+    if valid { Ok(()) } else { Err(Error::InvalidKpp) }
+}
+
+pub fn check_bik(bik: &str) -> Result<(), Error> {
+    digits(bik, 9).map(|_| ()).ok_or(Error::InvalidBik)
+}
+
+/// Bank account check by the Central Bank: the key before the 20 digits of the account,
+/// weights 7, 1, 3 over and over, the sum must end with zero
+fn account_control(key: &str, account: &str) -> bool {
+    let (Some(key), Some(account)) = (digits(key, 3), digits(account, 20)) else {
+        return false;
+    };
+    key.iter()
+        .chain(&account)
+        .zip([7, 1, 3].iter().cycle())
+        .map(|(digit, weight)| digit * weight)
+        .sum::<u32>()
+        .is_multiple_of(10)
+}
+
+/// Settlement account, checked against the last three digits of the bank's BIK
+pub fn check_account(account: &str, bik: &str) -> Result<(), Error> {
+    check_bik(bik)?;
+    match account_control(&bik[6..], account) {
+        true => Ok(()),
+        false => Err(Error::InvalidAccount),
+    }
+}
+
+/// Correspondent account of the bank, checked against "0" and the fifth and sixth digits of the BIK.
+/// Banks served by the Central Bank directly have none, so empty is fine
+pub fn check_correspondent(account: &str, bik: &str) -> Result<(), Error> {
+    if account.is_empty() { return Ok(()); }
+    check_bik(bik)?;
+    match account_control(&format!("0{}", &bik[4..6]), account) {
+        true => Ok(()),
+        false => Err(Error::InvalidCorrespondentAccount),
+    }
+}
+
+/// Payer or recipient with their bank
+#[derive(Debug,
+    PartialEq, Eq,
+    Clone,
+    Default)]
+pub struct Party {
+    pub name: String,
+    pub inn: String,
+    pub kpp: String,
+    pub account: String,
+    /// Name and city of the bank
+    pub bank: String,
+    pub bik: String,
+    pub correspondent: String
+}
+
+impl Party {
+    /// Everything wrong with the requisites, empty when the party is fine
+    pub fn problems(&self) -> Vec<Error> {
+        [
+            check_inn(&self.inn),
+            check_kpp(&self.kpp),
+            check_bik(&self.bik),
+            check_account(&self.account, &self.bik),
+            check_correspondent(&self.correspondent, &self.bik),
+        ]
+        .into_iter()
+        .filter_map(Result::err)
+        // Accounts can't be checked without a BIK and say so, once is enough
+        .fold(Vec::new(), |mut problems, error| {
+            if !problems.contains(&error) {
+                problems.push(error);
+            }
+            problems
+        })
+    }
+
+    /// "ИНН 7707083893 ПАО Сбербанк", how 1C writes a party in one line
+    fn title(&self) -> String {
+        format!("ИНН {} {}", self.inn, self.name)
+    }
+}
+
+/// Платёжное поручение, form 0401060
+#[derive(Debug,
+    PartialEq, Eq,
+    Clone)]
+pub struct PaymentOrder {
+    pub number: u32,
+    pub date: NaiveDate,
+    pub amount: Money,
+    pub payer: Party,
+    pub recipient: Party,
+    pub purpose: String,
+    /// VAT included in the amount, named at the end of the purpose
+    pub vat: VatRate,
+    /// Очерёдность платежа, 5 for ordinary payments
+    pub priority: u8
+}
+
+impl PaymentOrder {
+    /// Problems of the order itself, the parties are checked by `Party::problems`
+    pub fn problems(&self) -> Vec<Error> {
+        let mut problems = Vec::new();
+        match self.amount.sign() {
+            Sign::Negative => problems.push(Error::NegativeAmount),
+            Sign::Positive if self.amount.0.is_zero() => problems.push(Error::ZeroAmount),
+            Sign::Positive => {},
+        }
+        if self.purpose.trim().is_empty() {
+            problems.push(Error::EmptyPurpose);
+        }
+        if let Err(error) = self.vat_line() {
+            problems.push(error);
+        }
+        problems
+    }
+
+    /// The first problem of the order or its parties
+    pub fn check(&self) -> Result<(), Error> {
+        let mut problems = self.problems();
+        problems.extend(self.payer.problems());
+        problems.extend(self.recipient.problems());
+        match problems.into_iter().next() {
+            Some(problem) => Err(problem),
+            None => Ok(()),
+        }
+    }
+
+    /// "В т.ч. НДС 20% - 250.00 руб." or "НДС не облагается"
+    pub fn vat_line(&self) -> Result<String, Error> {
+        let vat = Vat::calculate(self.amount, self.vat, VatMode::Extract)?;
+        Ok(match vat.rate.percent() {
+            Some(percent) => format!("В т.ч. НДС {}% - {} руб.", percent.normalize(), decimal(vat.vat)),
+            None => "НДС не облагается".to_string(),
+        })
+    }
+
+    /// Purpose with the VAT line, the way it goes to the bank
+    pub fn full_purpose(&self) -> Result<String, Error> {
+        Ok(format!("{} {}", self.purpose.trim(), self.vat_line()?))
+    }
+
+    /// "Сумма прописью" of the form
+    pub fn words(&self) -> Result<String, Error> {
+        Ok(self.amount.document_words()?)
+    }
+
+    fn document(&self) -> Result<Vec<String>, Error> {
+        self.check()?;
+        let mut lines = vec![
+            "СекцияДокумент=Платежное поручение".to_string(),
+            format!("Номер={}", self.number),
+            format!("Дата={}", self.date.format("%d.%m.%Y")),
+            format!("Сумма={}", decimal(self.amount)),
+        ];
+        for (side, party) in [("Плательщик", &self.payer), ("Получатель", &self.recipient)] {
+            lines.extend([
+                format!("{}Счет={}", side, party.account),
+                format!("{}={}", side, party.title()),
+                format!("{}ИНН={}", side, party.inn),
+                format!("{}КПП={}", side, party.kpp),
+                format!("{}1={}", side, party.name),
+                format!("{}РасчСчет={}", side, party.account),
+                format!("{}Банк1={}", side, party.bank),
+                format!("{}БИК={}", side, party.bik),
+                format!("{}Корсчет={}", side, party.correspondent),
+            ]);
+        }
+        lines.extend([
+            "ВидОплаты=01".to_string(),
+            format!("Очередность={}", self.priority),
+            format!("НазначениеПлатежа={}", self.full_purpose()?),
+            "КонецДокумента".to_string(),
+        ]);
+        Ok(lines)
+    }
+
+    /// Printable form as an HTML page, opened in a browser and printed from there
+    pub fn html(&self) -> Result<String, Error> {
+        self.check()?;
+        let amount = self.amount.0.round_dp(2);
+        let written = format!("{}-{:02}", amount.trunc(), (amount.fract() * Decimal::ONE_HUNDRED).trunc());
+        let cell = |text: &str| escape(text);
+
+        Ok(format!(r#"<!DOCTYPE html>
+<html lang="ru">
+<head>
+<meta charset="utf-8">
+<title>Платёжное поручение № {number}</title>
+<style>
+body {{ font-family: Arial, sans-serif; font-size: 10pt; width: 180mm; margin: 10mm auto; }}
+table {{ border-collapse: collapse; width: 100%; }}
+td {{ border: 1px solid #000; padding: 2px 4px; vertical-align: top; }}
+.form {{ text-align: right; }}
+.signatures td {{ border: none; padding-top: 12mm; }}
+</style>
+</head>
+<body>
+<p class="form">0401060</p>
+<h3>ПЛАТЁЖНОЕ ПОРУЧЕНИЕ № {number} от {date}</h3>
+<table>
+<tr><td>Сумма прописью</td><td colspan="3">{words}</td></tr>
+<tr><td>ИНН {payer_inn}</td><td>КПП {payer_kpp}</td><td>Сумма</td><td>{written}</td></tr>
+<tr><td colspan="2">{payer}<br>Плательщик</td><td>Сч. №</td><td>{payer_account}</td></tr>
+<tr><td colspan="2" rowspan="2">{payer_bank}<br>Банк плательщика</td><td>БИК</td><td>{payer_bik}</td></tr>
+<tr><td>Сч. №</td><td>{payer_correspondent}</td></tr>
+<tr><td colspan="2" rowspan="2">{recipient_bank}<br>Банк получателя</td><td>БИК</td><td>{recipient_bik}</td></tr>
+<tr><td>Сч. №</td><td>{recipient_correspondent}</td></tr>
+<tr><td>ИНН {recipient_inn}</td><td>КПП {recipient_kpp}</td><td>Сч. №</td><td>{recipient_account}</td></tr>
+<tr><td colspan="2">{recipient}<br>Получатель</td><td>Вид оп.<br>Очер. плат.</td><td>01<br>{priority}</td></tr>
+<tr><td colspan="4">{purpose}<br>Назначение платежа</td></tr>
+</table>
+<table class="signatures">
+<tr><td>Подписи ____________________</td><td>Отметки банка</td></tr>
+<tr><td>____________________<br>М.П.</td><td></td></tr>
+</table>
+</body>
+</html>
+"#,
+            number = self.number,
+            date = self.date.format("%d.%m.%Y"),
+            words = cell(&self.words()?),
+            written = written,
+            payer = cell(&self.payer.name),
+            payer_inn = cell(&self.payer.inn),
+            payer_kpp = cell(&self.payer.kpp),
+            payer_account = cell(&self.payer.account),
+            payer_bank = cell(&self.payer.bank),
+            payer_bik = cell(&self.payer.bik),
+            payer_correspondent = cell(&self.payer.correspondent),
+            recipient = cell(&self.recipient.name),
+            recipient_inn = cell(&self.recipient.inn),
+            recipient_kpp = cell(&self.recipient.kpp),
+            recipient_account = cell(&self.recipient.account),
+            recipient_bank = cell(&self.recipient.bank),
+            recipient_bik = cell(&self.recipient.bik),
+            recipient_correspondent = cell(&self.recipient.correspondent),
+            priority = self.priority,
+            purpose = cell(&self.full_purpose()?),
+        ))
+    }
+}
+
+/// "1500.00", the way 1C writes amounts
+fn decimal(amount: Money) -> String {
+    format!("{:.2}", amount.0)
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Payer accounts, each once however the orders are mixed
+fn accounts(orders: &[PaymentOrder]) -> Vec<&str> {
+    let mut accounts: Vec<&str> = orders.iter().map(|order| order.payer.account.as_str()).collect();
+    accounts.sort_unstable();
+    accounts.dedup();
+    accounts
+}
+
+/// File for the bank client in the 1CClientBankExchange format, the orders may come
+/// from several of the payer's accounts
+pub fn exchange(orders: &[PaymentOrder], created: NaiveDateTime) -> Result<String, Error> {
+    let from = orders.iter().map(|order| order.date).min().unwrap_or(created.date());
+    let to = orders.iter().map(|order| order.date).max().unwrap_or(created.date());
+
+    let mut lines = vec![
+        "1CClientBankExchange".to_string(),
+        "ВерсияФормата=1.03".to_string(),
+        "Кодировка=Windows".to_string(),
+        "Отправитель=Money counter".to_string(),
+        "Получатель=".to_string(),
+        format!("ДатаСоздания={}", created.format("%d.%m.%Y")),
+        format!("ВремяСоздания={}", created.format("%H:%M:%S")),
+        format!("ДатаНачала={}", from.format("%d.%m.%Y")),
+        format!("ДатаКонца={}", to.format("%d.%m.%Y")),
+    ];
+    lines.extend(accounts(orders).iter().map(|account| format!("РасчСчет={}", account)));
+    lines.push("Документ=Платежное поручение".to_string());
+    for order in orders {
+        lines.extend(order.document()?);
+    }
+    lines.push("КонецФайла".to_string());
+
+    Ok(lines.join("\r\n") + "\r\n")
+}
+
+/// Bank clients expect windows-1251
+pub fn encode(text: &str) -> Vec<u8> {
+    WINDOWS_1251.encode(text).0.into_owned()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn order(account: &str) -> PaymentOrder {
+        PaymentOrder {
+            number: 1,
+            date: NaiveDate::from_ymd_opt(2024, 10, 1).unwrap(),
+            amount: Money(Decimal::from(100)),
+            payer: Party { account: account.to_string(), ..Party::default() },
+            recipient: Party::default(),
+            purpose: "Оплата по счёту № 5".to_string(),
+            vat: VatRate::WithoutVat,
+            priority: 5
+        }
+    }
+
+    const BIK: &str = "044525225";
+    const CORRESPONDENT: &str = "30101810400000000225";
+
+    fn party(name: &str, inn: &str, kpp: &str, account: &str) -> Party {
+        Party {
+            name: name.to_string(),
+            inn: inn.to_string(),
+            kpp: kpp.to_string(),
+            account: account.to_string(),
+            bank: "ПАО Сбербанк г. Москва".to_string(),
+            bik: BIK.to_string(),
+            correspondent: CORRESPONDENT.to_string()
+        }
+    }
+
+    #[test]
+    fn inn() {
+        assert_eq!(check_inn("7707083893"), Ok(()));
+        assert_eq!(check_inn("500100732259"), Ok(()));
+        assert_eq!(check_inn("7707083894"), Err(Error::InvalidInn));
+        assert_eq!(check_inn("500100732250"), Err(Error::InvalidInn));
+        assert_eq!(check_inn("77070838"), Err(Error::InvalidInn));
+        assert_eq!(check_inn("77070838a3"), Err(Error::InvalidInn));
+    }
+
+    #[test]
+    fn kpp() {
+        assert_eq!(check_kpp("773601001"), Ok(()));
+        assert_eq!(check_kpp("7736AB001"), Ok(()));
+        assert_eq!(check_kpp(""), Ok(()));
+        assert_eq!(check_kpp("77360100"), Err(Error::InvalidKpp));
+        assert_eq!(check_kpp("7736ab001"), Err(Error::InvalidKpp));
+        assert_eq!(check_kpp("77360100A"), Err(Error::InvalidKpp));
+    }
+
+    #[test]
+    fn bik() {
+        assert_eq!(check_bik(BIK), Ok(()));
+        assert_eq!(check_bik("04452522"), Err(Error::InvalidBik));
+        assert_eq!(check_bik("04452522X"), Err(Error::InvalidBik));
+    }
+
+    #[test]
+    fn settlement_account() {
+        assert_eq!(check_account("40702810938000000001", BIK), Ok(()));
+        assert_eq!(check_account("40702810938000000002", BIK), Err(Error::InvalidAccount));
+        assert_eq!(check_account("4070281093800000000", BIK), Err(Error::InvalidAccount));
+        assert_eq!(check_account("40702810938000000001", ""), Err(Error::InvalidBik));
+    }
+
+    #[test]
+    fn correspondent_account() {
+        assert_eq!(check_correspondent(CORRESPONDENT, BIK), Ok(()));
+        assert_eq!(check_correspondent("", BIK), Ok(()));
+        assert_eq!(check_correspondent("30101810400000000226", BIK), Err(Error::InvalidCorrespondentAccount));
+        assert_eq!(check_correspondent(CORRESPONDENT, "044030653"), Err(Error::InvalidCorrespondentAccount));
+    }
+
+    #[test]
+    fn exchange_file() {
+        let order = PaymentOrder {
+            number: 17,
+            date: NaiveDate::from_ymd_opt(2024, 10, 1).unwrap(),
+            amount: Money(Decimal::from(1500)),
+            payer: party("ООО Ромашка", "7707083893", "773601001", "40702810938000000001"),
+            recipient: party("Иванов Иван Иванович", "500100732259", "", "40817810000000000002"),
+            purpose: "Оплата по счёту № 5 от 30.09.2024".to_string(),
+            vat: VatRate::Vat20,
+            priority: 5
+        };
+        let created = NaiveDate::from_ymd_opt(2024, 10, 2).unwrap().and_hms_opt(9, 30, 0).unwrap();
+        let expected = [
+            "1CClientBankExchange",
+            "ВерсияФормата=1.03",
+            "Кодировка=Windows",
+            "Отправитель=Money counter",
+            "Получатель=",
+            "ДатаСоздания=02.10.2024",
+            "ВремяСоздания=09:30:00",
+            "ДатаНачала=01.10.2024",
+            "ДатаКонца=01.10.2024",
+            "РасчСчет=40702810938000000001",
+            "Документ=Платежное поручение",
+            "СекцияДокумент=Платежное поручение",
+            "Номер=17",
+            "Дата=01.10.2024",
+            "Сумма=1500.00",
+            "ПлательщикСчет=40702810938000000001",
+            "Плательщик=ИНН 7707083893 ООО Ромашка",
+            "ПлательщикИНН=7707083893",
+            "ПлательщикКПП=773601001",
+            "Плательщик1=ООО Ромашка",
+            "ПлательщикРасчСчет=40702810938000000001",
+            "ПлательщикБанк1=ПАО Сбербанк г. Москва",
+            "ПлательщикБИК=044525225",
+            "ПлательщикКорсчет=30101810400000000225",
+            "ПолучательСчет=40817810000000000002",
+            "Получатель=ИНН 500100732259 Иванов Иван Иванович",
+            "ПолучательИНН=500100732259",
+            "ПолучательКПП=",
+            "Получатель1=Иванов Иван Иванович",
+            "ПолучательРасчСчет=40817810000000000002",
+            "ПолучательБанк1=ПАО Сбербанк г. Москва",
+            "ПолучательБИК=044525225",
+            "ПолучательКорсчет=30101810400000000225",
+            "ВидОплаты=01",
+            "Очередность=5",
+            "НазначениеПлатежа=Оплата по счёту № 5 от 30.09.2024 В т.ч. НДС 20% - 250.00 руб.",
+            "КонецДокумента",
+            "КонецФайла",
+        ];
+        assert_eq!(exchange(std::slice::from_ref(&order), created).unwrap(), expected.join("\r\n") + "\r\n");
+
+        let unsigned = PaymentOrder { payer: party("ООО Ромашка", "7707083894", "773601001", "40702810938000000001"), ..order };
+        assert_eq!(exchange(&[unsigned], created), Err(Error::InvalidInn));
+    }
+
+    #[test]
+    fn every_account_is_listed_once() {
+        let first = "40702810900000000001";
+        let second = "40702810900000000002";
+        let orders = [order(first), order(second), order(first)];
+        assert_eq!(accounts(&orders), vec![first, second]);
+    }
+}
@@ -1,20 +1,25 @@
-use core::panic;
-use std::ops::IndexMut;
+use crate::money::Error;
 
+#[derive(Clone, Copy)]
 pub struct Triplet {
     number: [u8;3],
-    ///Maximum supported size: 12 (0-12)
+    /// Power of a thousand, names are available up to `Scale::name`
     pos: usize,
-    feminie: bool
+    feminie: bool,
+    scale: Scale
 }
 
 impl Triplet {
     pub fn new(number: [u8;3], pos: usize) -> Triplet {
-        Triplet { number, pos, feminie: false}
+        Triplet { number, pos, feminie: false, scale: Scale::Short }
     }
 
-    pub fn into_feminie(&self) -> Self {
-        Triplet { feminie: true, ..*self  }
+    pub fn with_scale(self, scale: Scale) -> Self {
+        Triplet { scale, ..self }
+    }
+
+    pub fn into_feminie(self) -> Self {
+        Triplet { feminie: true, ..self  }
     }
 
     fn pronounce_hundreeds(&self) -> &'static str {
@@ -53,10 +58,10 @@ impl Triplet {
             b'3' => ("тридцать",false),
             b'4' => ("сорок",false),
             b'5' => ("пятьдесят",false),
-            b'6' => ("шестдесят",false),
+            b'6' => ("шестьдесят",false),
             b'7' => ("семьдесят",false),
             b'8' => ("восемьдесят",false),
-            b'9' => ("девяноста",false),
+            b'9' => ("девяносто",false),
             _ => ("",false)
         }
     }
@@ -82,7 +87,7 @@ impl Triplet {
         let mut pronounced_number = String::new();
 
         let hundreeds = self.pronounce_hundreeds();
-        pronounced_number.push_str(&hundreeds);
+        pronounced_number.push_str(hundreeds);
         if !hundreeds.is_empty() { pronounced_number.push(' ') }
 
         let (tens, units_processed) = self.pronounce_tens();
@@ -114,7 +119,7 @@ impl Triplet {
 
     }
 
-    pub fn pronounce(&self) -> String {
+    pub fn pronounce(&self) -> Result<String, Error> {
         let mut pronounced_triplet = String::new();
 
         let number_pronounce = &self.number_pronounce();
@@ -132,106 +137,115 @@ impl Triplet {
                         )
                     )
                 },
-                2 => {
-                    pronounced_triplet.push_str(
-                        self.triplet_pronounce(
-                            ["миллион","миллиона","миллионов"]
-                        )
-                    )
-                },
-                3 => {
-                    pronounced_triplet.push_str(
-                        self.triplet_pronounce(
-                            ["миллиард","миллиарда","миллиардов"]
-                        )
-                    )
-                },
-                4 => {
-                    pronounced_triplet.push_str(
-                        self.triplet_pronounce(
-                            ["триллион","триллиона","триллионов"]
-                        )
-                    )
-                },
-                5 => {
-                    pronounced_triplet.push_str(
-                        self.triplet_pronounce(
-                            ["квадрилион","квадриллиона","квадриллионов"]
-                        )
-                    )
-                },
-                6 => {
-                    pronounced_triplet.push_str(
-                        self.triplet_pronounce(
-                            ["квинтиллион","квинтиллиона","квинтиллионов"]
-                        )
-                    )
-                },
-                7 => {
+                pos => {
+                    let name = self.scale.name(pos).ok_or(Error::NumberTooLarge)?;
+                    let pronounces = [name.clone(), name.clone() + "а", name + "ов"];
                     pronounced_triplet.push_str(
                         self.triplet_pronounce(
-                            ["секситиллион","секстиллиона","секстиллионов"]
+                            [&pronounces[0], &pronounces[1], &pronounces[2]]
                         )
                     )
-                },
-                8 => {
-                    pronounced_triplet.push_str(
-                        self.triplet_pronounce(
-                            ["септиллион","септиллиона","септиллионов"]
-                        )
-                    )
-                },
-                9 => {
-                    pronounced_triplet.push_str(
-                        self.triplet_pronounce(
-                            ["октиллион","октиллиона","октиллионов"]
-                        )
-                    )
-                },
-                10 => {
-                    pronounced_triplet.push_str(
-                        self.triplet_pronounce(
-                            ["нониллион","нониллиона","нониллионов"]
-                        )
-                    )
-                },
-                11 => {
-                    pronounced_triplet.push_str(
-                        self.triplet_pronounce(
-                            ["дециллион","дециллиона","дециллионов"]
-                        )
-                    )
-                },
-                12 => {
-                    pronounced_triplet.push_str(
-                        self.triplet_pronounce(
-                            ["ундециллион","ундециллиона","ундециллионов"]
-                        )
-                    )
-                },
-                _ => panic!("Triplet size overflow")
+                }
             };
         }
 
-        pronounced_triplet
+        Ok(pronounced_triplet)
+    }
+}
+
+/// Language of amounts in words, chosen apart from the interface language
+#[derive(Debug,
+    PartialEq, Eq,
+    Clone, Copy,
+    Default)]
+pub enum Language {
+    #[default]
+    Russian,
+    English
+}
+
+impl Language {
+    pub const ALL: [Language;2] = [Language::Russian, Language::English];
+
+    /// BCP 47 tag, also the name of the catalog directory
+    pub fn code(&self) -> &'static str {
+        match self {
+            Language::Russian => "ru",
+            Language::English => "en",
+        }
+    }
+
+    /// Name of the language in itself, for selectors
+    pub fn native_name(&self) -> &'static str {
+        match self {
+            Language::Russian => "Русский",
+            Language::English => "English",
+        }
+    }
+}
+
+/// Latin prefixes of "-иллион" names, from "ми" (10^6) to "вигинти"
+const LATIN_PREFIXES: [&str;20] = [
+    "ми", "би", "три", "квадри", "квинти",
+    "сексти", "септи", "окти", "нони", "деци",
+    "ундеци", "дуодеци", "тредеци", "кваттуордеци", "квиндеци",
+    "сексдеци", "септендеци", "октодеци", "новемдеци", "вигинти"
+];
+
+/// Naming of powers of a thousand above "тысяча"
+#[derive(Debug,
+    PartialEq, Eq,
+    Clone, Copy,
+    Default)]
+pub enum Scale {
+    /// Every next thousand gets a new name:
+    /// миллион, миллиард, триллион, квадриллион...
+    #[default]
+    Short,
+    /// "-иллион" and "-иллиард" alternate:
+    /// миллион, миллиард, биллион, биллиард, триллион...
+    Long
+}
+
+impl Scale {
+    /// Name of 1000^pos, `None` if there is no such name.
+    /// Only valid for `pos` >= 2
+    pub fn name(&self, pos: usize) -> Option<String> {
+        match self {
+            Scale::Short => match pos {
+                3 => Some("миллиард".to_string()),
+                pos => LATIN_PREFIXES
+                    .get(pos.checked_sub(2)?)
+                    .map(|prefix| format!("{}ллион", prefix)),
+            },
+            Scale::Long => {
+                let prefix = LATIN_PREFIXES.get((pos / 2).checked_sub(1)?)?;
+                match pos % 2 {
+                    0 => Some(format!("{}ллион", prefix)),
+                    _ => Some(format!("{}ллиард", prefix)),
+                }
+            },
+        }
     }
 }
 
 
 pub trait NumberPronouce {
+    #[allow(clippy::wrong_self_convention)]
     fn into_triplets(& self) -> Vec<Triplet>;
-    fn pronounce(&self) -> String {
+
+    fn pronounce_in(&self, scale: Scale) -> Result<String, Error> {
         let mut result = String::new();
 
-        self
-            .into_triplets()
-            .into_iter()
-            .rev()
-            .for_each( |triplet| {
-                result.push_str(&triplet.pronounce());
-                result.push(' ');
-            });
+        for triplet in self.into_triplets().into_iter().rev() {
+            result.push_str(&triplet.with_scale(scale).pronounce()?);
+            result.push(' ');
+        }
+
+        Ok(result)
+    }
 
-        result
+    fn pronounce(&self) -> Result<String, Error> {
+        self.pronounce_in(Scale::Short)
     }
 }
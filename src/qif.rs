@@ -0,0 +1,146 @@
+use chrono::NaiveDate;
+
+use crate::{ledger::Transaction, statement::{self, Error, Problem, Statement}};
+
+/// Account types whose records are plain transactions, investment ones are not
+const TRANSACTIONS: [&str;5] = ["Bank", "Cash", "CCard", "Oth A", "Oth L"];
+
+/// `01/31/2024`, `1/31'24`, `31.01.2024` or `2024-01-31`. Slashes are American
+/// month first, an apostrophe before the year means 2000s
+fn date(value: &str) -> Result<NaiveDate, Error> {
+    let invalid = Error::InvalidField("D");
+    let value: String = value.chars().filter(|c| !c.is_whitespace()).collect();
+    let parts: Vec<&str> = value.split(['/', '\'', '.', '-']).collect();
+    let [first, second, third] = parts[..] else {
+        return Err(invalid);
+    };
+    let number = |part: &str| part.parse::<u32>().map_err(|_| Error::InvalidField("D"));
+
+    let (year, month, day) = match value.contains('/') {
+        true => (third, first, second),
+        false if value.contains('.') => (third, second, first),
+        false => (first, second, third),
+    };
+    let digits = year.len();
+    let (year, month, day) = (number(year)?, number(month)?, number(day)?);
+    let year = match (digits, value.contains('\'')) {
+        (3.., _) => year,
+        (_, true) => 2000 + year,
+        (_, false) if year < 50 => 2000 + year,
+        (_, false) => 1900 + year,
+    };
+
+    i32::try_from(year)
+        .ok()
+        .and_then(|year| NaiveDate::from_ymd_opt(year, month, day))
+        .ok_or(invalid)
+}
+
+/// One record up to `^`, fields by their first letter
+struct Record {
+    line: usize,
+    fields: Vec<(char, String)>
+}
+
+impl Record {
+    fn get(&self, code: char) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(field, value)| *field == code && !value.is_empty())
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn transaction(&self, currency: &str) -> Result<Transaction, Error> {
+        let date = date(self.get('D').ok_or(Error::MissingField("D"))?)?;
+        let (code, amount) = match self.get('T') {
+            Some(amount) => ("T", amount),
+            None => ("U", self.get('U').ok_or(Error::MissingField("T"))?),
+        };
+        // `[Account]` is a transfer, not a category
+        let category = self.get('L').filter(|category| !category.starts_with('['));
+
+        Ok(Transaction {
+            id: None,
+            date,
+            amount: statement::amount(amount, code)?,
+            currency: currency.to_string(),
+            category: category.unwrap_or_default().to_string(),
+            counterparty: self.get('P').unwrap_or_default().to_string(),
+            note: self.get('M').unwrap_or_default().to_string(),
+            reference: self.get('N').unwrap_or_default().to_string()
+        })
+    }
+}
+
+/// Quicken interchange format. It names no currency, so all amounts are in `currency`
+pub fn parse(text: &str, currency: &str) -> Result<Statement, Error> {
+    let mut statement = Statement::default();
+    let mut kind = None;
+    let mut record: Option<Record> = None;
+    let mut found = false;
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim_end();
+        if line.trim().is_empty() { continue; }
+
+        if let Some(header) = line.strip_prefix('!') {
+            found = true;
+            // `!Account` is followed by a record that describes the account
+            kind = header.strip_prefix("Type:").map(|kind| kind.trim().to_string());
+            if header.starts_with("Account") {
+                kind = Some("Account".to_string());
+            }
+            continue;
+        }
+
+        let mut chars = line.chars();
+        match chars.next() {
+            Some('^') => {
+                let Some(done) = record.take() else {
+                    continue;
+                };
+                match kind.as_deref() {
+                    Some("Account") => statement.add_account(done.get('N').unwrap_or_default()),
+                    Some(kind) if TRANSACTIONS.contains(&kind) => match done.transaction(currency) {
+                        Ok(transaction) => statement.transactions.push(transaction),
+                        Err(error) => statement.problems.push(Problem { line: done.line, section: kind.to_string(), error }),
+                    },
+                    _ => {},
+                }
+            },
+            Some(code) => record
+                .get_or_insert_with(|| Record { line: index + 1, fields: Vec::new() })
+                .fields
+                .push((code, chars.as_str().trim().to_string())),
+            None => {},
+        }
+    }
+    if !found {
+        return Err(Error::UnknownFormat);
+    }
+    if let (Some(unclosed), Some(kind)) = (record, kind) {
+        if TRANSACTIONS.contains(&kind.as_str()) {
+            statement.problems.push(Problem { line: unclosed.line, section: kind, error: Error::UnclosedSection });
+        }
+    }
+
+    statement.from = statement.transactions.iter().map(|transaction| transaction.date).min();
+    statement.to = statement.transactions.iter().map(|transaction| transaction.date).max();
+    Ok(statement.finish())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dates_in_every_layout() {
+        let expected = NaiveDate::from_ymd_opt(2024, 1, 31);
+        assert_eq!(date("01/31/2024").ok(), expected);
+        assert_eq!(date("1/31'24").ok(), expected);
+        assert_eq!(date("31.01.2024").ok(), expected);
+        assert_eq!(date("2024-01-31").ok(), expected);
+        assert_eq!(date("01/31/99").ok(), NaiveDate::from_ymd_opt(1999, 1, 31));
+        assert_eq!(date("31/01/2024"), Err(Error::InvalidField("D")));
+    }
+}
@@ -0,0 +1,1155 @@
+use std::path::Path;
+
+use image::{imageops::FilterType, GrayImage};
+
+#[derive(Debug,
+    PartialEq,
+    Clone)]
+pub enum Error {
+    /// The file can't be opened or is not a PNG or JPEG picture
+    Image(String)
+}
+
+/// For logs only, what the user reads comes from `Localizer::error`
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl std::error::Error for Error { }
+
+/// Photos larger than this are first tried scaled down, a receipt's QR code stays readable
+const WORKING_SIZE: u32 = 1600;
+
+/// Finder triples tried per bitmap, best first. Patterns that only look like finders
+/// make every triple fail slowly, a receipt's own code is among the first few
+const MAX_TRIPLES: usize = 8;
+
+/// How many modules around the expected corner the fourth point of a code
+/// without an alignment pattern is searched
+const CORNER_SEARCH: i32 = 2;
+
+/// Error correction level, in the order of the tables below
+#[derive(Debug,
+    PartialEq, Eq,
+    Clone, Copy)]
+enum Level {
+    Low,
+    Medium,
+    Quartile,
+    High
+}
+
+impl Level {
+    /// Two bits of the format information
+    fn from_bits(bits: u32) -> Level {
+        match bits {
+            1 => Level::Low,
+            0 => Level::Medium,
+            3 => Level::Quartile,
+            _ => Level::High,
+        }
+    }
+
+    fn index(&self) -> usize {
+        *self as usize
+    }
+}
+
+/// Error correction codewords per block, by level and version
+static ECC_PER_BLOCK: [[u8;41];4] = [
+    [0, 7, 10, 15, 20, 26, 18, 20, 24, 30, 18, 20, 24, 26, 30, 22, 24, 28, 30, 28, 28, 28, 28, 30, 30, 26, 28, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30],
+    [0, 10, 16, 26, 18, 24, 16, 18, 22, 22, 26, 30, 22, 22, 24, 24, 28, 28, 26, 26, 26, 26, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28],
+    [0, 13, 22, 18, 26, 18, 24, 18, 22, 20, 24, 28, 26, 24, 20, 30, 24, 28, 28, 26, 30, 28, 30, 30, 30, 30, 28, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30],
+    [0, 17, 28, 22, 16, 22, 28, 26, 26, 24, 28, 24, 28, 22, 24, 24, 30, 28, 28, 26, 28, 30, 24, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30],
+];
+
+/// Number of error correction blocks, by level and version
+static BLOCKS: [[u8;41];4] = [
+    [0, 1, 1, 1, 1, 1, 2, 2, 2, 2, 4, 4, 4, 4, 4, 6, 6, 6, 6, 7, 8, 8, 9, 9, 10, 12, 12, 12, 13, 14, 15, 16, 17, 18, 19, 19, 20, 21, 22, 24, 25],
+    [0, 1, 1, 1, 2, 2, 4, 4, 4, 5, 5, 5, 8, 9, 9, 10, 10, 11, 13, 14, 16, 17, 17, 18, 20, 21, 23, 25, 26, 28, 29, 31, 33, 35, 37, 38, 40, 43, 45, 47, 49],
+    [0, 1, 1, 2, 2, 4, 4, 6, 6, 8, 8, 8, 10, 12, 16, 12, 17, 16, 18, 21, 20, 23, 23, 25, 27, 29, 34, 34, 35, 38, 40, 43, 45, 48, 51, 53, 56, 59, 62, 65, 68],
+    [0, 1, 1, 2, 4, 4, 4, 5, 6, 8, 8, 11, 11, 16, 16, 18, 16, 19, 21, 25, 25, 25, 34, 30, 32, 35, 37, 40, 42, 45, 48, 51, 54, 57, 60, 63, 66, 70, 74, 77, 81],
+];
+
+const ALPHANUMERIC: &[u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ $%*+-./:";
+
+/// Arithmetic in GF(256) with the QR polynomial x^8 + x^4 + x^3 + x^2 + 1
+struct Field {
+    exp: [u8;512],
+    log: [u8;256]
+}
+
+impl Field {
+    const fn new() -> Self {
+        let mut exp = [0u8;512];
+        let mut log = [0u8;256];
+        let mut value: u32 = 1;
+        let mut i = 0;
+        while i < 255 {
+            exp[i] = value as u8;
+            exp[i + 255] = value as u8;
+            log[value as usize] = i as u8;
+            value <<= 1;
+            if value & 0x100 != 0 {
+                value ^= 0x11d;
+            }
+            i += 1;
+        }
+        Field { exp, log }
+    }
+
+    fn mul(&self, a: u8, b: u8) -> u8 {
+        if a == 0 || b == 0 { return 0; }
+        self.exp[self.log[a as usize] as usize + self.log[b as usize] as usize]
+    }
+
+    fn div(&self, a: u8, b: u8) -> u8 {
+        if a == 0 { return 0; }
+        self.exp[self.log[a as usize] as usize + 255 - self.log[b as usize] as usize]
+    }
+
+    /// α^power, negative powers included
+    fn alpha(&self, power: i32) -> u8 {
+        self.exp[power.rem_euclid(255) as usize]
+    }
+
+    /// Polynomial with the lowest degree first
+    fn eval(&self, poly: &[u8], x: u8) -> u8 {
+        poly.iter().rev().fold(0, |sum, &coefficient| self.mul(sum, x) ^ coefficient)
+    }
+}
+
+static GF: Field = Field::new();
+
+/// Fixes up to half as many wrong bytes as there are `ecc` bytes at the end of the block.
+/// The first byte is the highest power, syndromes start from α^0
+fn correct(block: &mut [u8], ecc: usize) -> Option<()> {
+    let n = block.len();
+    let syndromes: Vec<u8> = (0..ecc)
+        .map(|i| block.iter().fold(0, |sum, &byte| GF.mul(sum, GF.alpha(i as i32)) ^ byte))
+        .collect();
+    if syndromes.iter().all(|&syndrome| syndrome == 0) { return Some(()); }
+
+    // Berlekamp-Massey, error locator with the lowest degree first
+    let mut locator = vec![1u8];
+    let mut previous = vec![1u8];
+    let mut errors = 0;
+    let mut shift = 1;
+    let mut last = 1u8;
+    for k in 0..ecc {
+        let mut discrepancy = syndromes[k];
+        for i in 1..=errors.min(locator.len() - 1) {
+            discrepancy ^= GF.mul(locator[i], syndromes[k - i]);
+        }
+        if discrepancy == 0 {
+            shift += 1;
+            continue;
+        }
+
+        let scale = GF.div(discrepancy, last);
+        let mut updated = locator.clone();
+        updated.resize(updated.len().max(previous.len() + shift), 0);
+        for (i, &coefficient) in previous.iter().enumerate() {
+            updated[i + shift] ^= GF.mul(scale, coefficient);
+        }
+        if 2 * errors <= k {
+            previous = std::mem::replace(&mut locator, updated);
+            errors = k + 1 - errors;
+            last = discrepancy;
+            shift = 1;
+        }
+        else {
+            locator = updated;
+            shift += 1;
+        }
+    }
+    locator.truncate(errors + 1);
+    if 2 * errors > ecc { return None; }
+
+    // Chien search: byte j stands for the power n - 1 - j
+    let positions: Vec<usize> = (0..n)
+        .filter(|&j| GF.eval(&locator, GF.alpha(-((n - 1 - j) as i32))) == 0)
+        .collect();
+    if positions.len() != errors { return None; }
+
+    // Forney: evaluator = syndromes * locator mod x^ecc
+    let mut evaluator = vec![0u8;ecc];
+    for (i, &syndrome) in syndromes.iter().enumerate() {
+        for (j, &coefficient) in locator.iter().enumerate().take(ecc - i) {
+            evaluator[i + j] ^= GF.mul(syndrome, coefficient);
+        }
+    }
+    let derivative: Vec<u8> = locator
+        .iter()
+        .enumerate()
+        .skip(1)
+        .map(|(i, &coefficient)| if i % 2 == 1 { coefficient } else { 0 })
+        .collect();
+    for j in positions {
+        let x = GF.alpha((n - 1 - j) as i32);
+        let inverse = GF.div(1, x);
+        let denominator = GF.eval(&derivative, inverse);
+        if denominator == 0 { return None; }
+        block[j] ^= GF.mul(x, GF.div(GF.eval(&evaluator, inverse), denominator));
+    }
+
+    Some(())
+}
+
+/// Black and white picture, `true` is dark
+struct Bitmap {
+    width: usize,
+    height: usize,
+    dark: Vec<bool>
+}
+
+impl Bitmap {
+    /// Threshold against the mean of the neighbourhood, copes with shadows on paper
+    fn adaptive(image: &GrayImage) -> Self {
+        let (width, height) = (image.width() as usize, image.height() as usize);
+        let mut integral = vec![0u64;(width + 1) * (height + 1)];
+        for y in 0..height {
+            let mut row = 0u64;
+            for x in 0..width {
+                row += u64::from(image.get_pixel(x as u32, y as u32).0[0]);
+                integral[(y + 1) * (width + 1) + x + 1] = integral[y * (width + 1) + x + 1] + row;
+            }
+        }
+
+        let radius = (width.max(height) / 16).max(8);
+        let mut dark = vec![false;width * height];
+        for y in 0..height {
+            let (top, bottom) = (y.saturating_sub(radius), (y + radius + 1).min(height));
+            for x in 0..width {
+                let (left, right) = (x.saturating_sub(radius), (x + radius + 1).min(width));
+                let sum = integral[bottom * (width + 1) + right] + integral[top * (width + 1) + left]
+                    - integral[top * (width + 1) + right] - integral[bottom * (width + 1) + left];
+                let area = ((bottom - top) * (right - left)) as u64;
+                let pixel = u64::from(image.get_pixel(x as u32, y as u32).0[0]);
+                dark[y * width + x] = pixel * area * 100 < sum * 85;
+            }
+        }
+
+        Bitmap { width, height, dark }
+    }
+
+    /// One threshold for the whole picture, chosen by Otsu's method
+    fn global(image: &GrayImage) -> Self {
+        let mut histogram = [0u64;256];
+        for pixel in image.pixels() {
+            histogram[pixel.0[0] as usize] += 1;
+        }
+        let total: u64 = histogram.iter().sum();
+        let weighted: f64 = histogram.iter().enumerate().map(|(value, &count)| value as f64 * count as f64).sum();
+
+        let (mut below, mut below_sum) = (0u64, 0f64);
+        let (mut best, mut threshold) = (0f64, 128u8);
+        for (value, &count) in histogram.iter().enumerate() {
+            below += count;
+            below_sum += value as f64 * count as f64;
+            if below == 0 || below == total { continue; }
+            let above = total - below;
+            let mean_below = below_sum / below as f64;
+            let mean_above = (weighted - below_sum) / above as f64;
+            let variance = below as f64 * above as f64 * (mean_below - mean_above).powi(2);
+            if variance > best {
+                best = variance;
+                threshold = value as u8;
+            }
+        }
+
+        Bitmap {
+            width: image.width() as usize,
+            height: image.height() as usize,
+            dark: image.pixels().map(|pixel| pixel.0[0] <= threshold).collect()
+        }
+    }
+
+    /// Outside of the picture is light
+    fn get(&self, x: f64, y: f64) -> bool {
+        if x < 0.0 || y < 0.0 { return false; }
+        let (x, y) = (x as usize, y as usize);
+        x < self.width && y < self.height && self.dark[y * self.width + x]
+    }
+}
+
+#[derive(Debug,
+    Clone, Copy)]
+struct Point {
+    x: f64,
+    y: f64
+}
+
+impl Point {
+    fn distance(&self, other: Point) -> f64 {
+        (self.x - other.x).hypot(self.y - other.y)
+    }
+}
+
+/// Centre of a 7x7 corner square
+#[derive(Debug,
+    Clone, Copy)]
+struct Finder {
+    center: Point,
+    module: f64,
+    hits: usize
+}
+
+/// Runs of dark, light, dark, light, dark in proportion 1:1:3:1:1
+fn finder_ratio(runs: &[usize;5]) -> Option<f64> {
+    let total: usize = runs.iter().sum();
+    if total < 7 { return None; }
+    let module = total as f64 / 7.0;
+    let tolerance = module / 2.0;
+    let fits = runs
+        .iter()
+        .zip([1.0, 1.0, 3.0, 1.0, 1.0])
+        .all(|(&run, size)| (run as f64 - module * size).abs() < tolerance * size);
+
+    fits.then_some(total as f64)
+}
+
+/// Walks from a dark pixel along a direction and measures the pattern through it,
+/// returns the centre offset along the walk and the total length
+fn cross_check(bitmap: &Bitmap, from: Point, step: (f64, f64), limit: usize) -> Option<(f64, f64)> {
+    let at = |i: isize| bitmap.get(from.x + step.0 * i as f64, from.y + step.1 * i as f64);
+    if !at(0) { return None; }
+
+    let mut runs = [0usize;5];
+    // Backwards: the centre, light, dark
+    let mut i = 0isize;
+    for (run, dark) in [(2, true), (1, false), (0, true)] {
+        while at(i) == dark && runs[run] <= limit {
+            runs[run] += 1;
+            i -= 1;
+        }
+    }
+    let back = runs[2];
+    // Forwards: the rest of the centre, light, dark
+    let mut i = 1isize;
+    for (run, dark) in [(2, true), (3, false), (4, true)] {
+        while at(i) == dark && runs[run] <= limit {
+            runs[run] += 1;
+            i += 1;
+        }
+    }
+    if runs.iter().any(|&run| run == 0 || run > limit) { return None; }
+
+    let total = finder_ratio(&runs)?;
+    Some((runs[2] as f64 / 2.0 - back as f64 + 0.5, total))
+}
+
+fn find_finders(bitmap: &Bitmap) -> Vec<Finder> {
+    let mut finders: Vec<Finder> = Vec::new();
+    for y in 0..bitmap.height {
+        let row = &bitmap.dark[y * bitmap.width..(y + 1) * bitmap.width];
+        let mut runs: Vec<(bool, usize, usize)> = Vec::new();
+        for (x, &dark) in row.iter().enumerate() {
+            match runs.last_mut() {
+                Some((color, _, length)) if *color == dark => *length += 1,
+                _ => runs.push((dark, x, 1)),
+            }
+        }
+
+        for window in runs.windows(5).filter(|window| window[0].0) {
+            let lengths = [window[0].2, window[1].2, window[2].2, window[3].2, window[4].2];
+            let Some(horizontal) = finder_ratio(&lengths) else {
+                continue;
+            };
+            let limit = (horizontal * 2.0) as usize;
+            let x = window[2].1 as f64 + window[2].2 as f64 / 2.0;
+            let Some((dy, vertical)) = cross_check(bitmap, Point { x, y: y as f64 + 0.5 }, (0.0, 1.0), limit) else {
+                continue;
+            };
+            if vertical > horizontal * 2.0 || horizontal > vertical * 2.0 { continue; }
+            let center_y = y as f64 + 0.5 + dy;
+            let Some((dx, horizontal)) = cross_check(bitmap, Point { x, y: center_y }, (1.0, 0.0), limit) else {
+                continue;
+            };
+            let center = Point { x: x + dx, y: center_y };
+            let module = (horizontal + vertical) / 14.0;
+
+            match finders.iter_mut().find(|finder| finder.center.distance(center) < finder.module.max(module) * 2.0) {
+                Some(finder) => {
+                    let weight = finder.hits as f64;
+                    finder.center.x = (finder.center.x * weight + center.x) / (weight + 1.0);
+                    finder.center.y = (finder.center.y * weight + center.y) / (weight + 1.0);
+                    finder.module = (finder.module * weight + module) / (weight + 1.0);
+                    finder.hits += 1;
+                },
+                None => finders.push(Finder { center, module, hits: 1 }),
+            }
+        }
+    }
+
+    finders.retain(|finder| finder.hits >= 2);
+    finders.sort_by_key(|finder| std::cmp::Reverse(finder.hits));
+    finders.truncate(12);
+    finders
+}
+
+/// Top left, top right and bottom left finders that may belong to one code, best first
+fn corners(finders: &[Finder]) -> Vec<[Finder;3]> {
+    let mut triples = Vec::new();
+    for a in 0..finders.len() {
+        for b in a + 1..finders.len() {
+            for c in b + 1..finders.len() {
+                let three = [finders[a], finders[b], finders[c]];
+                let largest = three.iter().map(|finder| finder.module).fold(0.0, f64::max);
+                let smallest = three.iter().map(|finder| finder.module).fold(f64::MAX, f64::min);
+                if largest > smallest * 1.5 { continue; }
+
+                // The corner is the finder opposite to the longest side
+                let mut sides = [
+                    (three[1].center.distance(three[2].center), 0, 1, 2),
+                    (three[0].center.distance(three[2].center), 1, 0, 2),
+                    (three[0].center.distance(three[1].center), 2, 0, 1),
+                ];
+                sides.sort_by(|x, y| y.0.total_cmp(&x.0));
+                let (_, corner, first, second) = sides[0];
+                let (top_left, mut top_right, mut bottom_left) = (three[corner], three[first], three[second]);
+
+                let (ax, ay) = (top_right.center.x - top_left.center.x, top_right.center.y - top_left.center.y);
+                let (bx, by) = (bottom_left.center.x - top_left.center.x, bottom_left.center.y - top_left.center.y);
+                let (a_length, b_length) = (ax.hypot(ay), bx.hypot(by));
+                let cosine = (ax * bx + ay * by) / (a_length * b_length);
+                if cosine.abs() > 0.35 { continue; }
+                if a_length.max(b_length) > a_length.min(b_length) * 1.6 { continue; }
+                // Version 1 has 14 modules between the centres, run lengths of a turned code look up to √2 longer
+                if a_length.min(b_length) < smallest * 9.0 { continue; }
+                // Clockwise from the corner in picture coordinates
+                if ax * by - ay * bx < 0.0 {
+                    std::mem::swap(&mut top_right, &mut bottom_left);
+                }
+
+                triples.push((cosine.abs(), [top_left, top_right, bottom_left]));
+            }
+        }
+    }
+    triples.sort_by(|x, y| x.0.total_cmp(&y.0));
+
+    triples.into_iter().take(MAX_TRIPLES).map(|(_, triple)| triple).collect()
+}
+
+/// Projective map from module coordinates to picture coordinates
+struct Transform([f64;8]);
+
+impl Transform {
+    fn from_points(modules: [Point;4], picture: [Point;4]) -> Option<Self> {
+        let mut matrix = [[0f64;9];8];
+        for (i, (from, to)) in modules.iter().zip(picture.iter()).enumerate() {
+            matrix[2 * i] = [from.x, from.y, 1.0, 0.0, 0.0, 0.0, -from.x * to.x, -from.y * to.x, to.x];
+            matrix[2 * i + 1] = [0.0, 0.0, 0.0, from.x, from.y, 1.0, -from.x * to.y, -from.y * to.y, to.y];
+        }
+
+        // Gaussian elimination with partial pivoting
+        for column in 0..8 {
+            let pivot = (column..8).max_by(|&a, &b| matrix[a][column].abs().total_cmp(&matrix[b][column].abs()))?;
+            if matrix[pivot][column].abs() < 1e-12 { return None; }
+            matrix.swap(column, pivot);
+            for row in 0..8 {
+                if row == column { continue; }
+                let factor = matrix[row][column] / matrix[column][column];
+                let pivot_row = matrix[column];
+                for (value, pivot_value) in matrix[row].iter_mut().zip(pivot_row).skip(column) {
+                    *value -= factor * pivot_value;
+                }
+            }
+        }
+
+        let mut h = [0f64;8];
+        for (i, value) in h.iter_mut().enumerate() {
+            *value = matrix[i][8] / matrix[i][i];
+        }
+        Some(Transform(h))
+    }
+
+    fn apply(&self, x: f64, y: f64) -> Point {
+        let h = &self.0;
+        let w = h[6] * x + h[7] * y + 1.0;
+        Point {
+            x: (h[0] * x + h[1] * y + h[2]) / w,
+            y: (h[3] * x + h[4] * y + h[5]) / w
+        }
+    }
+}
+
+/// Looks for the bottom right alignment pattern near where the three finders put it
+fn find_alignment(bitmap: &Bitmap, [top_left, top_right, bottom_left]: &[Finder;3], size: usize) -> Option<Point> {
+    let span = (size - 7) as f64;
+    let ux = ((top_right.center.x - top_left.center.x) / span, (top_right.center.y - top_left.center.y) / span);
+    let uy = ((bottom_left.center.x - top_left.center.x) / span, (bottom_left.center.y - top_left.center.y) / span);
+    let offset = size as f64 - 10.0;
+    let estimate = Point {
+        x: top_left.center.x + (ux.0 + uy.0) * offset,
+        y: top_left.center.y + (ux.1 + uy.1) * offset
+    };
+
+    // A photo at an angle makes the far corner smaller or larger and moves it off the estimate
+    let module = ux.0.hypot(ux.1).max(uy.0.hypot(uy.1));
+    let radius = (module * 8.0).ceil() as isize;
+    let mut best = 0;
+    let mut found: Vec<Point> = Vec::new();
+    for scale in [1.0, 0.85, 1.15] {
+        for dy in -radius..=radius {
+            for dx in -radius..=radius {
+                let center = Point { x: estimate.x + dx as f64, y: estimate.y + dy as f64 };
+                let mut score = 0;
+                for b in -2i32..=2 {
+                    for a in -2i32..=2 {
+                        let dark = a.abs().max(b.abs()) != 1;
+                        let x = center.x + (ux.0 * a as f64 + uy.0 * b as f64) * scale;
+                        let y = center.y + (ux.1 * a as f64 + uy.1 * b as f64) * scale;
+                        if bitmap.get(x, y) == dark {
+                            score += 1;
+                        }
+                    }
+                }
+                if score > best {
+                    best = score;
+                    found.clear();
+                }
+                if score == best {
+                    found.push(center);
+                }
+            }
+        }
+    }
+    if best < 23 { return None; }
+
+    // The best match nearest to the estimate, averaged with its neighbours
+    let nearest = found.iter().copied().min_by(|a, b| a.distance(estimate).total_cmp(&b.distance(estimate)))?;
+    let close: Vec<Point> = found.into_iter().filter(|point| point.distance(nearest) < module).collect();
+    let count = close.len() as f64;
+    Some(Point {
+        x: close.iter().map(|point| point.x).sum::<f64>() / count,
+        y: close.iter().map(|point| point.y).sum::<f64>() / count
+    })
+}
+
+/// Modules of a code, `true` is dark, rows first
+struct Grid {
+    size: usize,
+    modules: Vec<bool>
+}
+
+impl Grid {
+    fn sample(bitmap: &Bitmap, transform: &Transform, size: usize) -> Self {
+        let mut modules = Vec::with_capacity(size * size);
+        for row in 0..size {
+            for column in 0..size {
+                let point = transform.apply(column as f64 + 0.5, row as f64 + 0.5);
+                modules.push(bitmap.get(point.x, point.y));
+            }
+        }
+        Grid { size, modules }
+    }
+
+    fn get(&self, column: usize, row: usize) -> bool {
+        self.modules[row * self.size + column]
+    }
+
+    /// Mirror image, for codes photographed through the back of the paper or a front camera
+    fn transposed(&self) -> Self {
+        let mut modules = Vec::with_capacity(self.modules.len());
+        for row in 0..self.size {
+            for column in 0..self.size {
+                modules.push(self.get(row, column));
+            }
+        }
+        Grid { size: self.size, modules }
+    }
+
+    fn version(&self) -> usize {
+        (self.size - 17) / 4
+    }
+
+    /// Level and mask from either copy of the format information
+    fn format(&self) -> Option<(Level, usize)> {
+        let size = self.size;
+        let mut first = 0u32;
+        let mut second = 0u32;
+        for i in 0..15 {
+            let (column, row) = match i {
+                0..=5 => (8, i),
+                6 => (8, 7),
+                7 => (8, 8),
+                8 => (7, 8),
+                _ => (14 - i, 8),
+            };
+            first |= u32::from(self.get(column, row)) << i;
+            let (column, row) = match i {
+                0..=7 => (size - 1 - i, 8),
+                _ => (8, size - 15 + i),
+            };
+            second |= u32::from(self.get(column, row)) << i;
+        }
+
+        let mut best = (u32::MAX, 0u32);
+        for data in 0..32u32 {
+            let mut remainder = data;
+            for _ in 0..10 {
+                remainder = (remainder << 1) ^ ((remainder >> 9) * 0x537);
+            }
+            let code = ((data << 10) | remainder) ^ 0x5412;
+            for read in [first, second] {
+                let distance = (code ^ read).count_ones();
+                if distance < best.0 {
+                    best = (distance, data);
+                }
+            }
+        }
+        if best.0 > 3 { return None; }
+
+        Some((Level::from_bits(best.1 >> 3), (best.1 & 7) as usize))
+    }
+
+    fn alignment_positions(&self) -> Vec<usize> {
+        let version = self.version();
+        if version == 1 { return Vec::new(); }
+        let count = version / 7 + 2;
+        let step = (version * 8 + count * 3 + 5) / (count * 4 - 4) * 2;
+        let mut positions = vec![6];
+        positions.extend((0..count - 1).rev().map(|i| self.size - 7 - i * step));
+        positions
+    }
+
+    /// Modules that carry data rather than patterns and format information
+    fn data_mask(&self) -> Vec<bool> {
+        let size = self.size;
+        let mut data = vec![true;size * size];
+        let mut function = |column: usize, row: usize| data[row * size + column] = false;
+
+        for i in 0..size {
+            function(6, i);
+            function(i, 6);
+        }
+        for row in 0..9 {
+            for column in 0..9 {
+                function(column, row);
+            }
+        }
+        for row in 0..9 {
+            for column in size - 8..size {
+                function(column, row);
+            }
+        }
+        for row in size - 8..size {
+            for column in 0..9 {
+                function(column, row);
+            }
+        }
+
+        let positions = self.alignment_positions();
+        let last = positions.len().saturating_sub(1);
+        for (i, &row) in positions.iter().enumerate() {
+            for (j, &column) in positions.iter().enumerate() {
+                // The three that would overlap finders are not drawn
+                let corner = (i == 0 || i == last) && (j == 0 || j == last);
+                if corner && !(i == last && j == last) { continue; }
+                for dy in 0..5 {
+                    for dx in 0..5 {
+                        function(column + dx - 2, row + dy - 2);
+                    }
+                }
+            }
+        }
+
+        if self.version() >= 7 {
+            for i in 0..6 {
+                for j in size - 11..size - 8 {
+                    function(j, i);
+                    function(i, j);
+                }
+            }
+        }
+
+        data
+    }
+
+    /// All codewords in the zigzag order, mask removed
+    fn codewords(&self, mask: usize) -> Vec<u8> {
+        let size = self.size;
+        let data = self.data_mask();
+        let masked = |column: usize, row: usize| match mask {
+            0 => (column + row).is_multiple_of(2),
+            1 => row.is_multiple_of(2),
+            2 => column.is_multiple_of(3),
+            3 => (column + row).is_multiple_of(3),
+            4 => (column / 3 + row / 2).is_multiple_of(2),
+            5 => column * row % 2 + column * row % 3 == 0,
+            6 => (column * row % 2 + column * row % 3).is_multiple_of(2),
+            _ => ((column + row) % 2 + column * row % 3).is_multiple_of(2),
+        };
+
+        let mut bits = Vec::new();
+        let mut right = size - 1;
+        while right >= 1 {
+            if right == 6 {
+                right = 5;
+            }
+            let upward = (right + 1) & 2 == 0;
+            for vertical in 0..size {
+                let row = if upward { size - 1 - vertical } else { vertical };
+                for column in [right, right - 1] {
+                    if data[row * size + column] {
+                        bits.push(self.get(column, row) != masked(column, row));
+                    }
+                }
+            }
+            if right < 2 { break; }
+            right -= 2;
+        }
+
+        bits.chunks_exact(8)
+            .map(|byte| byte.iter().fold(0u8, |value, &bit| (value << 1) | u8::from(bit)))
+            .collect()
+    }
+
+    /// Data codewords after error correction
+    fn data(&self) -> Option<Vec<u8>> {
+        let (level, mask) = self.format()?;
+        let version = self.version();
+        let codewords = self.codewords(mask);
+
+        let blocks = BLOCKS[level.index()][version] as usize;
+        let ecc = ECC_PER_BLOCK[level.index()][version] as usize;
+        let short_blocks = blocks - codewords.len() % blocks;
+        let short_length = codewords.len() / blocks;
+        if short_length <= ecc { return None; }
+
+        let mut split: Vec<Vec<u8>> = vec![Vec::new();blocks];
+        let mut next = codewords.iter();
+        for i in 0..=short_length {
+            for (j, block) in split.iter_mut().enumerate() {
+                // Short blocks have one data codeword less
+                if j < short_blocks && i == short_length - ecc { continue; }
+                block.push(*next.next()?);
+            }
+        }
+
+        let mut data = Vec::new();
+        for mut block in split {
+            correct(&mut block, ecc)?;
+            data.extend_from_slice(&block[..block.len() - ecc]);
+        }
+        Some(data)
+    }
+
+    fn decode(&self) -> Option<String> {
+        let data = self.data()?;
+        let version = self.version();
+        let mut bits = Bits { data: &data, position: 0 };
+        let mut bytes = Vec::new();
+
+        while bits.remaining() >= 4 {
+            match bits.read(4)? {
+                0 => break,
+                // Numeric
+                1 => {
+                    let mut count = bits.read(match version { 1..=9 => 10, 10..=26 => 12, _ => 14 })?;
+                    while count > 0 {
+                        let digits = count.min(3);
+                        let value = bits.read([0, 4, 7, 10][digits])?;
+                        bytes.extend(format!("{:0width$}", value, width = digits).bytes());
+                        count -= digits;
+                    }
+                },
+                // Alphanumeric
+                2 => {
+                    let mut count = bits.read(match version { 1..=9 => 9, 10..=26 => 11, _ => 13 })?;
+                    while count >= 2 {
+                        let value = bits.read(11)?;
+                        bytes.push(*ALPHANUMERIC.get(value / 45)?);
+                        bytes.push(*ALPHANUMERIC.get(value % 45)?);
+                        count -= 2;
+                    }
+                    if count == 1 {
+                        bytes.push(*ALPHANUMERIC.get(bits.read(6)?)?);
+                    }
+                },
+                // Byte
+                4 => {
+                    let count = bits.read(if version <= 9 { 8 } else { 16 })?;
+                    for _ in 0..count {
+                        bytes.push(bits.read(8)? as u8);
+                    }
+                },
+                // Structured append header
+                3 => {
+                    bits.read(16)?;
+                },
+                // Extended channel, the text is taken as UTF-8 anyway
+                7 => {
+                    let first = bits.read(8)?;
+                    if first & 0x80 != 0 {
+                        bits.read(if first & 0x40 == 0 { 8 } else { 16 })?;
+                    }
+                },
+                // FNC1 markers, the second one carries an application indicator
+                5 => (),
+                9 => {
+                    bits.read(8)?;
+                },
+                _ => return None,
+            }
+        }
+
+        match String::from_utf8(bytes) {
+            Ok(text) => Some(text),
+            Err(error) => Some(error.into_bytes().iter().map(|&byte| char::from(byte)).collect()),
+        }
+    }
+}
+
+struct Bits<'a> {
+    data: &'a [u8],
+    position: usize
+}
+
+impl Bits<'_> {
+    fn remaining(&self) -> usize {
+        self.data.len() * 8 - self.position
+    }
+
+    fn read(&mut self, count: usize) -> Option<usize> {
+        if count > self.remaining() { return None; }
+        let mut value = 0;
+        for _ in 0..count {
+            let bit = self.data[self.position / 8] >> (7 - self.position % 8) & 1;
+            value = (value << 1) | usize::from(bit);
+            self.position += 1;
+        }
+        Some(value)
+    }
+}
+
+/// Modules whose colour is known in advance: finders, timing and alignment patterns
+fn known_modules(size: usize) -> Vec<(usize, usize, bool)> {
+    let mut known = Vec::new();
+    for (left, top) in [(0, 0), (size - 7, 0), (0, size - 7)] {
+        for dy in 0..7usize {
+            for dx in 0..7usize {
+                known.push((left + dx, top + dy, dx.abs_diff(3).max(dy.abs_diff(3)) != 2));
+            }
+        }
+    }
+    for i in 8..size - 8 {
+        known.push((i, 6, i % 2 == 0));
+        known.push((6, i, i % 2 == 0));
+    }
+    let grid = Grid { size, modules: Vec::new() };
+    let positions = grid.alignment_positions();
+    let last = positions.len().saturating_sub(1);
+    for (i, &row) in positions.iter().enumerate() {
+        for (j, &column) in positions.iter().enumerate() {
+            let corner = (i == 0 || i == last) && (j == 0 || j == last);
+            if corner && !(i == last && j == last) { continue; }
+            for dy in 0..5usize {
+                for dx in 0..5usize {
+                    known.push((column + dx - 2, row + dy - 2, dx.abs_diff(2).max(dy.abs_diff(2)) != 1));
+                }
+            }
+        }
+    }
+
+    known
+}
+
+/// Moves the four picture points a little at a time while more known modules fall into place,
+/// straightens out what the finders alone get wrong in a photo taken at an angle
+fn refine(bitmap: &Bitmap, size: usize, modules: [Point;4], mut picture: [Point;4], module: f64) -> Option<Transform> {
+    let known = known_modules(size);
+    let score = |picture: &[Point;4]| -> Option<usize> {
+        let transform = Transform::from_points(modules, *picture)?;
+        Some(known
+            .iter()
+            .filter(|(column, row, dark)| {
+                let point = transform.apply(*column as f64 + 0.5, *row as f64 + 0.5);
+                bitmap.get(point.x, point.y) == *dark
+            })
+            .count())
+    };
+
+    let mut best = score(&picture)?;
+    let mut step = module / 2.0;
+    while step > module / 8.0 {
+        let mut improved = true;
+        while improved {
+            improved = false;
+            for i in 0..4 {
+                for (dx, dy) in [(step, 0.0), (-step, 0.0), (0.0, step), (0.0, -step)] {
+                    let mut moved = picture;
+                    moved[i].x += dx;
+                    moved[i].y += dy;
+                    if let Some(candidate) = score(&moved).filter(|&candidate| candidate > best) {
+                        best = candidate;
+                        picture = moved;
+                        improved = true;
+                    }
+                }
+            }
+        }
+        step /= 2.0;
+    }
+
+    Transform::from_points(modules, picture)
+}
+
+/// Module size measured from the finder towards another one, rows and columns
+/// of a turned code cross the finder at an angle and look wider
+fn module_towards(bitmap: &Bitmap, finder: &Finder, to: &Finder) -> f64 {
+    let (dx, dy) = (to.center.x - finder.center.x, to.center.y - finder.center.y);
+    let length = dx.hypot(dy);
+    cross_check(bitmap, finder.center, (dx / length, dy / length), (finder.module * 14.0) as usize)
+        .map(|(_, total)| total / 7.0)
+        .unwrap_or(finder.module)
+}
+
+/// Reads the code the three finders belong to
+fn read_code(bitmap: &Bitmap, finders: &[Finder;3]) -> Option<String> {
+    let [top_left, top_right, bottom_left] = finders;
+    let across = (module_towards(bitmap, top_left, top_right) + module_towards(bitmap, top_right, top_left)) / 2.0;
+    let down = (module_towards(bitmap, top_left, bottom_left) + module_towards(bitmap, bottom_left, top_left)) / 2.0;
+    let span = (top_left.center.distance(top_right.center) / across + top_left.center.distance(bottom_left.center) / down) / 2.0;
+    let estimate = ((span + 7.0 - 17.0) / 4.0).round() as i64;
+
+    for version in [estimate, estimate - 1, estimate + 1] {
+        if !(1..=40).contains(&version) { continue; }
+        let size = 17 + 4 * version as usize;
+        let far = size as f64 - 3.5;
+        let modules = [Point { x: 3.5, y: 3.5 }, Point { x: far, y: 3.5 }, Point { x: 3.5, y: far }];
+        let picture = [top_left.center, top_right.center, bottom_left.center];
+
+        // The fourth point: the alignment pattern if there is one, otherwise the corner
+        // of the parallelogram and, for a photo at an angle, points around it
+        let span = (size - 7) as f64;
+        let ux = ((top_right.center.x - top_left.center.x) / span, (top_right.center.y - top_left.center.y) / span);
+        let uy = ((bottom_left.center.x - top_left.center.x) / span, (bottom_left.center.y - top_left.center.y) / span);
+        let mut offsets: Vec<(i32, i32)> = (-CORNER_SEARCH..=CORNER_SEARCH)
+            .flat_map(|a| (-CORNER_SEARCH..=CORNER_SEARCH).map(move |b| (a, b)))
+            .filter(|(a, b)| a * a + b * b <= CORNER_SEARCH * CORNER_SEARCH)
+            .collect();
+        offsets.sort_by_key(|(a, b)| a * a + b * b);
+        let mut fourth: Vec<(Point, Point)> = offsets
+            .into_iter()
+            .map(|(a, b)| (Point { x: far, y: far }, Point {
+                x: top_right.center.x + bottom_left.center.x - top_left.center.x + ux.0 * f64::from(a) + uy.0 * f64::from(b),
+                y: top_right.center.y + bottom_left.center.y - top_left.center.y + ux.1 * f64::from(a) + uy.1 * f64::from(b)
+            }))
+            .collect();
+        if version > 1 {
+            if let Some(alignment) = find_alignment(bitmap, finders, size) {
+                let near = size as f64 - 6.5;
+                fourth.insert(0, (Point { x: near, y: near }, alignment));
+            }
+        }
+
+        let module = (across + down) / 2.0;
+        for (attempt, (module_point, picture_point)) in fourth.into_iter().enumerate() {
+            let modules = [modules[0], modules[1], modules[2], module_point];
+            let picture = [picture[0], picture[1], picture[2], picture_point];
+            // The best guesses are refined, the rest of the points around the corner are tried as they are
+            let transform = match attempt {
+                0..=1 => refine(bitmap, size, modules, picture, module),
+                _ => Transform::from_points(modules, picture),
+            };
+            let Some(transform) = transform else {
+                continue;
+            };
+            let grid = Grid::sample(bitmap, &transform, size);
+            if let Some(text) = grid.decode().or_else(|| grid.transposed().decode()) {
+                return Some(text);
+            }
+        }
+    }
+
+    None
+}
+
+fn decode_at(image: &GrayImage) -> Vec<String> {
+    let mut texts: Vec<String> = Vec::new();
+    for bitmap in [Bitmap::adaptive(image), Bitmap::global(image)] {
+        for finders in corners(&find_finders(&bitmap)) {
+            if let Some(text) = read_code(&bitmap, &finders) {
+                if !texts.contains(&text) {
+                    texts.push(text);
+                }
+            }
+        }
+        if !texts.is_empty() { break; }
+    }
+
+    texts
+}
+
+/// Texts of the QR codes found in the picture, decoded locally.
+/// The decoder is our own: the offline build has no QR decoding crate to depend on,
+/// so turned, faded, shadowed and stained codes are checked by the tests below
+pub fn decode(image: &GrayImage) -> Vec<String> {
+    let largest = image.width().max(image.height());
+    if largest > WORKING_SIZE {
+        let scale = f64::from(WORKING_SIZE) / f64::from(largest);
+        let small = image::imageops::resize(
+            image,
+            (f64::from(image.width()) * scale) as u32,
+            (f64::from(image.height()) * scale) as u32,
+            FilterType::Triangle
+        );
+        let texts = decode_at(&small);
+        if !texts.is_empty() { return texts; }
+    }
+
+    decode_at(image)
+}
+
+/// Opens a PNG or JPEG file and decodes the QR codes in it
+pub fn read(path: impl AsRef<Path>) -> Result<Vec<String>, Error> {
+    let image = image::open(path).map_err(|error| Error::Image(error.to_string()))?;
+    Ok(decode(&image.to_luma8()))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use image::{ImageFormat, Luma};
+    use qrcode::{EcLevel, QrCode};
+
+    use super::*;
+
+    const RECEIPT: &str = "t=20240131T1215&s=1234.56&fn=9960440300123456&i=12345&fp=1234567890&n=1";
+
+    /// The code as a PNG file, read back the way a photo is
+    fn round_trip(image: &GrayImage, name: &str) -> Result<Vec<String>, Error> {
+        let path = std::env::temp_dir().join(format!("money-counter-qr-{}-{}.png", std::process::id(), name));
+        image.save_with_format(&path, ImageFormat::Png).unwrap();
+        let texts = read(&path);
+        std::fs::remove_file(&path).unwrap();
+        texts
+    }
+
+    /// Pixels per module of the test codes, with the usual quiet zone of four modules
+    const MODULE: u32 = 6;
+
+    fn code(text: &str) -> GrayImage {
+        code_at(text, EcLevel::M)
+    }
+
+    fn code_at(text: &str, level: EcLevel) -> GrayImage {
+        QrCode::with_error_correction_level(text, level).unwrap().render::<Luma<u8>>().module_dimensions(MODULE, MODULE).build()
+    }
+
+    /// Modules from `from` to `to`, counted inside the quiet zone, painted over with `color`
+    fn damaged(image: &GrayImage, from: (u32, u32), to: (u32, u32), color: u8) -> GrayImage {
+        let mut damaged = image.clone();
+        for (x, y, pixel) in damaged.enumerate_pixels_mut() {
+            let (column, row) = (x / MODULE, y / MODULE);
+            if (from.0 + 4..to.0 + 4).contains(&column) && (from.1 + 4..to.1 + 4).contains(&row) {
+                *pixel = Luma([color]);
+            }
+        }
+        damaged
+    }
+
+    /// Turned by `degrees` around the centre on a larger white sheet
+    fn rotate(image: &GrayImage, degrees: f64) -> GrayImage {
+        let side = (f64::from(image.width().max(image.height())) * 1.5) as u32;
+        let (sin, cos) = degrees.to_radians().sin_cos();
+        let (center, half) = (f64::from(side) / 2.0, (f64::from(image.width()) / 2.0, f64::from(image.height()) / 2.0));
+        GrayImage::from_fn(side, side, |x, y| {
+            let (dx, dy) = (f64::from(x) - center, f64::from(y) - center);
+            let (sx, sy) = (cos * dx + sin * dy + half.0, -sin * dx + cos * dy + half.1);
+            match sx >= 0.0 && sy >= 0.0 && sx < f64::from(image.width()) && sy < f64::from(image.height()) {
+                true => *image.get_pixel(sx as u32, sy as u32),
+                false => Luma([255]),
+            }
+        })
+    }
+
+    /// Every pixel shifted by up to ±`amount`, the same noise on every run
+    fn noisy(image: &GrayImage, amount: i32) -> GrayImage {
+        let mut state = 0x2545_f491_u32;
+        let mut noisy = image.clone();
+        for pixel in noisy.pixels_mut() {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            let shift = (state % (2 * amount as u32 + 1)) as i32 - amount;
+            pixel.0[0] = (i32::from(pixel.0[0]) + shift).clamp(0, 255) as u8;
+        }
+        noisy
+    }
+
+    #[test]
+    fn receipt_code_round_trip() {
+        assert_eq!(round_trip(&code(RECEIPT), "plain"), Ok(vec![RECEIPT.to_string()]));
+    }
+
+    #[test]
+    fn longer_text_round_trip() {
+        let text = "ST00012|Name=ООО Ромашка|PersonalAcc=40702810900000000001|BankName=ПАО Сбербанк|BIC=044525225|CorrespAcc=30101810400000000225|Sum=123456";
+        assert_eq!(round_trip(&code(text), "long"), Ok(vec![text.to_string()]));
+    }
+
+    #[test]
+    fn rotated_code() {
+        assert_eq!(round_trip(&rotate(&code(RECEIPT), 30.0), "rotated"), Ok(vec![RECEIPT.to_string()]));
+    }
+
+    #[test]
+    fn code_turned_any_way() {
+        for degrees in [90.0, 180.0, 270.0, 45.0, -15.0] {
+            assert_eq!(decode(&rotate(&code(RECEIPT), degrees)), vec![RECEIPT.to_string()], "{}°", degrees);
+        }
+    }
+
+    #[test]
+    fn low_contrast_code() {
+        let faded = code(RECEIPT);
+        let grey = GrayImage::from_fn(faded.width(), faded.height(), |x, y| Luma([if faded.get_pixel(x, y).0[0] < 128 { 110 } else { 150 }]));
+        assert_eq!(decode(&grey), vec![RECEIPT.to_string()]);
+
+        // A shadow across the receipt: the light side of the code is darker than the dark side of the other
+        let width = f64::from(faded.width());
+        let shadowed = GrayImage::from_fn(faded.width(), faded.height(), |x, y| {
+            let light = 1.0 - 0.7 * f64::from(x) / width;
+            let value = if faded.get_pixel(x, y).0[0] < 128 { 20.0 } else { 250.0 };
+            Luma([(value * light) as u8])
+        });
+        assert_eq!(decode(&shadowed), vec![RECEIPT.to_string()]);
+    }
+
+    #[test]
+    fn damaged_code() {
+        // A stain over the data, within what level M corrects
+        assert_eq!(decode(&damaged(&code(RECEIPT), (14, 14), (19, 19), 0)), vec![RECEIPT.to_string()]);
+        assert_eq!(decode(&damaged(&code(RECEIPT), (9, 20), (15, 26), 255)), vec![RECEIPT.to_string()]);
+        // Torn across: too much is lost for level L, and nothing wrong is read instead
+        let torn = damaged(&code_at(RECEIPT, EcLevel::L), (9, 9), (24, 24), 255);
+        assert!(decode(&torn).is_empty());
+    }
+
+    #[test]
+    fn noisy_code() {
+        assert_eq!(round_trip(&noisy(&code(RECEIPT), 60), "noisy"), Ok(vec![RECEIPT.to_string()]));
+    }
+
+    #[test]
+    fn finder_look_alikes_are_tried_a_bounded_number_of_times() {
+        let squares = GrayImage::from_fn(1200, 900, |x, y| {
+            let distance = ((x % 300) as i32 - 100).abs().max(((y % 300) as i32 - 100).abs());
+            Luma([if distance < 18 || (30..42).contains(&distance) { 0 } else { 255 }])
+        });
+        let bitmap = Bitmap::adaptive(&squares);
+        assert_eq!(find_finders(&bitmap).len(), 12);
+        assert_eq!(corners(&find_finders(&bitmap)).len(), MAX_TRIPLES);
+    }
+
+    #[test]
+    fn picture_without_code() {
+        let stripes = GrayImage::from_fn(800, 600, |x, y| Luma([if (x / 7 + y / 11) % 3 == 0 { 0 } else { 255 }]));
+        assert_eq!(round_trip(&stripes, "stripes"), Ok(Vec::new()));
+    }
+}
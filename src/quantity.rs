@@ -0,0 +1,145 @@
+use rust_decimal::{prelude::ToPrimitive, Decimal};
+
+use crate::{
+    measure::{MeasureUnit, Measured},
+    money::{Error, Money, Operand, Rounding},
+    pronounce::NumberPronouce,
+    unit::Unit
+};
+
+/// Fractional amount of goods, e.g. 1.25 kg.
+/// `precision` is the number of allowed decimal places: 3 for kilograms
+/// weighed to a gram, 0 for pieces
+#[derive(Debug,
+    PartialEq, Eq, PartialOrd, Ord,
+    Clone, Copy)]
+pub struct Quantity {
+    value: Decimal,
+    precision: u32
+}
+
+impl Quantity {
+    /// Longest fraction that still has a name: "миллионная"
+    pub const MAX_PRECISION: u32 = 6;
+
+    pub fn new(value: Decimal, precision: u32) -> Result<Self, Error> {
+        if precision > Quantity::MAX_PRECISION { return Err(Error::PrecisionTooBig(Quantity::MAX_PRECISION)); }
+        if value.is_sign_negative() && !value.is_zero() { return Err(Error::NegativeQuantity); }
+        if value.normalize().scale() > precision { return Err(Error::QuantityDigitsTooBig(precision)); }
+
+        Ok(Quantity { value, precision })
+    }
+
+    /// Accepts both "1.25" and "1,25"
+    pub fn parse(text: &str, precision: u32) -> Result<Self, Error> {
+        let value = Decimal::from_str_exact(&text.trim().replace(',', "."))?;
+        Quantity::new(value, precision)
+    }
+
+    pub fn value(&self) -> Decimal {
+        self.value
+    }
+
+    pub fn precision(&self) -> u32 {
+        self.precision
+    }
+
+    /// Changes precision, rounding off digits that no longer fit
+    pub fn with_precision(&self, precision: u32, rounding: Rounding) -> Result<Self, Error> {
+        if precision > Quantity::MAX_PRECISION { return Err(Error::PrecisionTooBig(Quantity::MAX_PRECISION)); }
+
+        Ok(Quantity {
+            value: self.value.round_dp_with_strategy(precision, rounding.strategy()),
+            precision
+        })
+    }
+
+    /// Price of this quantity, brought to kopeks by `rounding`
+    pub fn cost(&self, price: Money, rounding: Rounding) -> Result<Money, Error> {
+        Ok(Money::round(price.checked_mul(self.value)?.0, rounding))
+    }
+}
+
+impl TryFrom<Unit> for Quantity {
+    type Error = Error;
+
+    fn try_from(value: Unit) -> Result<Self, Self::Error> {
+        Ok(Quantity { value: value.to_decimal()?, precision: 0 })
+    }
+}
+
+impl std::fmt::Display for Quantity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", MeasuredQuantity(*self, &MeasureUnit::ITEM))
+    }
+}
+
+/// Numbers before "целая" and "десятая" agree with feminine nouns
+fn pronounce_feminine(number: u128) -> Result<String, Error> {
+    if number == 0 { return Ok("ноль".to_string()); }
+
+    let pronounced = Measured(Unit(number), &MeasureUnit::ITEM).pronounce()?;
+    Ok(pronounced.split_whitespace().collect::<Vec<&str>>().join(" "))
+}
+
+fn fraction_pronounce(digits: u32) -> [&'static str;2] {
+    match digits {
+        1 => ["десятая", "десятых"],
+        2 => ["сотая", "сотых"],
+        3 => ["тысячная", "тысячных"],
+        4 => ["десятитысячная", "десятитысячных"],
+        5 => ["стотысячная", "стотысячных"],
+        _ => ["миллионная", "миллионных"]
+    }
+}
+
+/// Singular only after a number ending in 1, but not 11
+fn is_singular(number: u128) -> bool {
+    number % 10 == 1 && number % 100 != 11
+}
+
+/// Quantity with its unit of measure:
+/// "два килограмма", "одна целая двадцать пять сотых килограмма"
+pub struct MeasuredQuantity<'a>(pub Quantity, pub &'a MeasureUnit);
+
+impl std::fmt::Display for MeasuredQuantity<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let MeasuredQuantity(quantity, measure) = self;
+        let value = quantity.value.normalize();
+        let whole = value.trunc().to_u128().unwrap_or_default();
+
+        if value.scale() == 0 {
+            return write!(f, "{}", Measured(Unit(whole), measure));
+        }
+
+        let fraction = value.fract().mantissa().unsigned_abs();
+        let [fraction_one, fraction_many] = fraction_pronounce(value.scale());
+
+        let (Ok(whole_words), Ok(fraction_words)) = (pronounce_feminine(whole), pronounce_feminine(fraction)) else {
+            return write!(f, "{} {}", value, measure.short);
+        };
+
+        write!(f, "{} {} {} {} {}",
+            whole_words,
+            if is_singular(whole) { "целая" } else { "целых" },
+            fraction_words,
+            if is_singular(fraction) { fraction_one } else { fraction_many },
+            // Nouns after a fraction always take genitive singular
+            measure.forms[1]
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn precision_above_maximum_is_an_error() {
+        assert_eq!(Quantity::new(Decimal::ONE, 7), Err(Error::PrecisionTooBig(Quantity::MAX_PRECISION)));
+
+        let quantity = Quantity::parse("1,2345", 4).unwrap();
+        assert_eq!(quantity.with_precision(7, Rounding::HalfUp), Err(Error::PrecisionTooBig(Quantity::MAX_PRECISION)));
+        assert_eq!(quantity.with_precision(2, Rounding::HalfUp).unwrap().value(), Decimal::new(123, 2));
+    }
+}
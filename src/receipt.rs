@@ -0,0 +1,250 @@
+use std::{path::Path, str::FromStr};
+
+use chrono::{NaiveDateTime, Timelike};
+use rust_decimal::Decimal;
+
+use crate::{budget::Expense, money::{Money, SignPolicy}, qr};
+
+#[derive(Debug,
+    PartialEq,
+    Clone)]
+pub enum Error {
+    /// Name of the field missing from the QR string
+    MissingField(&'static str),
+    InvalidField(&'static str),
+    /// The picture has no QR code that could be read
+    NoQrCode,
+    Qr(qr::Error)
+}
+
+/// For logs only, what the user reads comes from `Localizer::error`
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Qr(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<qr::Error> for Error {
+    fn from(value: qr::Error) -> Self {
+        Error::Qr(value)
+    }
+}
+
+/// Type of a fiscal document, the `n` field of the QR code
+#[derive(Debug,
+    PartialEq, Eq,
+    Clone, Copy,
+    Default)]
+pub enum Operation {
+    /// The buyer pays, an ordinary purchase
+    #[default]
+    Income,
+    /// The buyer gets the money back
+    IncomeReturn,
+    /// The shop pays, e.g. buys scrap metal from the customer
+    Expense,
+    ExpenseReturn
+}
+
+impl Operation {
+    pub const ALL: [Operation;4] = [Operation::Income, Operation::IncomeReturn, Operation::Expense, Operation::ExpenseReturn];
+
+    fn code(&self) -> u8 {
+        match self {
+            Operation::Income => 1,
+            Operation::IncomeReturn => 2,
+            Operation::Expense => 3,
+            Operation::ExpenseReturn => 4,
+        }
+    }
+
+    /// Whether the money leaves the customer's pocket
+    pub fn spends(&self) -> bool {
+        matches!(self, Operation::Income | Operation::ExpenseReturn)
+    }
+}
+
+/// What the QR code of a Russian cash receipt says, enough to find the receipt
+/// at the tax service. Decoded offline, the items are not in the code
+#[derive(Debug,
+    PartialEq, Eq,
+    Clone)]
+pub struct Receipt {
+    /// Local time printed on the receipt
+    pub time: NaiveDateTime,
+    pub sum: Money,
+    /// Number of the fiscal storage, ФН
+    pub storage: String,
+    /// Number of the fiscal document, ФД
+    pub document: u32,
+    /// Fiscal sign, ФП
+    pub sign: String,
+    pub operation: Operation
+}
+
+impl Receipt {
+    /// Sum from the customer's side: negative when spent, positive when returned
+    pub fn amount(&self) -> Money {
+        match self.operation.spends() {
+            true => Money(-self.sum.0),
+            false => self.sum,
+        }
+    }
+
+    /// Budget expense, a return comes as a negative expense
+    pub fn expense(&self, category: &str) -> Expense {
+        Expense {
+            date: self.time.date(),
+            category: category.to_string(),
+            amount: Money(-self.amount().0),
+            note: self.note()
+        }
+    }
+
+    /// "ФН ..., ФД ..., ФП ...", enough to look the receipt up again
+    pub fn note(&self) -> String {
+        format!("ФН {}, ФД {}, ФП {}", self.storage, self.document, self.sign)
+    }
+}
+
+/// Receipt from a photo or a screenshot, the first QR code that is a receipt
+pub fn from_image(path: impl AsRef<Path>) -> Result<Receipt, Error> {
+    let texts = qr::read(path)?;
+    let mut first_error = None;
+    for text in texts {
+        match text.parse() {
+            Ok(receipt) => return Ok(receipt),
+            Err(error) => {
+                first_error.get_or_insert(error);
+            }
+        }
+    }
+
+    Err(first_error.unwrap_or(Error::NoQrCode))
+}
+
+fn digits(value: &str, field: &'static str) -> Result<String, Error> {
+    match !value.is_empty() && value.bytes().all(|byte| byte.is_ascii_digit()) {
+        true => Ok(value.to_string()),
+        false => Err(Error::InvalidField(field)),
+    }
+}
+
+impl FromStr for Receipt {
+    type Err = Error;
+
+    /// `t=20241001T1230&s=1234.50&fn=...&i=...&fp=...&n=1`, fields in any order,
+    /// unknown fields are skipped
+    fn from_str(text: &str) -> Result<Self, Error> {
+        let mut fields = std::collections::HashMap::new();
+        for pair in text.trim().split('&').filter(|pair| !pair.is_empty()) {
+            let (key, value) = pair.split_once('=').ok_or(Error::InvalidField("="))?;
+            fields.insert(key.trim().to_ascii_lowercase(), value.trim());
+        }
+        let field = |key: &'static str| fields.get(key).copied().ok_or(Error::MissingField(key));
+
+        let time = field("t")?;
+        let time = NaiveDateTime::parse_from_str(time, "%Y%m%dT%H%M%S")
+            .or_else(|_| NaiveDateTime::parse_from_str(time, "%Y%m%dT%H%M"))
+            .map_err(|_| Error::InvalidField("t"))?;
+        let sum = Money::try_from(field("s")?)
+            .and_then(|sum| SignPolicy::NonNegative.check(sum))
+            .map_err(|_| Error::InvalidField("s"))?;
+        let operation = match field("n")? {
+            "1" => Operation::Income,
+            "2" => Operation::IncomeReturn,
+            "3" => Operation::Expense,
+            "4" => Operation::ExpenseReturn,
+            _ => return Err(Error::InvalidField("n")),
+        };
+
+        Ok(Receipt {
+            time,
+            sum,
+            storage: digits(field("fn")?, "fn")?,
+            document: field("i")?.parse().map_err(|_| Error::InvalidField("i"))?,
+            sign: digits(field("fp")?, "fp")?,
+            operation
+        })
+    }
+}
+
+impl std::fmt::Display for Receipt {
+    /// The QR string back, kopeks always written
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut sum: Decimal = self.sum.0.round_dp(2);
+        sum.rescale(2);
+        let time = match self.time.second() {
+            0 => self.time.format("%Y%m%dT%H%M"),
+            _ => self.time.format("%Y%m%dT%H%M%S"),
+        };
+        write!(f, "t={}&s={}&fn={}&i={}&fp={}&n={}",
+            time,
+            sum,
+            self.storage,
+            self.document,
+            self.sign,
+            self.operation.code()
+        )
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+
+    const TEXT: &str = "t=20241001T1230&s=1234.50&fn=9960440300123456&i=12345&fp=1234567890&n=1";
+
+    #[test]
+    fn valid_string() {
+        let receipt: Receipt = TEXT.parse().unwrap();
+        assert_eq!(receipt, Receipt {
+            time: NaiveDate::from_ymd_opt(2024, 10, 1).unwrap().and_hms_opt(12, 30, 0).unwrap(),
+            sum: Money(Decimal::new(123450, 2)),
+            storage: "9960440300123456".to_string(),
+            document: 12345,
+            sign: "1234567890".to_string(),
+            operation: Operation::Income
+        });
+        assert_eq!(receipt.amount(), Money(Decimal::new(-123450, 2)));
+        assert_eq!(receipt.to_string(), TEXT);
+
+        let shuffled = "n=2&fp=1234567890&i=12345&fn=9960440300123456&s=100&t=20241001T123005&extra=1";
+        let receipt: Receipt = shuffled.parse().unwrap();
+        assert_eq!((receipt.operation, receipt.time.second()), (Operation::IncomeReturn, 5));
+        assert_eq!(receipt.to_string(), "t=20241001T123005&s=100.00&fn=9960440300123456&i=12345&fp=1234567890&n=2");
+    }
+
+    #[test]
+    fn missing_field() {
+        let text = TEXT.replace("&fp=1234567890", "");
+        assert_eq!(text.parse::<Receipt>(), Err(Error::MissingField("fp")));
+        assert_eq!("".parse::<Receipt>(), Err(Error::MissingField("t")));
+    }
+
+    #[test]
+    fn malformed_time_and_sum() {
+        for time in ["2024-10-01T12:30", "20241301T1230", "20241001"] {
+            let text = TEXT.replace("20241001T1230", time);
+            assert_eq!(text.parse::<Receipt>(), Err(Error::InvalidField("t")), "{}", time);
+        }
+        for sum in ["12,34,5", "-10", "1.234", "сто"] {
+            let text = TEXT.replace("1234.50", sum);
+            assert_eq!(text.parse::<Receipt>(), Err(Error::InvalidField("s")), "{}", sum);
+        }
+        assert_eq!(TEXT.replace("&n=1", "&n=5").parse::<Receipt>(), Err(Error::InvalidField("n")));
+        assert_eq!(TEXT.replace("fp=", "fp").parse::<Receipt>(), Err(Error::InvalidField("=")));
+    }
+}
@@ -0,0 +1,227 @@
+use chrono::{Days, Months, NaiveDate};
+use rust_decimal::Decimal;
+
+use crate::money::{Error, Money};
+
+#[derive(Debug,
+    PartialEq, Eq,
+    Clone, Copy,
+    Default)]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    /// On the same day of the month, the last day for months that are shorter
+    #[default]
+    Monthly,
+    Yearly
+}
+
+impl Frequency {
+    pub const ALL: [Frequency;4] = [Frequency::Daily, Frequency::Weekly, Frequency::Monthly, Frequency::Yearly];
+}
+
+/// When a recurring payment stops
+#[derive(Debug,
+    PartialEq, Eq,
+    Clone, Copy,
+    Default)]
+pub enum End {
+    #[default]
+    Never,
+    /// After this many payments
+    After(u32),
+    /// Last possible date, inclusive
+    Until(NaiveDate)
+}
+
+/// Rent, deliveries, licences: the same amount again and again
+#[derive(Debug,
+    PartialEq, Eq,
+    Clone)]
+pub struct Recurring {
+    pub name: String,
+    pub category: String,
+    pub amount: Money,
+    /// Date of the first payment
+    pub start: NaiveDate,
+    pub frequency: Frequency,
+    /// Every `interval` days, weeks, months or years
+    pub interval: u32,
+    pub end: End
+}
+
+/// One projected payment
+#[derive(Debug,
+    PartialEq, Eq,
+    Clone, Copy)]
+pub struct Occurrence {
+    /// Position of the rule in the slice given to `upcoming`
+    pub rule: usize,
+    pub date: NaiveDate,
+    pub amount: Money
+}
+
+impl Recurring {
+    /// Date of payment number `n`, counted from the start so that
+    /// a payment on the 31st doesn't drift to the 28th after February
+    fn nth(&self, n: u32) -> Option<NaiveDate> {
+        let steps = n.checked_mul(self.interval)?;
+        match self.frequency {
+            Frequency::Daily => self.start.checked_add_days(Days::new(steps.into())),
+            Frequency::Weekly => self.start.checked_add_days(Days::new(u64::from(steps) * 7)),
+            Frequency::Monthly => self.start.checked_add_months(Months::new(steps)),
+            Frequency::Yearly => self.start.checked_add_months(Months::new(steps.checked_mul(12)?)),
+        }
+    }
+
+    /// Payment dates between `from` and `to`, both inclusive
+    pub fn dates(&self, from: NaiveDate, to: NaiveDate) -> Result<Vec<NaiveDate>, Error> {
+        if self.interval == 0 { return Err(Error::ZeroInterval); }
+
+        let mut dates = Vec::new();
+        for n in 0.. {
+            if let End::After(count) = self.end {
+                if n >= count { break; }
+            }
+            let Some(date) = self.nth(n) else {
+                break;
+            };
+            if date > to { break; }
+            if let End::Until(until) = self.end {
+                if date > until { break; }
+            }
+
+            if date >= from {
+                dates.push(date);
+            }
+        }
+
+        Ok(dates)
+    }
+}
+
+/// Payments of all rules between `from` and `to`, earliest first
+pub fn upcoming(rules: &[Recurring], from: NaiveDate, to: NaiveDate) -> Result<Vec<Occurrence>, Error> {
+    let mut occurrences = Vec::new();
+    for (rule, recurring) in rules.iter().enumerate() {
+        for date in recurring.dates(from, to)? {
+            occurrences.push(Occurrence { rule, date, amount: recurring.amount });
+        }
+    }
+    occurrences.sort_by(|a, b| a.date.cmp(&b.date).then(a.rule.cmp(&b.rule)));
+
+    Ok(occurrences)
+}
+
+/// How much the rules of `category` will take between `from` and `to`
+pub fn due(rules: &[Recurring], category: &str, from: NaiveDate, to: NaiveDate) -> Result<Money, Error> {
+    let mut total = Money(Decimal::ZERO);
+    for recurring in rules.iter().filter(|recurring| recurring.category == category) {
+        let count = recurring.dates(from, to)?.len();
+        total = total.checked_add(recurring.amount.checked_mul(Decimal::from(count))?)?;
+    }
+
+    Ok(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn rule(start: NaiveDate, frequency: Frequency, interval: u32, end: End) -> Recurring {
+        Recurring {
+            name: "Аренда".to_string(),
+            category: "Жильё".to_string(),
+            amount: Money(Decimal::from(30_000)),
+            start,
+            frequency,
+            interval,
+            end
+        }
+    }
+
+    #[test]
+    fn the_31st_does_not_drift_to_the_28th() {
+        let rent = rule(date(2023, 1, 31), Frequency::Monthly, 1, End::Never);
+        assert_eq!(rent.dates(date(2023, 1, 1), date(2023, 5, 31)), Ok(vec![
+            date(2023, 1, 31),
+            date(2023, 2, 28),
+            date(2023, 3, 31),
+            date(2023, 4, 30),
+            date(2023, 5, 31)
+        ]));
+    }
+
+    #[test]
+    fn leap_day_falls_back_in_ordinary_years() {
+        let birthday = rule(date(2024, 2, 29), Frequency::Yearly, 1, End::Never);
+        assert_eq!(birthday.dates(date(2024, 1, 1), date(2028, 12, 31)), Ok(vec![
+            date(2024, 2, 29),
+            date(2025, 2, 28),
+            date(2026, 2, 28),
+            date(2027, 2, 28),
+            date(2028, 2, 29)
+        ]));
+    }
+
+    #[test]
+    fn intervals() {
+        let fortnightly = rule(date(2024, 1, 1), Frequency::Weekly, 2, End::Never);
+        assert_eq!(fortnightly.dates(date(2024, 1, 10), date(2024, 2, 12)), Ok(vec![
+            date(2024, 1, 15),
+            date(2024, 1, 29),
+            date(2024, 2, 12)
+        ]));
+        let every_third_day = rule(date(2024, 2, 27), Frequency::Daily, 3, End::Never);
+        assert_eq!(every_third_day.dates(date(2024, 2, 1), date(2024, 3, 5)), Ok(vec![
+            date(2024, 2, 27),
+            date(2024, 3, 1),
+            date(2024, 3, 4)
+        ]));
+        let quarterly = rule(date(2023, 11, 30), Frequency::Monthly, 3, End::Never);
+        assert_eq!(quarterly.dates(date(2023, 12, 1), date(2024, 9, 1)), Ok(vec![
+            date(2024, 2, 29),
+            date(2024, 5, 30),
+            date(2024, 8, 30)
+        ]));
+        assert_eq!(rule(date(2024, 1, 1), Frequency::Monthly, 0, End::Never).dates(date(2024, 1, 1), date(2024, 12, 31)),
+            Err(Error::ZeroInterval));
+    }
+
+    #[test]
+    fn ends() {
+        // Payments before `from` still count towards the limit
+        let three = rule(date(2024, 1, 10), Frequency::Monthly, 1, End::After(3));
+        assert_eq!(three.dates(date(2024, 2, 1), date(2024, 12, 31)), Ok(vec![date(2024, 2, 10), date(2024, 3, 10)]));
+        let until = rule(date(2024, 1, 10), Frequency::Monthly, 1, End::Until(date(2024, 3, 10)));
+        assert_eq!(until.dates(date(2024, 1, 1), date(2024, 12, 31)),
+            Ok(vec![date(2024, 1, 10), date(2024, 2, 10), date(2024, 3, 10)]));
+        assert_eq!(rule(date(2024, 1, 10), Frequency::Monthly, 1, End::After(0)).dates(date(2024, 1, 1), date(2024, 12, 31)),
+            Ok(Vec::new()));
+    }
+
+    #[test]
+    fn upcoming_and_due() {
+        let mut internet = rule(date(2024, 1, 5), Frequency::Monthly, 1, End::Never);
+        internet.category = "Связь".to_string();
+        internet.amount = Money(Decimal::from(600));
+        let rules = [rule(date(2024, 1, 5), Frequency::Monthly, 1, End::Never), internet];
+
+        let occurrences = upcoming(&rules, date(2024, 1, 1), date(2024, 2, 29)).unwrap();
+        let order: Vec<(usize, NaiveDate)> = occurrences.iter().map(|occurrence| (occurrence.rule, occurrence.date)).collect();
+        assert_eq!(order, [
+            (0, date(2024, 1, 5)),
+            (1, date(2024, 1, 5)),
+            (0, date(2024, 2, 5)),
+            (1, date(2024, 2, 5))
+        ]);
+
+        assert_eq!(due(&rules, "Жильё", date(2024, 1, 1), date(2024, 3, 31)), Ok(Money(Decimal::from(90_000))));
+        assert_eq!(due(&rules, "Связь", date(2024, 2, 1), date(2024, 3, 31)), Ok(Money(Decimal::from(1200))));
+        assert_eq!(due(&rules, "Еда", date(2024, 1, 1), date(2024, 3, 31)), Ok(Money(Decimal::ZERO)));
+    }
+}
@@ -0,0 +1,294 @@
+use rust_decimal::Decimal;
+
+use crate::money::{Error, Money, Rounding, SignPolicy};
+
+/// Receipt line and the participants who shared it
+#[derive(Debug,
+    PartialEq, Eq,
+    Clone)]
+pub struct Item {
+    pub price: Money,
+    /// Indices of participants, the price is split equally between them
+    pub consumers: Vec<usize>
+}
+
+/// Money one participant gives another to settle up
+#[derive(Debug,
+    PartialEq, Eq,
+    Clone, Copy)]
+pub struct Transfer {
+    pub from: usize,
+    pub to: usize,
+    pub amount: Money
+}
+
+/// Splits `total` in proportion to `weights` to the kopek.
+/// Every share is rounded down and the kopeks left over go to the largest remainders,
+/// so the shares always add up to `total`
+pub fn by_weight(total: Money, weights: &[Decimal]) -> Result<Vec<Money>, Error> {
+    if weights.is_empty() { return Err(Error::NoParticipants); }
+    if weights.iter().any(|weight| weight.is_sign_negative() && !weight.is_zero()) {
+        return Err(Error::NegativeQuantity);
+    }
+    let total = SignPolicy::NonNegative.check(total)?.kopeks()?;
+
+    // Whole numbers keep the division exact: 0.5 and 1.25 become 50 and 125
+    let scale = weights.iter().map(Decimal::scale).max().unwrap_or(0);
+    let mut integer_weights = Vec::with_capacity(weights.len());
+    for weight in weights {
+        let mut weight = *weight;
+        weight.rescale(scale);
+        if weight.scale() != scale { return Err(Error::OutOfRange); }
+        integer_weights.push(weight.mantissa());
+    }
+    let sum = integer_weights.iter().try_fold(0i128, |sum, weight| sum.checked_add(*weight)).ok_or(Error::Overflow)?;
+    if sum == 0 { return Err(Error::DivisionByZero); }
+
+    let mut shares = Vec::with_capacity(weights.len());
+    let mut remainders = Vec::with_capacity(weights.len());
+    for (index, weight) in integer_weights.iter().enumerate() {
+        let product = total.checked_mul(*weight).ok_or(Error::Overflow)?;
+        shares.push(product / sum);
+        remainders.push((product % sum, index));
+    }
+
+    let mut left = total - shares.iter().sum::<i128>();
+    remainders.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+    for (_, index) in remainders {
+        if left == 0 { break; }
+        shares[index] += 1;
+        left -= 1;
+    }
+
+    shares.into_iter().map(Money::from_kopeks).collect()
+}
+
+/// Equal shares; the first participants pay a kopek more when it doesn't divide evenly
+pub fn equal(total: Money, participants: usize) -> Result<Vec<Money>, Error> {
+    by_weight(total, &vec![Decimal::ONE; participants])
+}
+
+/// Everyone pays for what they had
+pub fn by_items(items: &[Item], participants: usize) -> Result<Vec<Money>, Error> {
+    if participants == 0 { return Err(Error::NoParticipants); }
+
+    let mut shares = vec![Money(Decimal::ZERO); participants];
+    for item in items {
+        if item.consumers.is_empty() || item.consumers.iter().any(|consumer| *consumer >= participants) {
+            return Err(Error::NoParticipants);
+        }
+
+        for (consumer, share) in item.consumers.iter().zip(equal(item.price, item.consumers.len())?) {
+            shares[*consumer] = shares[*consumer].checked_add(share)?;
+        }
+    }
+
+    Ok(shares)
+}
+
+/// Tip or service charge of `percent` on `subtotal`
+pub fn tip(subtotal: Money, percent: Decimal, rounding: Rounding) -> Result<Money, Error> {
+    if percent.is_sign_negative() && !percent.is_zero() { return Err(Error::NegativeAmount); }
+
+    let tip = subtotal.checked_mul(percent)?.checked_div(Decimal::ONE_HUNDRED)?;
+    Ok(Money::round(tip.0, rounding))
+}
+
+/// Adds the tip on the whole bill to the shares, in proportion to them
+pub fn with_tip(shares: &[Money], percent: Decimal, rounding: Rounding) -> Result<Vec<Money>, Error> {
+    let subtotal = shares.iter().try_fold(Money(Decimal::ZERO), |sum, share| sum.checked_add(*share))?;
+    let tip = tip(subtotal, percent, rounding)?;
+    if tip.0.is_zero() { return Ok(shares.to_vec()); }
+
+    let weights: Vec<Decimal> = shares.iter().map(|share| share.0).collect();
+    shares.iter()
+        .zip(by_weight(tip, &weights)?)
+        .map(|(share, tip)| share.checked_add(tip))
+        .collect()
+}
+
+/// "Кто кому должен": transfers that square what everyone `paid` with their `shares`.
+/// Participants whose balances cancel out settle among themselves, which gives
+/// the fewest transfers; for large groups the search is skipped
+pub fn settle(shares: &[Money], paid: &[Money]) -> Result<Vec<Transfer>, Error> {
+    if shares.len() != paid.len() { return Err(Error::UnbalancedPayments); }
+
+    let mut balances = Vec::new();
+    for (index, (share, paid)) in shares.iter().zip(paid).enumerate() {
+        let balance = paid.kopeks()?.checked_sub(share.kopeks()?).ok_or(Error::Overflow)?;
+        if balance != 0 {
+            balances.push((index, balance));
+        }
+    }
+    if balances.iter().map(|(_, balance)| balance).sum::<i128>() != 0 {
+        return Err(Error::UnbalancedPayments);
+    }
+
+    let mut transfers = Vec::new();
+    for group in zero_sum_groups(&balances) {
+        transfers.extend(settle_group(group)?);
+    }
+
+    Ok(transfers)
+}
+
+/// Groups the exact search is done for, 2^16 subsets at most
+const EXACT_LIMIT: usize = 16;
+
+/// Splits balances into as many groups summing to zero as possible.
+/// A group of n needs n - 1 transfers, so more groups mean fewer transfers
+fn zero_sum_groups(balances: &[(usize, i128)]) -> Vec<Vec<(usize, i128)>> {
+    let count = balances.len();
+    if count > EXACT_LIMIT { return vec![balances.to_vec()]; }
+
+    let subsets = 1usize << count;
+    let mut sums = vec![0i128; subsets];
+    let mut groups = vec![0u8; subsets];
+    for mask in 1..subsets {
+        let lowest = mask.trailing_zeros() as usize;
+        sums[mask] = sums[mask ^ (1 << lowest)] + balances[lowest].1;
+
+        let best = (0..count)
+            .filter(|i| mask & (1 << i) != 0)
+            .map(|i| groups[mask ^ (1 << i)])
+            .max()
+            .unwrap_or(0);
+        groups[mask] = best + u8::from(sums[mask] == 0);
+    }
+
+    // Taking members away one by one, a zero sum marks the end of a group
+    let mut order = Vec::with_capacity(count);
+    let mut mask = subsets - 1;
+    while mask != 0 {
+        let closes = u8::from(sums[mask] == 0);
+        let Some(i) = (0..count)
+            .filter(|i| mask & (1 << i) != 0)
+            .find(|i| groups[mask ^ (1 << i)] + closes == groups[mask]) else {
+            break;
+        };
+        order.push(i);
+        mask ^= 1 << i;
+    }
+
+    let mut result = Vec::new();
+    let mut group = Vec::new();
+    let mut sum = 0;
+    for i in order.into_iter().rev() {
+        group.push(balances[i]);
+        sum += balances[i].1;
+        if sum == 0 {
+            result.push(std::mem::take(&mut group));
+        }
+    }
+
+    result
+}
+
+/// Largest debtor pays largest creditor until everyone is even
+fn settle_group(mut group: Vec<(usize, i128)>) -> Result<Vec<Transfer>, Error> {
+    group.sort_by(|a, b| a.1.cmp(&b.1).then(a.0.cmp(&b.0)));
+
+    let mut transfers = Vec::new();
+    let (mut debtor, mut creditor) = (0, group.len());
+    while debtor < creditor {
+        if group[debtor].1 >= 0 { break; }
+        let (to, credit) = group[creditor - 1];
+        if credit <= 0 { break; }
+
+        let (from, debt) = group[debtor];
+        let amount = credit.min(-debt);
+        transfers.push(Transfer { from, to, amount: Money::from_kopeks(amount)? });
+
+        group[debtor].1 += amount;
+        group[creditor - 1].1 -= amount;
+        if group[debtor].1 == 0 { debtor += 1; }
+        if group[creditor - 1].1 == 0 { creditor -= 1; }
+    }
+
+    Ok(transfers)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rubles(kopeks: i64) -> Money {
+        Money(Decimal::new(kopeks, 2))
+    }
+
+    /// What everyone ends with after the transfers, must be even
+    fn after(shares: &[Money], paid: &[Money], transfers: &[Transfer]) -> Vec<Decimal> {
+        let mut balances: Vec<Decimal> = paid.iter().zip(shares).map(|(paid, share)| paid.0 - share.0).collect();
+        for transfer in transfers {
+            balances[transfer.from] += transfer.amount.0;
+            balances[transfer.to] -= transfer.amount.0;
+        }
+        balances
+    }
+
+    #[test]
+    fn equal_shares_add_up() {
+        assert_eq!(equal(rubles(10000), 3).unwrap(), vec![rubles(3334), rubles(3333), rubles(3333)]);
+        assert_eq!(equal(rubles(200), 3).unwrap(), vec![rubles(67), rubles(67), rubles(66)]);
+        assert_eq!(equal(rubles(100), 0), Err(Error::NoParticipants));
+    }
+
+    #[test]
+    fn largest_remainders_get_the_kopeks() {
+        let weights = [Decimal::new(5, 1), Decimal::new(125, 2), Decimal::ONE];
+        let shares = by_weight(rubles(1000), &weights).unwrap();
+        // 1.8181..., 4.5454..., 3.6363...: the two kopeks left go to the first and the third
+        assert_eq!(shares, vec![rubles(182), rubles(454), rubles(364)]);
+        assert_eq!(by_weight(rubles(1000), &[Decimal::ZERO, Decimal::ZERO]), Err(Error::DivisionByZero));
+        assert_eq!(by_weight(rubles(1000), &[Decimal::NEGATIVE_ONE]), Err(Error::NegativeQuantity));
+    }
+
+    #[test]
+    fn items_are_paid_by_who_had_them() {
+        let items = [
+            Item { price: rubles(30000), consumers: vec![0, 1, 2] },
+            Item { price: rubles(15000), consumers: vec![1] },
+        ];
+        assert_eq!(by_items(&items, 3).unwrap(), vec![rubles(10000), rubles(25000), rubles(10000)]);
+        assert_eq!(by_items(&[Item { price: rubles(100), consumers: vec![3] }], 3), Err(Error::NoParticipants));
+    }
+
+    #[test]
+    fn tip_is_shared_in_proportion() {
+        assert_eq!(tip(rubles(123450), Decimal::TEN, Rounding::HalfUp).unwrap(), rubles(12345));
+        let shares = with_tip(&[rubles(10000), rubles(20000)], Decimal::TEN, Rounding::HalfUp).unwrap();
+        assert_eq!(shares, vec![rubles(11000), rubles(22000)]);
+        assert_eq!(with_tip(&[rubles(100), rubles(100)], Decimal::ZERO, Rounding::HalfUp).unwrap(), vec![rubles(100), rubles(100)]);
+        assert_eq!(tip(rubles(100), Decimal::NEGATIVE_ONE, Rounding::HalfUp), Err(Error::NegativeAmount));
+    }
+
+    #[test]
+    fn settle_finds_fewest_transfers() {
+        // Balances +5, +4, -3, -2, -4: greedy makes four transfers,
+        // {+4, -4} and {+5, -3, -2} settle in three
+        let shares = [rubles(0), rubles(0), rubles(300), rubles(200), rubles(400)];
+        let paid = [rubles(500), rubles(400), rubles(0), rubles(0), rubles(0)];
+        let transfers = settle(&shares, &paid).unwrap();
+        assert_eq!(transfers.len(), 3);
+        assert!(after(&shares, &paid, &transfers).iter().all(Decimal::is_zero));
+        assert!(transfers.contains(&Transfer { from: 4, to: 1, amount: rubles(400) }));
+    }
+
+    #[test]
+    fn large_groups_still_settle() {
+        let count = EXACT_LIMIT + 2;
+        let shares = equal(rubles(100000), count).unwrap();
+        let mut paid = vec![rubles(0); count];
+        paid[0] = rubles(100000);
+        let transfers = settle(&shares, &paid).unwrap();
+        assert_eq!(transfers.len(), count - 1);
+        assert!(after(&shares, &paid, &transfers).iter().all(Decimal::is_zero));
+    }
+
+    #[test]
+    fn payments_must_match_the_bill() {
+        assert_eq!(settle(&[rubles(100)], &[rubles(50)]), Err(Error::UnbalancedPayments));
+        assert_eq!(settle(&[rubles(100)], &[]), Err(Error::UnbalancedPayments));
+    }
+}
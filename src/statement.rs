@@ -0,0 +1,218 @@
+use std::path::Path;
+
+use chrono::NaiveDate;
+use encoding_rs::{Encoding, IBM866, WINDOWS_1251};
+
+use crate::{camt, clientbank, ledger::Transaction, money::Money, ofx, qif};
+
+#[derive(Debug,
+    PartialEq,
+    Clone)]
+pub enum Error {
+    /// The file can't be read
+    File(String),
+    /// None of 1CClientBankExchange, OFX, QIF or camt.053
+    UnknownFormat,
+    Xml(String),
+    /// Name of the field as it is in the file
+    MissingField(&'static str),
+    InvalidField(&'static str),
+    /// A section runs into the next one or into the end of the file
+    UnclosedSection,
+    /// Neither side of a payment is the statement's account
+    UnknownDirection
+}
+
+/// For logs only, what the user reads comes from `Localizer::error`
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl std::error::Error for Error { }
+
+/// Something wrong with one section, the rest of the statement is still read
+#[derive(Debug,
+    PartialEq,
+    Clone)]
+pub struct Problem {
+    /// Line the section starts on, counted from one
+    pub line: usize,
+    /// "Платежное поручение", "STMTTRN", "Ntry" and so on
+    pub section: String,
+    pub error: Error
+}
+
+/// Bank statement in any of the supported formats
+#[derive(Debug,
+    PartialEq,
+    Clone,
+    Default)]
+pub struct Statement {
+    /// Accounts the statement is for
+    pub accounts: Vec<String>,
+    pub from: Option<NaiveDate>,
+    /// Inclusive
+    pub to: Option<NaiveDate>,
+    /// Only when the statement is for one account
+    pub opening: Option<Money>,
+    pub closing: Option<Money>,
+    /// Income is positive, payments are negative
+    pub transactions: Vec<Transaction>,
+    pub problems: Vec<Problem>
+}
+
+impl Statement {
+    pub(crate) fn add_account(&mut self, account: &str) {
+        if !account.is_empty() && !self.accounts.iter().any(|own| own == account) {
+            self.accounts.push(account.to_string());
+        }
+    }
+
+    /// Several accounts have no common balance
+    pub(crate) fn finish(mut self) -> Self {
+        if self.accounts.len() > 1 {
+            (self.opening, self.closing) = (None, None);
+        }
+        self.problems.sort_by_key(|problem| problem.line);
+        self
+    }
+}
+
+/// Text of the file: UTF-8 when it is valid UTF-8, then the encoding the file
+/// declares, windows-1251 when it declares nothing
+pub fn decode(bytes: &[u8]) -> String {
+    if let Ok(text) = std::str::from_utf8(bytes) {
+        return text.trim_start_matches('\u{feff}').to_string();
+    }
+
+    let (dos, _, _) = IBM866.decode(bytes);
+    if dos.lines().any(|line| line.trim() == "Кодировка=DOS") {
+        return dos.into_owned();
+    }
+    declared(bytes).unwrap_or(WINDOWS_1251).decode(bytes).0.into_owned()
+}
+
+/// `CHARSET:1252` of an OFX header or `encoding="..."` of an XML declaration
+fn declared(bytes: &[u8]) -> Option<&'static Encoding> {
+    let head = String::from_utf8_lossy(&bytes[..bytes.len().min(512)]).into_owned();
+    if let Some((_, rest)) = head.split_once("CHARSET:") {
+        let charset: String = rest.chars().take_while(|c| c.is_ascii_alphanumeric() || *c == '-').collect();
+        return match charset.bytes().all(|byte| byte.is_ascii_digit()) {
+            true => Encoding::for_label(format!("windows-{}", charset).as_bytes()),
+            false => Encoding::for_label(charset.as_bytes()),
+        };
+    }
+    let (_, rest) = head.split_once("encoding=")?;
+    let label = rest.trim_start_matches(['"', '\'']).split(['"', '\'']).next()?;
+    Encoding::for_label(label.as_bytes())
+}
+
+/// Amount as banks write it: `-1234.56`, `+10`, `1,234.56`, `1 234,56`, `1.234.567`.
+/// With both marks the last one is decimal; a mark that repeats groups thousands;
+/// a single mark is decimal, except a lone comma before exactly three digits: "12,345"
+/// is twelve thousand in one bank and twelve in another, so it is refused.
+/// The text goes to `Money` as it is, never through a float
+pub(crate) fn amount(text: &str, field: &'static str) -> Result<Money, Error> {
+    let invalid = Error::InvalidField(field);
+    let mut digits: String = text
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '\'')
+        .collect();
+    if digits.starts_with('+') {
+        digits.remove(0);
+    }
+
+    let decimal = match (digits.rfind('.'), digits.rfind(',')) {
+        (Some(dot), Some(comma)) => Some(dot.max(comma)),
+        (None, Some(comma)) if digits.matches(',').count() == 1 => match digits.len() - comma {
+            4 => return Err(invalid),
+            _ => Some(comma),
+        },
+        (Some(dot), None) if digits.matches('.').count() == 1 => Some(dot),
+        _ => None,
+    };
+
+    // Thousands come in threes: "1,234,567", never "12,34,5"
+    let whole = &digits[..decimal.unwrap_or(digits.len())];
+    let groups: Vec<&str> = whole.trim_start_matches('-').split(['.', ',']).collect();
+    if let [first, rest @ ..] = &groups[..] {
+        if !rest.is_empty() && (first.is_empty() || first.len() > 3 || rest.iter().any(|group| group.len() != 3)) {
+            return Err(invalid);
+        }
+    }
+
+    let normalized: String = digits
+        .char_indices()
+        .filter_map(|(index, c)| match c {
+            '.' | ',' if Some(index) == decimal => Some('.'),
+            '.' | ',' => None,
+            c => Some(c),
+        })
+        .collect();
+
+    Money::try_from(normalized.as_str()).map_err(|_| invalid)
+}
+
+/// Statement in whichever format the text is in. `currency` is for formats
+/// that don't name one: QIF and the ruble 1C statements
+pub fn parse(text: &str, currency: &str) -> Result<Statement, Error> {
+    let start = text.trim_start();
+    if start.starts_with("1CClientBankExchange") {
+        clientbank::parse(text, currency)
+    } else if start.starts_with('!') {
+        qif::parse(text, currency)
+    } else if text.contains("BkToCstmrStmt") {
+        camt::parse(text)
+    } else if text.contains("<OFX>") || start.starts_with("OFXHEADER") {
+        ofx::parse(text)
+    } else {
+        Err(Error::UnknownFormat)
+    }
+}
+
+pub fn read(path: impl AsRef<Path>, currency: &str) -> Result<Statement, Error> {
+    let bytes = std::fs::read(path).map_err(|error| Error::File(error.to_string()))?;
+    parse(&decode(&bytes), currency)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal::Decimal;
+
+    use super::*;
+
+    fn amount_of(text: &str) -> Result<Money, Error> {
+        amount(text, "Amt")
+    }
+
+    #[test]
+    fn amounts_as_banks_write_them() {
+        let cents = |value: i64| Ok(Money(Decimal::new(value, 2)));
+        assert_eq!(amount_of("-1234.56"), cents(-123456));
+        assert_eq!(amount_of("+10"), cents(1000));
+        assert_eq!(amount_of("1,234.56"), cents(123456));
+        assert_eq!(amount_of("1.234,56"), cents(123456));
+        assert_eq!(amount_of("1 234,56"), cents(123456));
+        assert_eq!(amount_of("1'234.5"), cents(123450));
+        assert_eq!(amount_of("1,234,567"), cents(123456700));
+        assert_eq!(amount_of("1.234.567"), cents(123456700));
+        assert_eq!(amount_of("12,5"), cents(1250));
+        assert_eq!(amount_of("100.000"), cents(10000));
+    }
+
+    #[test]
+    fn doubtful_amounts_are_refused() {
+        for text in ["12,345", "12,34,5", "1234,567,890", ",5,000", "1.2.3", "12.345", "сто", ""] {
+            assert_eq!(amount_of(text), Err(Error::InvalidField("Amt")), "{}", text);
+        }
+    }
+
+    #[test]
+    fn format_is_recognized() {
+        assert_eq!(parse("hello", "RUB"), Err(Error::UnknownFormat));
+        assert!(parse("!Type:Bank\n^\n", "RUB").is_ok());
+    }
+}
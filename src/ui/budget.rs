@@ -0,0 +1,443 @@
+use eframe::egui::{Button, Color32, ComboBox, Grid, Ui};
+use money_counter::{
+    budget::{self, Budget, CarryOver, Category, Expense},
+    field::Field,
+    money::{self as money, Money, Rounding, Sign},
+    i18n::Localizer,
+    recurring::{self, End, Frequency, Recurring},
+    unit::Unit
+};
+use chrono::{Days, Local, Months, NaiveDate};
+use rust_decimal::Decimal;
+
+use super::{field_edit, field_error, leftover_line};
+
+#[derive(Clone, PartialEq)]
+pub struct CategoryInput {
+    pub name: String,
+    monthly: Field<Money>,
+    carry_over: CarryOver
+}
+
+impl CategoryInput {
+    fn new(name: &str) -> Self {
+        CategoryInput {
+            name: name.to_string(),
+            monthly: Field::new(Money(Decimal::ZERO)),
+            carry_over: CarryOver::default()
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum EndKind {
+    Never,
+    After,
+    Until
+}
+
+#[derive(Clone, PartialEq)]
+pub struct RecurringInput {
+    name: String,
+    category: String,
+    amount: Field<Money>,
+    start: Field<NaiveDate>,
+    frequency: Frequency,
+    interval: Field<Unit>,
+    end: EndKind,
+    count: Field<Unit>,
+    until: Field<NaiveDate>
+}
+
+impl RecurringInput {
+    fn new(category: String) -> Self {
+        let today = Local::now().date_naive();
+        RecurringInput {
+            name: String::new(),
+            category,
+            amount: Field::new(Money(Decimal::ZERO)),
+            start: Field::new(today),
+            frequency: Frequency::default(),
+            interval: Field::new(Unit(1)),
+            end: EndKind::Never,
+            count: Field::new(Unit(12)),
+            until: Field::new(today)
+        }
+    }
+
+    fn recurring(&self) -> Result<Recurring, money::Error> {
+        let number = |field: &Field<Unit>| u32::try_from(field.value().0).map_err(|_| money::Error::OutOfRange);
+
+        Ok(Recurring {
+            name: self.name.trim().to_string(),
+            category: self.category.clone(),
+            amount: *self.amount.value(),
+            start: *self.start.value(),
+            frequency: self.frequency,
+            interval: number(&self.interval)?,
+            end: match self.end {
+                EndKind::Never => End::Never,
+                EndKind::After => End::After(number(&self.count)?),
+                EndKind::Until => End::Until(*self.until.value()),
+            }
+        })
+    }
+
+    pub fn ui(&mut self, ui: &mut Ui, id: usize, names: &[String], rounding: Rounding, tr: &Localizer) {
+        ui.horizontal(
+            |ui| {
+                ui.text_edit_singleline(&mut self.name);
+                ui.label(tr.tr("budget-category"));
+                BudgetInput::category_combo(ui, &format!("recurring_category_{}", id), &mut self.category, names, None);
+                ui.label(tr.tr("budget-amount"));
+                field_edit(ui, &mut self.amount);
+            });
+        field_error(ui, &mut self.amount, rounding, tr);
+        ui.horizontal(
+            |ui| {
+                ui.label(tr.tr("recurring-start"));
+                field_edit(ui, &mut self.start);
+                ui.label(tr.tr("recurring-every"));
+                field_edit(ui, &mut self.interval);
+                ComboBox::from_id_source(format!("recurring_frequency_{}", id))
+                    .selected_text(tr.frequency(self.frequency))
+                    .show_ui(ui, |ui| {
+                        for frequency in Frequency::ALL {
+                            ui.selectable_value(&mut self.frequency, frequency, tr.frequency(frequency));
+                        }
+                    });
+            });
+        field_error(ui, &mut self.start, rounding, tr);
+        field_error(ui, &mut self.interval, rounding, tr);
+        ui.horizontal(
+            |ui| {
+                ui.radio_value(&mut self.end, EndKind::Never, tr.tr("recurring-never"));
+                ui.radio_value(&mut self.end, EndKind::After, tr.tr("recurring-after"));
+                if self.end == EndKind::After {
+                    field_edit(ui, &mut self.count);
+                }
+                ui.radio_value(&mut self.end, EndKind::Until, tr.tr("recurring-until"));
+                if self.end == EndKind::Until {
+                    field_edit(ui, &mut self.until);
+                }
+            });
+        match self.end {
+            EndKind::After => field_error(ui, &mut self.count, rounding, tr),
+            EndKind::Until => field_error(ui, &mut self.until, rounding, tr),
+            EndKind::Never => (),
+        }
+    }
+}
+
+/// Budget tracker mode
+#[derive(Clone, PartialEq)]
+pub struct BudgetInput {
+    start: NaiveDate,
+    /// Month on screen
+    pub month: NaiveDate,
+    pub categories: Vec<CategoryInput>,
+    pub expenses: Vec<Expense>,
+    new_date: Field<NaiveDate>,
+    new_category: String,
+    new_amount: Field<Money>,
+    new_note: String,
+    recurring: Vec<RecurringInput>
+}
+
+impl Default for BudgetInput {
+    fn default() -> Self {
+        let today = Local::now().date_naive();
+        BudgetInput {
+            start: budget::month_of(today),
+            month: budget::month_of(today),
+            categories: ["продукты", "канцелярия", "транспорт"].into_iter().map(CategoryInput::new).collect(),
+            expenses: Vec::new(),
+            new_date: Field::new(today),
+            new_category: "продукты".to_string(),
+            new_amount: Field::new(Money(Decimal::ZERO)),
+            new_note: String::new(),
+            recurring: Vec::new()
+        }
+    }
+}
+
+impl BudgetInput {
+    /// How many days ahead the upcoming payments list looks
+    const UPCOMING_DAYS: u64 = 30;
+
+    fn rules(&self) -> Result<Vec<Recurring>, money::Error> {
+        self.recurring.iter().map(RecurringInput::recurring).collect()
+    }
+
+    /// Recurring payments of `category` still to come in the month `month` falls in.
+    /// Payments due today and earlier are expected to be recorded as expenses already
+    fn planned(&self, category: &str, month: NaiveDate) -> Result<Money, money::Error> {
+        let tomorrow = Local::now().date_naive().succ_opt().ok_or(money::Error::OutOfRange)?;
+        let from = budget::month_of(month).max(tomorrow);
+
+        recurring::due(&self.rules()?, category, from, budget::month_end(month))
+    }
+
+    pub fn budget(&self) -> Budget {
+        Budget {
+            start: self.start,
+            categories: self.categories
+                .iter()
+                .map(|category| Category {
+                    name: category.name.trim().to_string(),
+                    monthly: *category.monthly.value(),
+                    carry_over: category.carry_over
+                })
+                .collect(),
+            expenses: self.expenses.clone()
+        }
+    }
+
+    /// What is left in `category` this month after the recurring payments still to come,
+    /// `None` if there is no such category
+    pub fn remaining(&self, category: &str) -> Option<Result<Money, money::Error>> {
+        let budget = self.budget();
+        let category = budget.category(category)?;
+        let today = Local::now().date_naive();
+
+        Some(budget.status_of(category, today)
+            .and_then(|status| status.remaining()?.checked_sub(self.planned(&category.name, today)?)))
+    }
+
+    /// Payments of the next `UPCOMING_DAYS`, each can be recorded as paid
+    fn upcoming_ui(&mut self, ui: &mut Ui, tr: &Localizer) {
+        let today = Local::now().date_naive();
+        let to = today.checked_add_days(Days::new(BudgetInput::UPCOMING_DAYS)).unwrap_or(today);
+        let rules = match self.rules() {
+            Ok(rules) => rules,
+            Err(error) => {
+                ui.colored_label(Color32::RED, tr.error(&error));
+                return;
+            }
+        };
+        let occurrences = match recurring::upcoming(&rules, today, to) {
+            Ok(occurrences) if occurrences.is_empty() => return,
+            Ok(occurrences) => occurrences,
+            Err(error) => {
+                ui.colored_label(Color32::RED, tr.error(&error));
+                return;
+            }
+        };
+
+        ui.strong(tr.tr_args("recurring-upcoming", &[("days", BudgetInput::UPCOMING_DAYS.to_string())]));
+        let mut paid = None;
+        Grid::new("upcoming")
+            .striped(true)
+            .show(ui, |ui| {
+                for occurrence in occurrences.iter() {
+                    let rule = &rules[occurrence.rule];
+                    ui.label(occurrence.date.format("%d.%m.%Y").to_string());
+                    ui.label(&rule.name);
+                    ui.label(&rule.category);
+                    ui.label(occurrence.amount.accounting());
+                    if ui.button(tr.tr("recurring-paid")).clicked() {
+                        paid = Some(Expense {
+                            date: occurrence.date,
+                            category: rule.category.clone(),
+                            amount: occurrence.amount,
+                            note: rule.name.clone()
+                        });
+                    }
+                    ui.end_row();
+                }
+            });
+        if let Some(expense) = paid {
+            self.expenses.push(expense);
+        }
+    }
+
+    pub fn category_combo(ui: &mut Ui, id: &str, selected: &mut String, names: &[String], none: Option<String>) {
+        ComboBox::from_id_source(id)
+            .selected_text(match (selected.as_str(), &none) {
+                ("", Some(none)) => none.clone(),
+                (name, _) => name.to_string()
+            })
+            .show_ui(ui, |ui| {
+                if let Some(none) = none {
+                    ui.selectable_value(selected, String::new(), none);
+                }
+                for name in names {
+                    ui.selectable_value(selected, name.clone(), name);
+                }
+            });
+    }
+
+    pub fn ui(&mut self, ui: &mut Ui, rounding: Rounding, words: impl Fn(Money) -> Result<String, money::Error>, tr: &Localizer) {
+        ui.horizontal(
+            |ui| {
+                if ui.button("◀").clicked() {
+                    self.month = self.month.checked_sub_months(Months::new(1)).unwrap_or(self.month);
+                }
+                ui.strong(self.month.format("%m.%Y").to_string());
+                if ui.button("▶").clicked() {
+                    self.month = self.month.checked_add_months(Months::new(1)).unwrap_or(self.month);
+                }
+            });
+
+        ui.collapsing(tr.tr("budget-categories"), |ui| {
+            let mut removed = None;
+            for (i, category) in self.categories.iter_mut().enumerate() {
+                ui.horizontal(
+                    |ui| {
+                        ui.text_edit_singleline(&mut category.name);
+                        ui.label(tr.tr("budget-monthly"));
+                        field_edit(ui, &mut category.monthly);
+                        ComboBox::from_id_source(format!("carry_over_{}", i))
+                            .selected_text(tr.carry_over(category.carry_over))
+                            .show_ui(ui, |ui| {
+                                for carry_over in CarryOver::ALL {
+                                    ui.selectable_value(&mut category.carry_over, carry_over, tr.carry_over(carry_over));
+                                }
+                            });
+                        if ui.button(tr.tr("remove")).clicked() {
+                            removed = Some(i);
+                        }
+                    });
+                field_error(ui, &mut category.monthly, rounding, tr);
+            }
+            if let Some(i) = removed {
+                self.categories.remove(i);
+            }
+            if ui.button(tr.tr("budget-add-category")).clicked() {
+                self.categories.push(CategoryInput::new(""));
+            }
+        });
+
+        let names: Vec<String> = self.categories.iter().map(|category| category.name.trim().to_string()).collect();
+        ui.collapsing(tr.tr("recurring"), |ui| {
+            let mut removed = None;
+            for (i, recurring) in self.recurring.iter_mut().enumerate() {
+                ui.separator();
+                recurring.ui(ui, i, &names, rounding, tr);
+                if ui.button(tr.tr("remove")).clicked() {
+                    removed = Some(i);
+                }
+            }
+            if let Some(i) = removed {
+                self.recurring.remove(i);
+            }
+            ui.separator();
+            if ui.button(tr.tr("recurring-add")).clicked() {
+                self.recurring.push(RecurringInput::new(names.first().cloned().unwrap_or_default()));
+            }
+        });
+        self.upcoming_ui(ui, tr);
+        ui.separator();
+        ui.strong(tr.tr("budget-new-expense"));
+        ui.horizontal(
+            |ui| {
+                ui.label(tr.tr("budget-date"));
+                field_edit(ui, &mut self.new_date);
+                ui.label(tr.tr("budget-category"));
+                BudgetInput::category_combo(ui, "new_expense_category", &mut self.new_category, &names, None);
+                ui.label(tr.tr("budget-amount"));
+                field_edit(ui, &mut self.new_amount);
+            });
+        field_error(ui, &mut self.new_date, rounding, tr);
+        field_error(ui, &mut self.new_amount, rounding, tr);
+        ui.horizontal(
+            |ui| {
+                ui.label(tr.tr("budget-note"));
+                ui.text_edit_singleline(&mut self.new_note);
+                let valid = self.new_date.error().is_none() && self.new_amount.error().is_none() && !self.new_category.is_empty();
+                if ui.add_enabled(valid, Button::new(tr.tr("add"))).clicked() {
+                    self.expenses.push(Expense {
+                        date: *self.new_date.value(),
+                        category: self.new_category.clone(),
+                        amount: *self.new_amount.value(),
+                        note: std::mem::take(&mut self.new_note)
+                    });
+                    self.new_amount.set(Money(Decimal::ZERO));
+                }
+            });
+
+        ui.separator();
+        let budget = self.budget();
+        match budget.status(self.month) {
+            Ok(statuses) => {
+                let projected = |status: &budget::Status| -> Result<(Money, Money, Money), money::Error> {
+                    let remaining = status.remaining()?;
+                    let planned = self.planned(&status.category, self.month)?;
+                    Ok((remaining, planned, remaining.checked_sub(planned)?))
+                };
+                let amount_label = |ui: &mut Ui, amount: Money| match amount.sign() {
+                    Sign::Negative => ui.colored_label(Color32::RED, amount.accounting()),
+                    Sign::Positive => ui.label(amount.accounting()),
+                };
+
+                Grid::new("budget_status")
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for id in ["budget-category-column", "budget-monthly-column", "budget-carried", "budget-spent", "budget-remaining", "budget-planned", "budget-projected"] {
+                            ui.label(tr.tr(id));
+                        }
+                        ui.end_row();
+
+                        for status in statuses.iter() {
+                            ui.label(&status.category);
+                            for amount in [status.monthly, status.carried, status.spent] {
+                                ui.label(amount.accounting());
+                            }
+                            match projected(status) {
+                                Ok((remaining, planned, leftover)) => {
+                                    amount_label(ui, remaining);
+                                    ui.label(planned.accounting());
+                                    amount_label(ui, leftover);
+                                },
+                                Err(error) => {
+                                    ui.colored_label(Color32::RED, tr.error(&error));
+                                }
+                            };
+                            ui.end_row();
+                        }
+                    });
+                ui.label(tr.tr("budget-month-end"));
+                for status in statuses.iter() {
+                    match projected(status).and_then(|(_, _, leftover)| leftover_line(leftover, &words, tr)) {
+                        Ok(line) => ui.label(tr.tr_args("budget-status", &[("category", status.category.clone()), ("status", line)])),
+                        Err(error) => ui.colored_label(Color32::RED, tr.error(&error)),
+                    };
+                }
+            },
+            Err(error) => {
+                ui.colored_label(Color32::RED, tr.error(&error));
+            }
+        }
+        match budget.uncategorized(self.month) {
+            Ok(spent) if spent.0.is_zero() => (),
+            Ok(spent) => {
+                ui.label(tr.tr_args("budget-uncategorized", &[("amount", spent.accounting())]));
+            },
+            Err(error) => {
+                ui.colored_label(Color32::RED, tr.error(&error));
+            }
+        }
+
+        ui.separator();
+        ui.strong(tr.tr("budget-expenses"));
+        let month = budget::month_of(self.month);
+        let mut removed = None;
+        Grid::new("expenses")
+            .striped(true)
+            .show(ui, |ui| {
+                for (i, expense) in self.expenses.iter().enumerate().filter(|(_, expense)| budget::month_of(expense.date) == month) {
+                    ui.label(expense.date.format("%d.%m.%Y").to_string());
+                    ui.label(&expense.category);
+                    ui.label(expense.amount.accounting());
+                    ui.label(&expense.note);
+                    if ui.button(tr.tr("remove")).clicked() {
+                        removed = Some(i);
+                    }
+                    ui.end_row();
+                }
+            });
+        if let Some(i) = removed {
+            self.expenses.remove(i);
+        }
+    }
+}
@@ -0,0 +1,163 @@
+use eframe::egui::{pos2, vec2, Align2, Color32, FontId, Grid, Sense, Shape, Stroke, Ui};
+use money_counter::{
+    budget,
+    chart::{self, Bucket},
+    field::Field,
+    money::{Money, Rounding},
+    i18n::Localizer
+};
+use chrono::{Local, Months, NaiveDate};
+use rust_decimal::Decimal;
+
+use super::{field_edit, field_error, budget::BudgetInput};
+
+/// Lines over the same days, amounts are converted to floats only here.
+/// `top` and `bottom` label the extremes, `hints` are shown for the day under the pointer
+pub fn line_chart(ui: &mut Ui, lines: &[(Vec<Money>, Color32)], top: Money, bottom: Money, hints: &[String]) {
+    let (response, painter) = ui.allocate_painter(vec2(ui.available_width(), 200.0), Sense::hover());
+    let rect = response.rect.shrink(4.0);
+    let text_color = ui.visuals().text_color();
+    painter.rect_stroke(rect, 0.0, Stroke::new(1.0, Color32::GRAY));
+
+    let (max, min) = (top.to_f64(), bottom.to_f64());
+    let range = if max > min { max - min } else { 1.0 };
+    let count = lines.iter().map(|(values, _)| values.len()).max().unwrap_or_default();
+    let x = |i: usize| rect.left() + rect.width() * i as f32 / count.saturating_sub(1).max(1) as f32;
+    let y = |value: f64| rect.bottom() - rect.height() * ((value - min) / range) as f32;
+
+    if min < 0.0 {
+        painter.hline(rect.x_range(), y(0.0), Stroke::new(1.0, Color32::GRAY));
+    }
+    for (values, color) in lines {
+        let points = values.iter().enumerate().map(|(i, value)| pos2(x(i), y(value.to_f64()))).collect();
+        painter.add(Shape::line(points, Stroke::new(2.0, *color)));
+    }
+    painter.text(rect.left_top(), Align2::LEFT_TOP, top.accounting(), FontId::default(), text_color);
+    painter.text(rect.left_bottom(), Align2::LEFT_BOTTOM, bottom.accounting(), FontId::default(), text_color);
+
+    if let Some(pointer) = response.hover_pos() {
+        let step = rect.width() / count.saturating_sub(1).max(1) as f32;
+        let i = ((pointer.x - rect.left()) / step).round().max(0.0) as usize;
+        if let Some(hint) = hints.get(i) {
+            painter.vline(x(i), rect.y_range(), Stroke::new(1.0, Color32::GRAY));
+            response.on_hover_text_at_pointer(hint);
+        }
+    }
+}
+
+/// One bar per row, lengths relative to the largest amount
+pub fn bar_chart(ui: &mut Ui, bars: &[(String, Money)]) {
+    let largest = bars.iter().map(|(_, amount)| amount.to_f64()).fold(0.0, f64::max);
+    Grid::new("bar_chart")
+        .show(ui, |ui| {
+            for (label, amount) in bars {
+                ui.label(label);
+                let (response, painter) = ui.allocate_painter(vec2(300.0, 16.0), Sense::hover());
+                if largest > 0.0 {
+                    let mut bar = response.rect;
+                    bar.set_width(bar.width() * (amount.to_f64() / largest).max(0.0) as f32);
+                    painter.rect_filled(bar, 2.0, Color32::from_rgb(90, 140, 200));
+                }
+                ui.label(amount.accounting());
+                ui.end_row();
+            }
+        });
+}
+
+/// Charts mode, pictures of the budget's expenses
+#[derive(Clone, PartialEq)]
+pub struct ChartsInput {
+    bucket: Bucket,
+    pub from: Field<NaiveDate>,
+    pub to: Field<NaiveDate>
+}
+
+impl Default for ChartsInput {
+    fn default() -> Self {
+        let today = Local::now().date_naive();
+        ChartsInput {
+            bucket: Bucket::default(),
+            from: Field::new(budget::month_of(today).checked_sub_months(Months::new(2)).unwrap_or(today)),
+            to: Field::new(budget::month_end(today))
+        }
+    }
+}
+
+impl ChartsInput {
+    pub fn ui(&mut self, ui: &mut Ui, budget: &BudgetInput, rounding: Rounding, tr: &Localizer) {
+        ui.horizontal(
+            |ui| {
+                ui.label(tr.tr("chart-from"));
+                field_edit(ui, &mut self.from);
+                ui.label(tr.tr("chart-to"));
+                field_edit(ui, &mut self.to);
+                for bucket in Bucket::ALL {
+                    ui.radio_value(&mut self.bucket, bucket, tr.bucket(bucket));
+                }
+            });
+        field_error(ui, &mut self.from, rounding, tr);
+        field_error(ui, &mut self.to, rounding, tr);
+
+        let (from, to) = (*self.from.value(), *self.to.value());
+        let spending = chart::spending(&budget.expenses, self.bucket, from, to);
+        let categories = chart::by_category(&budget.expenses, from, to);
+        let (points, categories) = match spending.and_then(|points| Ok((points, categories?))) {
+            Ok(charts) => charts,
+            Err(error) => {
+                ui.colored_label(Color32::RED, tr.error(&error));
+                return;
+            }
+        };
+
+        ui.separator();
+        ui.strong(tr.tr("chart-spending"));
+        if categories.is_empty() {
+            ui.label(tr.tr("chart-empty"));
+        }
+        else {
+            let amounts: Vec<Money> = points.iter().map(|point| point.amount).collect();
+            let hints: Vec<String> = points
+                .iter()
+                .map(|point| format!("{}: {}", point.start.format("%d.%m.%Y"), point.amount.accounting()))
+                .collect();
+            let top = amounts.iter().copied().max().unwrap_or(Money(Decimal::ZERO));
+            line_chart(ui, &[(amounts, Color32::from_rgb(200, 90, 60))], top, Money(Decimal::ZERO), &hints);
+
+            ui.separator();
+            ui.strong(tr.tr("chart-categories"));
+            bar_chart(ui, &categories);
+        }
+
+        ui.separator();
+        ui.strong(tr.tr_args("chart-burn-down", &[("month", budget.month.format("%m.%Y").to_string())]));
+        let days = match chart::burn_down(&budget.budget(), budget.month, rounding) {
+            Ok(days) => days,
+            Err(error) => {
+                ui.colored_label(Color32::RED, tr.error(&error));
+                return;
+            }
+        };
+        // The future is not spent yet, the line stops today
+        let today = Local::now().date_naive();
+        let remaining: Vec<Money> = days.iter().take_while(|day| day.date <= today).map(|day| day.remaining).collect();
+        let even: Vec<Money> = days.iter().map(|day| day.even).collect();
+        let hints: Vec<String> = days
+            .iter()
+            .map(|day| format!("{}\n{}: {}\n{}: {}",
+                day.date.format("%d.%m.%Y"),
+                tr.tr("chart-remaining"),
+                day.remaining.accounting(),
+                tr.tr("chart-even"),
+                day.even.accounting()))
+            .collect();
+        let top = remaining.iter().chain(even.iter()).copied().max().unwrap_or(Money(Decimal::ZERO));
+        let bottom = remaining.iter().copied().min().unwrap_or(Money(Decimal::ZERO)).min(Money(Decimal::ZERO));
+        let (remaining_color, even_color) = (Color32::from_rgb(60, 160, 90), Color32::GRAY);
+        ui.horizontal(
+            |ui| {
+                ui.colored_label(remaining_color, tr.tr("chart-remaining"));
+                ui.colored_label(even_color, tr.tr("chart-even"));
+            });
+        line_chart(ui, &[(even, even_color), (remaining, remaining_color)], top, bottom, &hints);
+    }
+}
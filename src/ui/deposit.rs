@@ -0,0 +1,153 @@
+use eframe::egui::{Color32, ComboBox, Grid, Ui};
+use money_counter::{
+    deposit::{Capitalization, Deposit},
+    field::Field,
+    money::{self as money, Money, Rounding},
+    i18n::Localizer,
+    unit::Unit
+};
+use chrono::{Local, Months};
+use rust_decimal::Decimal;
+
+use super::{field_edit, field_error};
+
+/// Savings projection mode
+#[derive(Clone, PartialEq)]
+pub struct DepositInput {
+    pub initial: Field<Money>,
+    top_up: Field<Money>,
+    pub rate: Field<Decimal>,
+    capitalization: Capitalization,
+    pub months: Field<Unit>,
+    tax: Field<Decimal>
+}
+
+impl Default for DepositInput {
+    fn default() -> Self {
+        DepositInput {
+            initial: Field::new(Money(Decimal::ZERO)),
+            top_up: Field::new(Money(Decimal::ZERO)),
+            rate: Field::new(Decimal::ZERO),
+            capitalization: Capitalization::default(),
+            months: Field::new(Unit(12)),
+            tax: Field::new(Decimal::ZERO)
+        }
+    }
+}
+
+impl DepositInput {
+    /// Starts today, the date isn't part of the input so that history stays stable
+    fn deposit(&self) -> Result<Deposit, money::Error> {
+        Ok(Deposit {
+            start: Local::now().date_naive(),
+            initial: *self.initial.value(),
+            top_up: *self.top_up.value(),
+            annual_rate: *self.rate.value(),
+            capitalization: self.capitalization,
+            months: u32::try_from(self.months.value().0).map_err(|_| money::Error::OutOfRange)?,
+            tax_rate: *self.tax.value()
+        })
+    }
+
+    /// `target` is the budget of the purchase mode
+    pub fn ui(&mut self, ui: &mut Ui, target: &mut Field<Money>, rounding: Rounding, words: impl Fn(Money) -> Result<String, money::Error>, tr: &Localizer) {
+        for (label, field) in [("deposit-initial", &mut self.initial), ("deposit-top-up", &mut self.top_up)] {
+            ui.horizontal(
+                |ui| {
+                    ui.label(tr.tr(label));
+                    field_edit(ui, field);
+                });
+            field_error(ui, field, rounding, tr);
+        }
+        for (label, field) in [("deposit-rate", &mut self.rate), ("deposit-tax", &mut self.tax)] {
+            ui.horizontal(
+                |ui| {
+                    ui.label(tr.tr(label));
+                    field_edit(ui, field);
+                });
+            field_error(ui, field, rounding, tr);
+        }
+        ui.horizontal(
+            |ui| {
+                ui.label(tr.tr("deposit-months"));
+                field_edit(ui, &mut self.months);
+                ui.label(tr.tr("deposit-capitalization"));
+                ComboBox::from_id_source("capitalization")
+                    .selected_text(tr.capitalization(self.capitalization))
+                    .show_ui(ui, |ui| {
+                        for capitalization in Capitalization::ALL {
+                            ui.selectable_value(&mut self.capitalization, capitalization, tr.capitalization(capitalization));
+                        }
+                    });
+            });
+        field_error(ui, &mut self.months, rounding, tr);
+        ui.horizontal(
+            |ui| {
+                ui.label(tr.tr("deposit-target"));
+                field_edit(ui, target);
+            });
+        field_error(ui, target, rounding, tr);
+
+        let deposit = match self.deposit() {
+            Ok(deposit) => deposit,
+            Err(error) => {
+                ui.colored_label(Color32::RED, tr.error(&error));
+                return;
+            }
+        };
+        match deposit.months_to_reach(*target.value(), rounding) {
+            Ok(Some(months)) => {
+                let date = deposit.start.checked_add_months(Months::new(months)).unwrap_or(deposit.start);
+                ui.label(tr.tr_args("deposit-target-reached", &[
+                    ("months", months.to_string()),
+                    ("date", date.format("%d.%m.%Y").to_string()),
+                ]));
+            },
+            Ok(None) => {
+                ui.label(tr.tr_args("deposit-target-unreachable", &[("years", (Deposit::SEARCH_LIMIT / 12).to_string())]));
+            },
+            Err(error) => {
+                ui.colored_label(Color32::RED, tr.error(&error));
+            }
+        }
+
+        ui.separator();
+        let projection = match deposit.project(rounding) {
+            Ok(projection) => projection,
+            Err(error) => {
+                ui.colored_label(Color32::RED, tr.error(&error));
+                return;
+            }
+        };
+        let totals = [
+            ("deposit-total-balance", projection.balance().unwrap_or(*self.initial.value())),
+            ("deposit-total-top-ups", projection.total_top_ups),
+            ("deposit-total-interest", projection.total_interest),
+            ("deposit-total-tax", projection.total_tax),
+        ];
+        for (id, amount) in totals {
+            match words(amount) {
+                Ok(amount) => ui.label(tr.tr_args(id, &[("amount", amount)])),
+                Err(error) => ui.colored_label(Color32::RED, tr.error(&error)),
+            };
+        }
+
+        Grid::new("projection")
+            .striped(true)
+            .show(ui, |ui| {
+                for id in ["loan-number", "deposit-date", "deposit-top-up-column", "deposit-interest", "deposit-tax-column", "deposit-balance"] {
+                    ui.label(tr.tr(id));
+                }
+                ui.end_row();
+
+                for period in projection.periods.iter() {
+                    ui.label(period.number.to_string());
+                    ui.label(period.end.format("%d.%m.%Y").to_string());
+                    for amount in [period.top_up, period.interest, period.tax, period.balance] {
+                        ui.label(amount.accounting());
+                    }
+                    ui.end_row();
+                }
+            });
+    }
+}
@@ -0,0 +1,226 @@
+use eframe::egui::{Color32, ComboBox, Grid, TextEdit, Ui};
+use money_counter::{
+    field::Field,
+    money::{self as money, Money, Rounding},
+    i18n::Localizer,
+    invoice::{self, Document, Kind, Template},
+    unit::Unit,
+    vat::{VatMode, VatRate}
+};
+use chrono::{Local, NaiveDate};
+use rust_decimal::Decimal;
+
+use super::{export, field_edit, field_error, Notice, payment::party_ui};
+
+/// One line of an invoice or act
+#[derive(Clone, PartialEq)]
+pub struct InvoiceItemInput {
+    name: String,
+    quantity: Field<Unit>,
+    unit: String,
+    price: Field<Money>
+}
+
+impl InvoiceItemInput {
+    fn new() -> Self {
+        InvoiceItemInput {
+            name: String::new(),
+            quantity: Field::new(Unit(1)),
+            unit: "шт.".to_string(),
+            price: Field::new(Money(Decimal::ZERO))
+        }
+    }
+}
+
+/// Invoice and act mode
+#[derive(Clone, PartialEq)]
+pub struct InvoiceInput {
+    pub kind: Kind,
+    pub number: Field<Unit>,
+    date: Field<NaiveDate>,
+    buyer: String,
+    basis: String,
+    items: Vec<InvoiceItemInput>,
+    vat: VatRate,
+    vat_mode: VatMode,
+    template: Template,
+    template_path: String,
+    pdf_path: String
+}
+
+impl Default for InvoiceInput {
+    fn default() -> Self {
+        InvoiceInput {
+            kind: Kind::Invoice,
+            number: Field::new(Unit(1)),
+            date: Field::new(Local::now().date_naive()),
+            buyer: String::new(),
+            basis: String::new(),
+            items: vec![InvoiceItemInput::new()],
+            vat: VatRate::Vat20,
+            vat_mode: VatMode::Extract,
+            template: Template::default(),
+            template_path: "template.txt".to_string(),
+            pdf_path: "invoice.pdf".to_string()
+        }
+    }
+}
+
+impl InvoiceInput {
+    pub fn document(&self) -> Result<Document, invoice::Error> {
+        Ok(Document {
+            kind: self.kind,
+            number: u32::try_from(self.number.value().0).map_err(|_| money::Error::OutOfRange)?,
+            date: *self.date.value(),
+            buyer: self.buyer.clone(),
+            basis: self.basis.clone(),
+            items: self.items
+                .iter()
+                .map(|item| invoice::Item {
+                    name: item.name.clone(),
+                    quantity: *item.quantity.value(),
+                    unit: item.unit.clone(),
+                    price: *item.price.value()
+                })
+                .collect(),
+            vat: self.vat,
+            vat_mode: self.vat_mode
+        })
+    }
+
+    pub fn ui(&mut self, ui: &mut Ui, rounding: Rounding, tr: &Localizer, notice: &mut Notice) {
+        ui.horizontal(
+            |ui| {
+                for kind in Kind::ALL {
+                    ui.radio_value(&mut self.kind, kind, tr.invoice_kind(kind));
+                }
+                ui.label(tr.tr("payment-number"));
+                field_edit(ui, &mut self.number);
+                ui.label(tr.tr("payment-date"));
+                field_edit(ui, &mut self.date);
+            });
+        field_error(ui, &mut self.number, rounding, tr);
+        field_error(ui, &mut self.date, rounding, tr);
+        Grid::new("invoice-buyer")
+            .num_columns(2)
+            .show(ui, |ui| {
+                for (label, value) in [("invoice-buyer", &mut self.buyer), ("invoice-basis", &mut self.basis)] {
+                    ui.label(tr.tr(label));
+                    ui.add(TextEdit::singleline(value).desired_width(420.0));
+                    ui.end_row();
+                }
+            });
+
+        ui.separator();
+        ui.strong(tr.tr("invoice-items"));
+        let mut removed = None;
+        for (i, item) in self.items.iter_mut().enumerate() {
+            ui.horizontal(
+                |ui| {
+                    ui.label(format!("{}.", i + 1));
+                    ui.label(tr.tr("invoice-item-name"));
+                    ui.add(TextEdit::singleline(&mut item.name).desired_width(240.0));
+                    ui.label(tr.tr("invoice-item-quantity"));
+                    field_edit(ui, &mut item.quantity);
+                    ui.label(tr.tr("invoice-item-unit"));
+                    ui.add(TextEdit::singleline(&mut item.unit).desired_width(40.0));
+                    ui.label(tr.tr("price"));
+                    field_edit(ui, &mut item.price);
+                    if ui.button(tr.tr("remove")).clicked() {
+                        removed = Some(i);
+                    }
+                });
+            field_error(ui, &mut item.quantity, rounding, tr);
+            field_error(ui, &mut item.price, rounding, tr);
+        }
+        if let Some(i) = removed {
+            self.items.remove(i);
+        }
+        if ui.button(tr.tr("invoice-add-item")).clicked() {
+            self.items.push(InvoiceItemInput::new());
+        }
+        ui.horizontal(
+            |ui| {
+                ui.label(tr.tr("vat-rate"));
+                ComboBox::from_id_source("invoice-vat")
+                    .selected_text(tr.vat_rate(self.vat))
+                    .show_ui(ui, |ui| {
+                        for rate in VatRate::PRESETS {
+                            ui.selectable_value(&mut self.vat, rate, tr.vat_rate(rate));
+                        }
+                    });
+                let mut included = self.vat_mode == VatMode::Extract;
+                if ui.checkbox(&mut included, tr.tr("invoice-prices-include-vat")).changed() {
+                    self.vat_mode = if included { VatMode::Extract } else { VatMode::Add };
+                }
+            });
+
+        ui.separator();
+        ui.strong(tr.tr("invoice-seller"));
+        party_ui(ui, "invoice-seller", &mut self.template.seller, tr);
+        Grid::new("invoice-template")
+            .num_columns(2)
+            .show(ui, |ui| {
+                for (label, value) in [
+                    ("invoice-address", &mut self.template.address),
+                    ("invoice-logo", &mut self.template.logo),
+                    ("invoice-font", &mut self.template.font),
+                    ("invoice-director", &mut self.template.director),
+                    ("invoice-accountant", &mut self.template.accountant),
+                    ("invoice-terms", &mut self.template.terms),
+                ] {
+                    ui.label(tr.tr(label));
+                    ui.add(TextEdit::singleline(value).desired_width(320.0));
+                    ui.end_row();
+                }
+            });
+        ui.horizontal(
+            |ui| {
+                ui.label(tr.tr("invoice-template-path"));
+                ui.text_edit_singleline(&mut self.template_path);
+                if ui.button(tr.tr("invoice-template-load")).clicked() {
+                    *notice = Some(match Template::read(&self.template_path) {
+                        Ok(template) => {
+                            self.template = template;
+                            Ok(tr.tr_args("invoice-template-loaded", &[("path", self.template_path.clone())]))
+                        },
+                        Err(error) => Err(tr.error(&error)),
+                    });
+                }
+                if ui.button(tr.tr("invoice-template-save")).clicked() {
+                    *notice = Some(export(&self.template_path, self.template.to_string(), tr));
+                }
+            });
+        ui.separator();
+
+        let document = match self.document() {
+            Ok(document) => document,
+            Err(error) => {
+                ui.colored_label(Color32::RED, tr.error(&error));
+                return;
+            }
+        };
+        let totals = match document.totals() {
+            Ok(totals) => totals,
+            Err(error) => {
+                ui.colored_label(Color32::RED, tr.error(&error));
+                return;
+            }
+        };
+        ui.strong(tr.tr_args("invoice-total", &[("total", totals.gross.accounting()), ("vat", totals.vat.accounting())]));
+        if let Ok(words) = totals.gross.document_words() {
+            ui.label(words);
+        }
+        ui.horizontal(
+            |ui| {
+                ui.label(tr.tr("export-path"));
+                ui.text_edit_singleline(&mut self.pdf_path);
+                if ui.button(tr.tr("invoice-pdf")).clicked() {
+                    *notice = Some(match document.pdf(&self.template) {
+                        Ok(pdf) => export(&self.pdf_path, pdf, tr),
+                        Err(error) => Err(tr.error(&error)),
+                    });
+                }
+            });
+    }
+}
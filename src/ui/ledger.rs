@@ -0,0 +1,261 @@
+use eframe::egui::{Button, Color32, Grid, TextEdit, Ui};
+use money_counter::{
+    budget::{self, Expense},
+    field::{self, Field},
+    money::{self as money, Money, Rounding, Sign},
+    i18n::Localizer,
+    ledger::{self, Filter, Ledger, Transaction},
+    statement
+};
+use chrono::{Local, NaiveDate};
+use rust_decimal::Decimal;
+
+use super::{field_edit, field_error, Session, budget::BudgetInput};
+
+/// Ledger mode: a SQLite file with transactions
+#[derive(Clone, PartialEq)]
+pub struct LedgerInput {
+    pub path: String,
+    from: String,
+    to: String,
+    category: String,
+    counterparty: String,
+    currency: String,
+    new_date: Field<NaiveDate>,
+    new_amount: Field<Money>,
+    new_currency: String,
+    new_category: String,
+    new_counterparty: String,
+    new_note: String,
+    /// 1C, OFX, QIF or camt.053 file to import
+    statement: String,
+    /// For statements that don't name the currency
+    statement_currency: String
+}
+
+impl Default for LedgerInput {
+    fn default() -> Self {
+        LedgerInput {
+            path: "ledger.sqlite".to_string(),
+            from: String::new(),
+            to: String::new(),
+            category: String::new(),
+            counterparty: String::new(),
+            currency: String::new(),
+            new_date: Field::new(Local::now().date_naive()),
+            new_amount: Field::new(Money(Decimal::ZERO)),
+            new_currency: ledger::RUB.to_string(),
+            new_category: String::new(),
+            new_counterparty: String::new(),
+            new_note: String::new(),
+            statement: String::new(),
+            statement_currency: ledger::RUB.to_string()
+        }
+    }
+}
+
+/// Empty text is no limit
+impl LedgerInput {
+    fn filter(&self) -> Result<Filter, money::Error> {
+        Ok(Filter {
+            from: field::optional(&self.from)?,
+            to: field::optional(&self.to)?,
+            category: field::optional_text(&self.category),
+            counterparty: field::optional_text(&self.counterparty),
+            currency: field::optional_text(&self.currency.to_uppercase())
+        })
+    }
+
+    fn search(&self) -> Result<Vec<Transaction>, ledger::Error> {
+        Ledger::open(&self.path)?.query(&self.filter()?)
+    }
+
+    pub fn ui(&mut self, ui: &mut Ui, budget: &mut BudgetInput, rounding: Rounding, words: impl Fn(Money) -> Result<String, money::Error>, tr: &Localizer, session: &mut Session) {
+        ui.horizontal(
+            |ui| {
+                ui.label(tr.tr("ledger-path"));
+                ui.text_edit_singleline(&mut self.path);
+            });
+
+        ui.separator();
+        ui.strong(tr.tr("ledger-new"));
+        ui.horizontal(
+            |ui| {
+                ui.label(tr.tr("budget-date"));
+                field_edit(ui, &mut self.new_date);
+                ui.label(tr.tr("ledger-amount"));
+                field_edit(ui, &mut self.new_amount);
+                ui.label(tr.tr("ledger-currency"));
+                ui.add(TextEdit::singleline(&mut self.new_currency).desired_width(40.0));
+            });
+        field_error(ui, &mut self.new_date, rounding, tr);
+        field_error(ui, &mut self.new_amount, rounding, tr);
+        ui.horizontal(
+            |ui| {
+                ui.label(tr.tr("budget-category"));
+                ui.text_edit_singleline(&mut self.new_category);
+                ui.label(tr.tr("ledger-counterparty"));
+                ui.text_edit_singleline(&mut self.new_counterparty);
+            });
+        ui.horizontal(
+            |ui| {
+                ui.label(tr.tr("budget-note"));
+                ui.text_edit_singleline(&mut self.new_note);
+                let valid = self.new_date.error().is_none() && self.new_amount.error().is_none();
+                if ui.add_enabled(valid, Button::new(tr.tr("add"))).clicked() {
+                    let transaction = Transaction {
+                        id: None,
+                        date: *self.new_date.value(),
+                        amount: *self.new_amount.value(),
+                        currency: self.new_currency.trim().to_uppercase(),
+                        category: self.new_category.trim().to_string(),
+                        counterparty: self.new_counterparty.trim().to_string(),
+                        note: std::mem::take(&mut self.new_note),
+                        reference: String::new()
+                    };
+                    let added = Ledger::open(&self.path).and_then(|ledger| ledger.add(&transaction));
+                    session.notice = Some(match added {
+                        Ok(_) => Ok(tr.tr_args("ledger-added", &[("count", "1".to_string())])),
+                        Err(error) => Err(tr.error(&error)),
+                    });
+                    session.ledger = Some(self.search());
+                }
+            });
+        ui.horizontal(
+            |ui| {
+                if ui.button(tr.tr_args("ledger-from-budget", &[("month", budget.month.format("%m.%Y").to_string())])).clicked() {
+                    let month = budget::month_of(budget.month);
+                    let transactions: Vec<Transaction> = budget.expenses
+                        .iter()
+                        .filter(|expense| budget::month_of(expense.date) == month)
+                        .map(Transaction::from)
+                        .collect();
+                    let added = Ledger::open(&self.path).and_then(|mut ledger| ledger.add_all(&transactions));
+                    session.notice = Some(match added {
+                        Ok(count) => Ok(tr.tr_args("ledger-added", &[("count", count.to_string())])),
+                        Err(error) => Err(tr.error(&error)),
+                    });
+                    session.ledger = Some(self.search());
+                }
+            });
+        ui.horizontal(
+            |ui| {
+                ui.label(tr.tr("ledger-statement"));
+                ui.text_edit_singleline(&mut self.statement);
+                ui.label(tr.tr("ledger-currency"));
+                ui.add(TextEdit::singleline(&mut self.statement_currency).desired_width(40.0));
+                if ui.add_enabled(!self.statement.trim().is_empty(), Button::new(tr.tr("ledger-import"))).clicked() {
+                    let currency = self.statement_currency.trim().to_uppercase();
+                    let imported = Ledger::open(&self.path)
+                        .map_err(|error| tr.error(&error))
+                        .and_then(|mut ledger| import_statement(&mut ledger, self.statement.trim(), &currency, tr));
+                    session.notice = Some(imported);
+                    session.ledger = Some(self.search());
+                }
+            });
+
+        ui.separator();
+        ui.strong(tr.tr("ledger-search"));
+        ui.horizontal(
+            |ui| {
+                ui.label(tr.tr("ledger-from"));
+                ui.text_edit_singleline(&mut self.from);
+                ui.label(tr.tr("ledger-to"));
+                ui.text_edit_singleline(&mut self.to);
+            });
+        ui.horizontal(
+            |ui| {
+                ui.label(tr.tr("budget-category"));
+                ui.text_edit_singleline(&mut self.category);
+                ui.label(tr.tr("ledger-counterparty"));
+                ui.text_edit_singleline(&mut self.counterparty);
+                ui.label(tr.tr("ledger-currency"));
+                ui.add(TextEdit::singleline(&mut self.currency).desired_width(40.0));
+                if ui.button(tr.tr("ledger-find")).clicked() {
+                    session.ledger = Some(self.search());
+                }
+            });
+
+        let transactions = match &session.ledger {
+            None => return,
+            Some(Err(error)) => {
+                ui.colored_label(Color32::RED, tr.error(error));
+                return;
+            },
+            Some(Ok(transactions)) => transactions,
+        };
+
+        let currency = transactions.first().map_or(ledger::RUB, |transaction| transaction.currency.as_str());
+        let total = ledger::sum(transactions);
+        match total {
+            // Words are for rubles only
+            Ok(total) if currency != ledger::RUB => ui.label(tr.tr_args("ledger-total-in", &[
+                ("count", transactions.len().to_string()),
+                ("amount", total.accounting()),
+                ("currency", currency.to_string()),
+            ])),
+            total => match total.and_then(|total| Ok((total, words(total)?))) {
+                Ok((total, pronounced)) => ui.label(tr.tr_args("ledger-total", &[
+                    ("count", transactions.len().to_string()),
+                    ("amount", total.accounting()),
+                    ("words", pronounced),
+                ])),
+                Err(error) => ui.colored_label(Color32::RED, tr.error(&error)),
+            },
+        };
+        if ui.button(tr.tr("ledger-to-budget")).clicked() {
+            let expenses = transactions
+                .iter()
+                .filter(|transaction| transaction.amount.sign() == Sign::Negative && transaction.currency == ledger::RUB)
+                .map(|transaction| Expense {
+                    date: transaction.date,
+                    category: transaction.category.clone(),
+                    amount: transaction.amount.abs(),
+                    note: transaction.note.clone()
+                });
+            for expense in expenses {
+                if !budget.expenses.contains(&expense) {
+                    budget.expenses.push(expense);
+                }
+            }
+        }
+
+        let mut removed = None;
+        Grid::new("ledger")
+            .striped(true)
+            .show(ui, |ui| {
+                for id in ["deposit-date", "ledger-amount-column", "ledger-currency-column", "budget-category-column", "ledger-counterparty-column", "ledger-note-column", "ledger-reference-column"] {
+                    ui.label(tr.tr(id));
+                }
+                ui.end_row();
+
+                for transaction in transactions.iter() {
+                    ui.label(transaction.date.format("%d.%m.%Y").to_string());
+                    ui.label(transaction.amount.accounting());
+                    ui.label(&transaction.currency);
+                    ui.label(&transaction.category);
+                    ui.label(&transaction.counterparty);
+                    ui.label(&transaction.note);
+                    ui.label(&transaction.reference);
+                    if ui.button(tr.tr("remove")).clicked() {
+                        removed = transaction.id;
+                    }
+                    ui.end_row();
+                }
+            });
+        if let Some(id) = removed {
+            if let Err(error) = Ledger::open(&self.path).and_then(|ledger| ledger.remove(id)) {
+                session.notice = Some(Err(tr.error(&error)));
+            }
+            session.ledger = Some(self.search());
+        }
+    }
+}
+
+/// Stores the transactions of a statement that aren't in the ledger yet,
+/// the message lists the sections that were skipped
+pub fn import_statement(ledger: &mut Ledger, path: &str, currency: &str, tr: &Localizer) -> Result<String, String> {
+    let statement = statement::read(path, currency).map_err(|error| tr.error(&error))?;
+    let count = ledger.add_new(&statement.transactions).map_err(|error| tr.error(&error))?;
+    Ok(tr.imported(&statement, count))
+}
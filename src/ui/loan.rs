@@ -0,0 +1,163 @@
+use eframe::egui::{Color32, Grid, Ui};
+use money_counter::{
+    field::Field,
+    money::{self as money, Money, Rounding},
+    i18n::Localizer,
+    loan::{self, Loan},
+    unit::Unit
+};
+use rust_decimal::Decimal;
+
+use super::{export, field_edit, field_error, Notice};
+
+#[derive(Clone, PartialEq)]
+pub struct EarlyInput {
+    month: Field<Unit>,
+    amount: Field<Money>,
+    effect: loan::EarlyEffect
+}
+
+/// Loan calculator mode
+#[derive(Clone, PartialEq)]
+pub struct LoanInput {
+    pub principal: Field<Money>,
+    pub rate: Field<Decimal>,
+    pub months: Field<Unit>,
+    method: loan::Method,
+    early: Vec<EarlyInput>,
+    csv_path: String
+}
+
+impl Default for LoanInput {
+    fn default() -> Self {
+        LoanInput {
+            principal: Field::new(Money(Decimal::ZERO)),
+            rate: Field::new(Decimal::ZERO),
+            months: Field::new(Unit(12)),
+            method: loan::Method::default(),
+            early: Vec::new(),
+            csv_path: "schedule.csv".to_string()
+        }
+    }
+}
+
+impl LoanInput {
+    fn loan(&self) -> Result<Loan, money::Error> {
+        let month = |field: &Field<Unit>| u32::try_from(field.value().0).map_err(|_| money::Error::OutOfRange);
+
+        Ok(Loan {
+            principal: *self.principal.value(),
+            annual_rate: *self.rate.value(),
+            months: month(&self.months)?,
+            method: self.method,
+            early: self.early
+                .iter()
+                .map(|early| Ok(loan::EarlyRepayment {
+                    month: month(&early.month)?,
+                    amount: *early.amount.value(),
+                    effect: early.effect
+                }))
+                .collect::<Result<_, money::Error>>()?
+        })
+    }
+
+    pub fn ui(&mut self, ui: &mut Ui, rounding: Rounding, words: impl Fn(Money) -> Result<String, money::Error>, tr: &Localizer, notice: &mut Notice) {
+        ui.horizontal(
+            |ui| {
+                ui.label(tr.tr("loan-principal"));
+                field_edit(ui, &mut self.principal);
+            });
+        field_error(ui, &mut self.principal, rounding, tr);
+        ui.horizontal(
+            |ui| {
+                ui.label(tr.tr("loan-rate"));
+                field_edit(ui, &mut self.rate);
+            });
+        field_error(ui, &mut self.rate, rounding, tr);
+        ui.horizontal(
+            |ui| {
+                ui.label(tr.tr("loan-months"));
+                field_edit(ui, &mut self.months);
+            });
+        field_error(ui, &mut self.months, rounding, tr);
+        ui.horizontal(
+            |ui| {
+                ui.radio_value(&mut self.method, loan::Method::Annuity, tr.tr("loan-annuity"));
+                ui.radio_value(&mut self.method, loan::Method::Differentiated, tr.tr("loan-differentiated"));
+            });
+
+        ui.collapsing(tr.tr("loan-early"), |ui| {
+            let mut removed = None;
+            for (i, early) in self.early.iter_mut().enumerate() {
+                ui.horizontal(
+                    |ui| {
+                        ui.label(tr.tr("loan-early-month"));
+                        field_edit(ui, &mut early.month);
+                        ui.label(tr.tr("loan-early-amount"));
+                        field_edit(ui, &mut early.amount);
+                        ui.radio_value(&mut early.effect, loan::EarlyEffect::ReduceTerm, tr.tr("loan-reduce-term"));
+                        ui.radio_value(&mut early.effect, loan::EarlyEffect::ReducePayment, tr.tr("loan-reduce-payment"));
+                        if ui.button(tr.tr("remove")).clicked() {
+                            removed = Some(i);
+                        }
+                    });
+                field_error(ui, &mut early.month, rounding, tr);
+                field_error(ui, &mut early.amount, rounding, tr);
+            }
+            if let Some(i) = removed {
+                self.early.remove(i);
+            }
+            if ui.button(tr.tr("loan-add-early")).clicked() {
+                self.early.push(EarlyInput {
+                    month: Field::new(Unit(1)),
+                    amount: Field::new(Money(Decimal::ZERO)),
+                    effect: loan::EarlyEffect::default()
+                });
+            }
+        });
+
+        ui.separator();
+        let schedule = match self.loan().and_then(|loan| loan.schedule(rounding)) {
+            Ok(schedule) => schedule,
+            Err(error) => {
+                ui.colored_label(Color32::RED, tr.error(&error));
+                return;
+            }
+        };
+
+        for (id, amount) in [("loan-total-paid", schedule.total_paid), ("loan-total-interest", schedule.total_interest)] {
+            match words(amount) {
+                Ok(amount) => ui.label(tr.tr_args(id, &[("amount", amount)])),
+                Err(error) => ui.colored_label(Color32::RED, tr.error(&error)),
+            };
+        }
+        ui.horizontal(
+            |ui| {
+                ui.label(tr.tr("export-path"));
+                ui.text_edit_singleline(&mut self.csv_path);
+                if ui.button(tr.tr("export-csv")).clicked() {
+                    *notice = Some(export(&self.csv_path, schedule.csv(
+                        ["loan-number", "loan-payment", "loan-interest", "loan-principal-part", "loan-early-column", "loan-balance"].map(|id| tr.tr(id)),
+                        &tr.tr("loan-total")
+                    ), tr));
+                }
+            });
+
+        Grid::new("schedule")
+            .striped(true)
+            .show(ui, |ui| {
+                for id in ["loan-number", "loan-payment", "loan-interest", "loan-principal-part", "loan-early-column", "loan-balance"] {
+                    ui.label(tr.tr(id));
+                }
+                ui.end_row();
+
+                for payment in schedule.payments.iter() {
+                    ui.label(payment.number.to_string());
+                    for amount in [payment.payment, payment.interest, payment.principal, payment.early, payment.balance] {
+                        ui.label(amount.accounting());
+                    }
+                    ui.end_row();
+                }
+            });
+    }
+}
@@ -0,0 +1,82 @@
+use std::sync::mpsc::Receiver;
+
+use eframe::egui::{Color32, Response, Stroke, TextEdit, Ui};
+use money_counter::{
+    field::{Field, FieldValue},
+    i18n::Localizer,
+    ledger::Transaction,
+    money::{self as money, Money, Rounding, Sign},
+    receipt::{self, Receipt}
+};
+
+pub mod budget;
+pub mod charts;
+pub mod deposit;
+pub mod invoice;
+pub mod ledger;
+pub mod loan;
+pub mod payment;
+pub mod split;
+
+/// Text field that turns red while its text is rejected
+pub fn field_edit<T: FieldValue>(ui: &mut Ui, field: &mut Field<T>) -> Response {
+    let invalid = field.error().is_some();
+
+    ui.scope(|ui| {
+        if invalid {
+            let visuals = ui.visuals_mut();
+            visuals.widgets.inactive.bg_stroke = Stroke::new(1.0, Color32::RED);
+            visuals.widgets.hovered.bg_stroke = Stroke::new(1.0, Color32::RED);
+            visuals.selection.stroke = Stroke::new(1.0, Color32::RED);
+        }
+        ui.add(TextEdit::singleline(field).text_color_opt(invalid.then_some(Color32::RED)))
+    }).inner
+}
+
+/// Why the field's text was rejected, with a quick fix when there is one
+pub fn field_error<T: FieldValue>(ui: &mut Ui, field: &mut Field<T>, rounding: Rounding, tr: &Localizer) {
+    let Some(error) = field.error() else {
+        return;
+    };
+    let message = tr.error(error);
+    let fix = match error {
+        money::Error::KopekDigitsTooBig => tr.tr("fix-kopeks"),
+        _ => tr.tr("fix-round"),
+    };
+
+    ui.horizontal(
+        |ui| {
+            ui.colored_label(Color32::RED, message);
+            if field.can_fix(rounding) && ui.button(fix).clicked() {
+                field.fix(rounding);
+            }
+        });
+}
+
+/// "В остатке: ..." or "Не хватает: ..."
+pub fn leftover_line(leftover: Money, words: impl Fn(Money) -> Result<String, money::Error>, tr: &Localizer) -> Result<String, money::Error> {
+    Ok(match leftover.sign() {
+        Sign::Positive => tr.tr_args("leftover-positive", &[("amount", words(leftover)?)]),
+        Sign::Negative => tr.tr_args("leftover-negative", &[("amount", words(leftover.abs())?)]),
+    })
+}
+
+/// Outcome of the last file operation
+pub type Notice = Option<Result<String, String>>;
+
+/// What is shown but not undone: messages and query results.
+/// Kept out of `Calculator`, so that undo doesn't bring old ones back
+#[derive(Default)]
+pub struct Session {
+    pub notice: Notice,
+    ledger: Option<Result<Vec<Transaction>, money_counter::ledger::Error>>,
+    /// Receipt photo being recognized, decoding a large one takes seconds
+    pub recognition: Option<Receiver<Result<Receipt, receipt::Error>>>
+}
+
+pub fn export(path: &str, contents: impl AsRef<[u8]>, tr: &Localizer) -> Result<String, String> {
+    match std::fs::write(path, contents) {
+        Ok(()) => Ok(tr.tr_args("export-done", &[("path", path.to_string())])),
+        Err(error) => Err(tr.tr_args("export-failed", &[("path", path.to_string()), ("details", error.to_string())])),
+    }
+}
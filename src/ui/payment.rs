@@ -0,0 +1,171 @@
+use eframe::egui::{Button, Color32, ComboBox, DragValue, Grid, TextEdit, Ui};
+use money_counter::{
+    field::Field,
+    money::{self as money, Money, Rounding},
+    payment::{self, Party, PaymentOrder},
+    i18n::Localizer,
+    unit::Unit,
+    vat::VatRate
+};
+use chrono::{Local, NaiveDate};
+use rust_decimal::Decimal;
+
+use super::{export, field_edit, field_error, Notice};
+
+/// Payment order mode
+#[derive(Clone, PartialEq)]
+pub struct PaymentInput {
+    pub number: Field<Unit>,
+    date: Field<NaiveDate>,
+    pub amount: Field<Money>,
+    payer: Party,
+    recipient: Party,
+    purpose: String,
+    vat: VatRate,
+    priority: u8,
+    exchange_path: String,
+    html_path: String
+}
+
+impl Default for PaymentInput {
+    fn default() -> Self {
+        PaymentInput {
+            number: Field::new(Unit(1)),
+            date: Field::new(Local::now().date_naive()),
+            amount: Field::new(Money(Decimal::ZERO)),
+            payer: Party::default(),
+            recipient: Party::default(),
+            purpose: String::new(),
+            vat: VatRate::Vat20,
+            priority: 5,
+            exchange_path: "payment.txt".to_string(),
+            html_path: "payment.html".to_string()
+        }
+    }
+}
+
+/// Requisites of one side with what is wrong with them
+pub fn party_ui(ui: &mut Ui, id: &str, party: &mut Party, tr: &Localizer) {
+    Grid::new(id)
+        .num_columns(2)
+        .show(ui, |ui| {
+            for (label, value) in [
+                ("payment-name", &mut party.name),
+                ("payment-inn", &mut party.inn),
+                ("payment-kpp", &mut party.kpp),
+                ("payment-account", &mut party.account),
+                ("payment-bank", &mut party.bank),
+                ("payment-bik", &mut party.bik),
+                ("payment-correspondent", &mut party.correspondent),
+            ] {
+                ui.label(tr.tr(label));
+                ui.add(TextEdit::singleline(value).desired_width(320.0));
+                ui.end_row();
+            }
+        });
+    for problem in party.problems() {
+        ui.colored_label(Color32::RED, tr.error(&problem));
+    }
+}
+
+impl PaymentInput {
+    fn order(&self) -> Result<PaymentOrder, money::Error> {
+        Ok(PaymentOrder {
+            number: u32::try_from(self.number.value().0).map_err(|_| money::Error::OutOfRange)?,
+            date: *self.date.value(),
+            amount: *self.amount.value(),
+            payer: self.payer.clone(),
+            recipient: self.recipient.clone(),
+            purpose: self.purpose.clone(),
+            vat: self.vat,
+            priority: self.priority
+        })
+    }
+
+    pub fn ui(&mut self, ui: &mut Ui, rounding: Rounding, tr: &Localizer, notice: &mut Notice) {
+        ui.horizontal(
+            |ui| {
+                ui.label(tr.tr("payment-number"));
+                field_edit(ui, &mut self.number);
+                ui.label(tr.tr("payment-date"));
+                field_edit(ui, &mut self.date);
+                ui.label(tr.tr("payment-amount"));
+                field_edit(ui, &mut self.amount);
+            });
+        field_error(ui, &mut self.number, rounding, tr);
+        field_error(ui, &mut self.date, rounding, tr);
+        field_error(ui, &mut self.amount, rounding, tr);
+
+        if let Ok(words) = self.amount.value().document_words() {
+            ui.horizontal(
+                |ui| {
+                    ui.label(tr.tr_args("payment-words", &[("words", words.clone())]));
+                    if ui.button(tr.tr("payment-copy")).clicked() {
+                        ui.output_mut(|output| output.copied_text = words);
+                    }
+                });
+        }
+
+        ui.separator();
+        ui.strong(tr.tr("payment-payer"));
+        party_ui(ui, "payer", &mut self.payer, tr);
+        ui.separator();
+        ui.strong(tr.tr("payment-recipient"));
+        party_ui(ui, "recipient", &mut self.recipient, tr);
+        ui.separator();
+
+        ui.label(tr.tr("payment-purpose"));
+        ui.add(TextEdit::multiline(&mut self.purpose).desired_rows(2).desired_width(f32::INFINITY));
+        ui.horizontal(
+            |ui| {
+                ui.label(tr.tr("vat-rate"));
+                ComboBox::from_id_source("payment-vat")
+                    .selected_text(tr.vat_rate(self.vat))
+                    .show_ui(ui, |ui| {
+                        for rate in VatRate::PRESETS {
+                            ui.selectable_value(&mut self.vat, rate, tr.vat_rate(rate));
+                        }
+                    });
+                ui.label(tr.tr("payment-priority"));
+                ui.add(DragValue::new(&mut self.priority).range(1..=5));
+            });
+
+        let order = match self.order() {
+            Ok(order) => order,
+            Err(error) => {
+                ui.colored_label(Color32::RED, tr.error(&error));
+                return;
+            }
+        };
+        for problem in order.problems() {
+            ui.colored_label(Color32::RED, tr.error(&problem));
+        }
+        if let Ok(purpose) = order.full_purpose() {
+            ui.label(purpose);
+        }
+
+        let valid = order.check().is_ok();
+        ui.horizontal(
+            |ui| {
+                ui.label(tr.tr("export-path"));
+                ui.text_edit_singleline(&mut self.exchange_path);
+                if ui.add_enabled(valid, Button::new(tr.tr("payment-exchange"))).clicked() {
+                    *notice = Some(match payment::exchange(std::slice::from_ref(&order), Local::now().naive_local()) {
+                        Ok(text) => export(&self.exchange_path, payment::encode(&text), tr),
+                        Err(error) => Err(tr.error(&error)),
+                    });
+                }
+            });
+        ui.horizontal(
+            |ui| {
+                ui.label(tr.tr("export-path"));
+                ui.text_edit_singleline(&mut self.html_path);
+                if ui.add_enabled(valid, Button::new(tr.tr("payment-html"))).clicked() {
+                    *notice = Some(match order.html() {
+                        Ok(html) => export(&self.html_path, html, tr),
+                        Err(error) => Err(tr.error(&error)),
+                    });
+                }
+            });
+    }
+}
@@ -0,0 +1,271 @@
+use eframe::egui::{Color32, Grid, Ui};
+use money_counter::{
+    field::Field,
+    money::{self as money, Money, Rounding},
+    i18n::Localizer,
+    split
+};
+use rust_decimal::Decimal;
+
+use super::{field_edit, field_error};
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum SplitMethod {
+    Equal,
+    Weights,
+    Items
+}
+
+#[derive(Clone, PartialEq)]
+pub struct ParticipantInput {
+    name: String,
+    weight: Field<Decimal>,
+    paid: Field<Money>
+}
+
+impl ParticipantInput {
+    fn new() -> Self {
+        ParticipantInput {
+            name: String::new(),
+            weight: Field::new(Decimal::ONE),
+            paid: Field::new(Money(Decimal::ZERO))
+        }
+    }
+}
+
+#[derive(Clone, PartialEq)]
+pub struct ItemInput {
+    name: String,
+    price: Field<Money>,
+    /// One flag per participant
+    consumers: Vec<bool>
+}
+
+/// Split-the-bill mode
+#[derive(Clone, PartialEq)]
+pub struct SplitInput {
+    method: SplitMethod,
+    pub total: Field<Money>,
+    tip: Field<Decimal>,
+    pub participants: Vec<ParticipantInput>,
+    items: Vec<ItemInput>
+}
+
+impl Default for SplitInput {
+    fn default() -> Self {
+        SplitInput {
+            method: SplitMethod::Equal,
+            total: Field::new(Money(Decimal::ZERO)),
+            tip: Field::new(Decimal::ZERO),
+            participants: vec![ParticipantInput::new(), ParticipantInput::new()],
+            items: Vec::new()
+        }
+    }
+}
+
+impl SplitInput {
+    fn name(&self, index: usize, tr: &Localizer) -> String {
+        match self.participants[index].name.trim() {
+            "" => tr.tr_args("split-participant-numbered", &[("number", (index + 1).to_string())]),
+            name => name.to_string()
+        }
+    }
+
+    fn items(&self) -> Vec<split::Item> {
+        self.items
+            .iter()
+            .map(|item| split::Item {
+                price: *item.price.value(),
+                consumers: item.consumers
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(i, consumed)| consumed.then_some(i))
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Shares with the tip, in the order of participants
+    pub fn shares(&self, rounding: Rounding) -> Result<Vec<Money>, money::Error> {
+        let count = self.participants.len();
+        let shares = match self.method {
+            SplitMethod::Equal => split::equal(*self.total.value(), count)?,
+            SplitMethod::Weights => {
+                let weights: Vec<Decimal> = self.participants.iter().map(|participant| *participant.weight.value()).collect();
+                split::by_weight(*self.total.value(), &weights)?
+            },
+            SplitMethod::Items => split::by_items(&self.items(), count)?,
+        };
+
+        split::with_tip(&shares, *self.tip.value(), rounding)
+    }
+
+    pub fn ui(&mut self, ui: &mut Ui, rounding: Rounding, words: impl Fn(Money) -> Result<String, money::Error>, tr: &Localizer) {
+        ui.horizontal(
+            |ui| {
+                ui.radio_value(&mut self.method, SplitMethod::Equal, tr.tr("split-equal"));
+                ui.radio_value(&mut self.method, SplitMethod::Weights, tr.tr("split-weights"));
+                ui.radio_value(&mut self.method, SplitMethod::Items, tr.tr("split-items"));
+            });
+        if self.method != SplitMethod::Items {
+            ui.horizontal(
+                |ui| {
+                    ui.label(tr.tr("split-total"));
+                    field_edit(ui, &mut self.total);
+                });
+            field_error(ui, &mut self.total, rounding, tr);
+        }
+        ui.horizontal(
+            |ui| {
+                ui.label(tr.tr("split-tip"));
+                field_edit(ui, &mut self.tip);
+            });
+        field_error(ui, &mut self.tip, rounding, tr);
+
+        ui.separator();
+        ui.strong(tr.tr("split-participants"));
+        let mut removed = None;
+        for (i, participant) in self.participants.iter_mut().enumerate() {
+            ui.horizontal(
+                |ui| {
+                    ui.label(format!("{}.", i + 1));
+                    ui.text_edit_singleline(&mut participant.name);
+                    if self.method == SplitMethod::Weights {
+                        ui.label(tr.tr("split-weight"));
+                        field_edit(ui, &mut participant.weight);
+                    }
+                    ui.label(tr.tr("split-paid"));
+                    field_edit(ui, &mut participant.paid);
+                    if ui.button(tr.tr("remove")).clicked() {
+                        removed = Some(i);
+                    }
+                });
+            if self.method == SplitMethod::Weights {
+                field_error(ui, &mut participant.weight, rounding, tr);
+            }
+            field_error(ui, &mut participant.paid, rounding, tr);
+        }
+        if let Some(i) = removed {
+            self.participants.remove(i);
+            for item in self.items.iter_mut() {
+                if i < item.consumers.len() {
+                    item.consumers.remove(i);
+                }
+            }
+        }
+        if ui.button(tr.tr("split-add-participant")).clicked() {
+            self.participants.push(ParticipantInput::new());
+        }
+
+        if self.method == SplitMethod::Items {
+            self.items_ui(ui, rounding, tr);
+        }
+
+        ui.separator();
+        let shares = match self.shares(rounding) {
+            Ok(shares) => shares,
+            Err(error) => {
+                ui.colored_label(Color32::RED, tr.error(&error));
+                return;
+            }
+        };
+        let paid: Vec<Money> = self.participants.iter().map(|participant| *participant.paid.value()).collect();
+        self.shares_ui(ui, &shares, &paid, &words, tr);
+    }
+
+    fn items_ui(&mut self, ui: &mut Ui, rounding: Rounding, tr: &Localizer) {
+        ui.separator();
+        ui.strong(tr.tr("split-receipt"));
+        let names: Vec<String> = (0..self.participants.len()).map(|i| self.name(i, tr)).collect();
+        let mut removed = None;
+        for (i, item) in self.items.iter_mut().enumerate() {
+            item.consumers.resize(names.len(), false);
+            ui.horizontal(
+                |ui| {
+                    ui.label(format!("{}.", i + 1));
+                    ui.text_edit_singleline(&mut item.name);
+                    ui.label(tr.tr("price"));
+                    field_edit(ui, &mut item.price);
+                    if ui.button(tr.tr("remove")).clicked() {
+                        removed = Some(i);
+                    }
+                });
+            field_error(ui, &mut item.price, rounding, tr);
+            ui.horizontal_wrapped(
+                |ui| {
+                    for (name, consumed) in names.iter().zip(item.consumers.iter_mut()) {
+                        ui.checkbox(consumed, name);
+                    }
+                });
+        }
+        if let Some(i) = removed {
+            self.items.remove(i);
+        }
+        if ui.button(tr.tr("split-add-item")).clicked() {
+            self.items.push(ItemInput {
+                name: tr.tr_args("split-item-numbered", &[("number", (self.items.len() + 1).to_string())]),
+                price: Field::new(Money(Decimal::ZERO)),
+                consumers: vec![true; names.len()]
+            });
+        }
+
+        let total = self.items
+            .iter()
+            .try_fold(Money(Decimal::ZERO), |sum, item| sum.checked_add(*item.price.value()));
+        if let Ok(total) = total {
+            ui.label(tr.tr_args("split-items-total", &[("total", total.accounting())]));
+        }
+    }
+
+    fn shares_ui(&self, ui: &mut Ui, shares: &[Money], paid: &[Money], words: &impl Fn(Money) -> Result<String, money::Error>, tr: &Localizer) {
+        Grid::new("shares")
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label(tr.tr("split-participant"));
+                ui.label(tr.tr("split-share"));
+                ui.label(tr.tr("split-paid-column"));
+                ui.label(tr.tr("split-balance"));
+                ui.end_row();
+
+                for (i, (share, paid)) in shares.iter().zip(paid).enumerate() {
+                    ui.label(self.name(i, tr));
+                    ui.label(share.accounting());
+                    ui.label(paid.accounting());
+                    match paid.checked_sub(*share) {
+                        Ok(balance) => ui.label(balance.accounting()),
+                        Err(error) => ui.colored_label(Color32::RED, tr.error(&error)),
+                    };
+                    ui.end_row();
+                }
+            });
+
+        let total = shares.iter().try_fold(Money(Decimal::ZERO), |sum, share| sum.checked_add(*share));
+        match total.and_then(words) {
+            Ok(total) => ui.label(tr.tr_args("split-grand-total", &[("total", total)])),
+            Err(error) => ui.colored_label(Color32::RED, tr.error(&error)),
+        };
+
+        ui.strong(tr.tr("split-settlement"));
+        if paid.iter().all(|paid| paid.0.is_zero()) {
+            ui.label(tr.tr("split-enter-paid"));
+            return;
+        }
+        match split::settle(shares, paid) {
+            Ok(transfers) if transfers.is_empty() => {
+                ui.label(tr.tr("split-settled"));
+            },
+            Ok(transfers) => {
+                for transfer in transfers {
+                    ui.label(tr.tr_args("split-transfer", &[
+                        ("from", self.name(transfer.from, tr)),
+                        ("to", self.name(transfer.to, tr)),
+                        ("amount", transfer.amount.accounting()),
+                    ]));
+                }
+            },
+            Err(error) => {
+                ui.colored_label(Color32::RED, tr.error(&error));
+            }
+        }
+    }
+}
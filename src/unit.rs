@@ -1,8 +1,6 @@
-use eframe::egui::TextBuffer;
-use rust_decimal::Decimal;
-use std::str::FromStr;
+use rust_decimal::{prelude::FromPrimitive, Decimal};
 
-use crate::pronounce::{NumberPronouce, Triplet};
+use crate::{measure::{Gender, MeasureUnit, Measured}, money::Error, pronounce::{NumberPronouce, Triplet}};
 
 
 #[derive(
@@ -12,15 +10,16 @@ use crate::pronounce::{NumberPronouce, Triplet};
 )]
 pub struct Unit(pub u128);
 
-impl NumberPronouce for Unit {
-    fn into_triplets(& self) -> Vec<crate::pronounce::Triplet> {
+impl Unit {
+    /// Triplets where the lowest one agrees with the noun's gender
+    pub fn triplets_with_gender(&self, gender: Gender) -> Vec<Triplet> {
         let mut triplets: Vec<Triplet> = Vec::new();
 
         let mut units_str = self.0.to_string();
 
         match units_str.chars().count() % 3 {
             1 => units_str.insert_str(0,"00"),
-            2 => units_str.insert_str(0,"0"),
+            2 => units_str.insert(0, '0'),
             _ => ()
         }
 
@@ -45,7 +44,7 @@ impl NumberPronouce for Unit {
             )
         };
 
-        if let Some(triplet) = triplets.last_mut() {
+        if let (Some(triplet), Gender::Feminine) = (triplets.first_mut(), gender) {
             *triplet = triplet.into_feminie()
         }
         
@@ -54,94 +53,63 @@ impl NumberPronouce for Unit {
     }
 }
 
-impl std::ops::Add for Unit {
-    type Output = Self;
-
-    fn add(self, rhs: Self) -> Self::Output {
-        Self(self.0 + rhs.0)
+impl NumberPronouce for Unit {
+    fn into_triplets(& self) -> Vec<crate::pronounce::Triplet> {
+        self.triplets_with_gender(MeasureUnit::ITEM.gender)
     }
 }
 
-impl std::ops::Sub for Unit {
-    type Output = Self;
-
-    fn sub(self, rhs: Self) -> Self::Output {
-        Self(self.0 - rhs.0)
+impl Unit {
+    pub fn checked_add(self, rhs: Unit) -> Result<Self, Error> {
+        self.0.checked_add(rhs.0).map(Unit).ok_or(Error::Overflow)
     }
-}
-
-impl std::ops::Mul for Unit {
-    type Output = Self;
 
-    fn mul(self, rhs: Self) -> Self::Output {
-        Self(self.0 * rhs.0)
+    /// Going below zero is an overflow as well: `Unit` has no sign
+    pub fn checked_sub(self, rhs: Unit) -> Result<Self, Error> {
+        self.0.checked_sub(rhs.0).map(Unit).ok_or(Error::Overflow)
     }
-}
 
-impl std::ops::Div for Unit {
-    type Output = Self;
+    pub fn checked_mul(self, rhs: Unit) -> Result<Self, Error> {
+        self.0.checked_mul(rhs.0).map(Unit).ok_or(Error::Overflow)
+    }
 
-    fn div(self, rhs: Self) -> Self::Output {
-        Self(self.0 / rhs.0)
+    pub fn checked_div(self, rhs: Unit) -> Result<Self, Error> {
+        self.0.checked_div(rhs.0).map(Unit).ok_or(Error::DivisionByZero)
     }
 }
 
 impl std::fmt::Display for Unit {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let triplets = self.into_triplets();
-        let mut unit_pronounce: String = "".to_string();
-        if let Some(pronounce) = triplets.last() {
-            unit_pronounce.push_str(pronounce.triplet_pronounce(["единица","единицы","единиц"]));
-        }
-        write!(f,"{} {}",self.pronounce(),unit_pronounce)
+        write!(f,"{}",Measured(*self, &MeasureUnit::ITEM))
     }
 }
 
-impl From<&Unit> for Decimal {
-    fn from(value: &Unit) -> Self {
-        Decimal::from_parts(
-            (value.0 & 0xFFFFFFFF) as u32,
-            ((value.0 >> 32) & 0xFFFFFFFF) as u32,
-            ((value.0 >> 64) & 0xFFFFFFFF) as u32,
-            false,
-        0)
-    }
-}
+/// Decimal holds 96 bits, a `u128` may not fit
+impl TryFrom<&Unit> for Decimal {
+    type Error = Error;
 
-impl TextBuffer for Unit {
-    fn is_mutable(&self) -> bool {
-        true
+    fn try_from(value: &Unit) -> Result<Self, Error> {
+        Decimal::from_u128(value.0).ok_or(Error::OutOfRange)
     }
+}
 
-    fn as_str(&self) -> &str {
-        let formatted = format!("{}",self.0);
-        Box::leak(formatted.into_boxed_str())
-    }
-
-    fn insert_text(&mut self, text: &str, char_index: usize) -> usize {
-        let current_string = self.0.to_string();
-        let mut new_string = String::new();
-
-        new_string.push_str(&current_string[..char_index]);
-        new_string.push_str(text);
-        new_string.push_str(&current_string[char_index..]);
-
-        match u128::from_str(&new_string) {
-            Ok(v) => {
-                self.0 = v;
-                new_string.len() - current_string.len()
-            },
-            Err(_) => 0,
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checked_arithmetic() {
+        assert_eq!(Unit(u128::MAX).checked_add(Unit(1)), Err(Error::Overflow));
+        assert_eq!(Unit(0).checked_sub(Unit(1)), Err(Error::Overflow));
+        assert_eq!(Unit(u128::MAX).checked_mul(Unit(2)), Err(Error::Overflow));
+        assert_eq!(Unit(10).checked_div(Unit(0)), Err(Error::DivisionByZero));
+        assert_eq!(Unit(10).checked_div(Unit(3)), Ok(Unit(3)));
     }
 
-    fn delete_char_range(&mut self, char_range: std::ops::Range<usize>) {
-        let mut original_str = self.0.to_string();
-        original_str.delete_char_range(char_range);
-
-        match u128::from_str(&original_str) {
-            Ok(v) => self.0 = v,
-            Err(_) => (),
-        }
+    #[test]
+    fn decimal_keeps_every_bit_or_refuses() {
+        let max = (1u128 << 96) - 1;
+        assert_eq!(Decimal::try_from(&Unit(max)), Ok(Decimal::MAX));
+        assert_eq!(Decimal::try_from(&Unit(1 << 96)), Err(Error::OutOfRange));
     }
 }
@@ -0,0 +1,118 @@
+use rust_decimal::Decimal;
+
+use crate::money::{Error, Money, Rounding};
+
+#[derive(Debug,
+    PartialEq, Eq,
+    Clone, Copy)]
+pub enum VatRate {
+    Vat20,
+    Vat10,
+    Vat0,
+    /// "без НДС" - the document carries no VAT at all, unlike 0%
+    WithoutVat,
+    /// Percent, e.g. `Decimal::from(18)` for 18%
+    Custom(Decimal)
+}
+
+impl VatRate {
+    pub const PRESETS: [VatRate;4] = [VatRate::Vat20, VatRate::Vat10, VatRate::Vat0, VatRate::WithoutVat];
+
+    /// Rate in percent, `None` for "без НДС"
+    pub fn percent(&self) -> Option<Decimal> {
+        match self {
+            VatRate::Vat20 => Some(Decimal::from(20)),
+            VatRate::Vat10 => Some(Decimal::from(10)),
+            VatRate::Vat0 => Some(Decimal::ZERO),
+            VatRate::WithoutVat => None,
+            VatRate::Custom(percent) => Some(*percent),
+        }
+    }
+}
+
+#[derive(Debug,
+    PartialEq, Eq,
+    Clone, Copy)]
+pub enum VatMode {
+    /// Amount already includes VAT, VAT is extracted from it
+    Extract,
+    /// Amount is net, VAT is charged on top of it
+    Add
+}
+
+#[derive(Debug,
+    PartialEq, Eq,
+    Clone, Copy)]
+pub enum VatRounding {
+    /// VAT is rounded to kopeks for every line and then summed
+    PerLine,
+    /// VAT is calculated once from the document total
+    PerDocument
+}
+
+#[derive(Debug,
+    PartialEq, Eq,
+    Clone, Copy)]
+pub struct Vat {
+    pub rate: VatRate,
+    pub mode: VatMode,
+    pub net: Money,
+    pub vat: Money,
+    pub gross: Money
+}
+
+fn vat_of(amount: Money, rate: VatRate, mode: VatMode) -> Result<Money, Error> {
+    let Some(percent) = rate.percent() else {
+        return Ok(Money(Decimal::ZERO));
+    };
+
+    let divisor = match mode {
+        VatMode::Extract => Decimal::ONE_HUNDRED.checked_add(percent).ok_or(Error::Overflow)?,
+        VatMode::Add => Decimal::ONE_HUNDRED,
+    };
+
+    Ok(Money::round(amount.checked_mul(percent)?.checked_div(divisor)?.0, Rounding::HalfUp))
+}
+
+impl Vat {
+    pub fn calculate(amount: Money, rate: VatRate, mode: VatMode) -> Result<Self, Error> {
+        Vat::from_vat(amount, vat_of(amount, rate, mode)?, rate, mode)
+    }
+
+    pub fn calculate_document(lines: &[Money], rate: VatRate, mode: VatMode, rounding: VatRounding) -> Result<Self, Error> {
+        let total = lines
+            .iter()
+            .try_fold(Money(Decimal::ZERO), |acc, line| acc.checked_add(*line))?;
+
+        let vat = match rounding {
+            VatRounding::PerLine => lines
+                .iter()
+                .try_fold(Money(Decimal::ZERO), |acc, line| acc.checked_add(vat_of(*line, rate, mode)?))?,
+            VatRounding::PerDocument => vat_of(total, rate, mode)?,
+        };
+
+        Vat::from_vat(total, vat, rate, mode)
+    }
+
+    fn from_vat(amount: Money, vat: Money, rate: VatRate, mode: VatMode) -> Result<Self, Error> {
+        match mode {
+            VatMode::Extract => Ok(Vat { rate, mode, net: amount.checked_sub(vat)?, vat, gross: amount }),
+            VatMode::Add => Ok(Vat { rate, mode, net: amount, vat, gross: amount.checked_add(vat)? }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_mode() {
+        let amount = Money(Decimal::from(120));
+        let extracted = Vat::calculate(amount, VatRate::Vat20, VatMode::Extract).unwrap();
+        assert_eq!((extracted.mode, extracted.vat, extracted.gross), (VatMode::Extract, Money(Decimal::from(20)), amount));
+
+        let added = Vat::calculate(amount, VatRate::Vat20, VatMode::Add).unwrap();
+        assert_eq!((added.mode, added.vat, added.gross), (VatMode::Add, Money(Decimal::from(24)), Money(Decimal::from(144))));
+    }
+}